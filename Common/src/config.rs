@@ -0,0 +1,34 @@
+use std::ffi::OsString;
+use std::path::PathBuf;
+
+/// Overrides the config path, if the argument is not given.
+pub const PATH_VARIABLE: &str = "KPI_CONFIG";
+
+/// Path given by `--config <path>`, otherwise by the `KPI_CONFIG` variable,
+/// whose value is `variable`. `None` means the default path.
+pub fn path_override(
+    argument: Option<PathBuf>, variable: Option<OsString>,
+) -> Option<PathBuf> {
+    argument.or_else(|| {
+        variable
+            .filter(|value| !value.is_empty())
+            .map(PathBuf::from)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_path_override() {
+        let variable = Some(OsString::from("env.toml"));
+
+        let path = path_override(Some(PathBuf::from("a.toml")), variable.clone());
+        assert_eq!(path, Some(PathBuf::from("a.toml")));
+        let path = path_override(None, variable);
+        assert_eq!(path, Some(PathBuf::from("env.toml")));
+        let path = path_override(None, Some(OsString::new()));
+        assert_eq!(path, None);
+    }
+}
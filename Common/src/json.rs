@@ -0,0 +1,288 @@
+use std::ops::Range;
+
+/// Quoted string with JSON escapes.
+pub fn json_string(text: &str) -> String {
+    let mut result = String::with_capacity(text.len() + 2);
+    result.push('"');
+    for ch in text.chars() {
+        match ch {
+            '"' => result.push_str("\\\""),
+            '\\' => result.push_str("\\\\"),
+            '\n' => result.push_str("\\n"),
+            '\r' => result.push_str("\\r"),
+            '\t' => result.push_str("\\t"),
+            ch if ch.is_control() => result.push_str(&format!("\\u{:04x}", ch as u32)),
+            ch => result.push(ch),
+        }
+    }
+    result.push('"');
+    result
+}
+
+/// Items, which are JSON already, as an array.
+pub fn json_array(items: impl IntoIterator<Item = String>) -> String {
+    format!("[{}]", items.into_iter().collect::<Vec<String>>().join(","))
+}
+
+/// Object of a syntax error of the CLIs: `kind`, `message`, `start` and `end`,
+/// and the `value` of the token if it has one.
+pub fn syntax_error_json(
+    kind: &str, message: &str, position: &Range<usize>, value: Option<&str>,
+) -> String {
+    json_object(syntax_error_fields(kind, message, position, value))
+}
+
+/// Fields of `syntax_error_json`, for the labs which add their own ones.
+pub fn syntax_error_fields<'a>(
+    kind: &str, message: &str, position: &Range<usize>, value: Option<&str>,
+) -> Vec<(&'a str, String)> {
+    let mut fields = vec![
+        ("kind", json_string(kind)),
+        ("message", json_string(message)),
+        ("start", position.start.to_string()),
+        ("end", position.end.to_string()),
+    ];
+    if let Some(value) = value {
+        fields.push(("value", json_string(value)));
+    }
+    fields
+}
+
+/// Object of the fields, whose values are JSON already.
+pub fn json_object<'a>(fields: impl IntoIterator<Item = (&'a str, String)>) -> String {
+    let fields = fields
+        .into_iter()
+        .map(|(name, value)| format!("{}:{}", json_string(name), value))
+        .collect::<Vec<String>>();
+    format!("{{{}}}", fields.join(","))
+}
+
+/// Fields of a flat JSON object, whose values are strings or `null`.
+pub fn parse_string_object(
+    text: &str,
+) -> Result<Vec<(String, Option<String>)>, JsonError> {
+    let mut reader = Reader {
+        chars: text.char_indices().peekable(),
+        length: text.len(),
+    };
+    let mut fields = Vec::new();
+
+    reader.expect('{')?;
+    if reader.peek() == Some('}') {
+        reader.next();
+    } else {
+        loop {
+            let name = reader.string()?;
+            reader.expect(':')?;
+            let value = match reader.peek() {
+                Some('n') => {
+                    for expected in "null".chars() {
+                        reader.expect_exact(expected)?;
+                    }
+                    None
+                },
+                _ => Some(reader.string()?),
+            };
+            fields.push((name, value));
+
+            match reader.next() {
+                Some((_, ',')) => continue,
+                Some((_, '}')) => break,
+                _ => return Err(reader.error("Expected ',' or '}'")),
+            }
+        }
+    }
+
+    match reader.peek() {
+        None => Ok(fields),
+        Some(_) => Err(reader.error("Unexpected text after the object")),
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct JsonError {
+    pub position: usize,
+    pub message: &'static str,
+}
+
+impl std::fmt::Display for JsonError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} at {}.", self.message, self.position)
+    }
+}
+
+impl std::error::Error for JsonError {}
+
+struct Reader<'a> {
+    chars: std::iter::Peekable<std::str::CharIndices<'a>>,
+    length: usize,
+}
+
+impl Reader<'_> {
+    // Next char after the whitespace
+    fn peek(&mut self) -> Option<char> {
+        while self.chars.next_if(|(_, ch)| ch.is_whitespace()).is_some() {}
+        self.chars.peek().map(|(_, ch)| *ch)
+    }
+
+    fn next(&mut self) -> Option<(usize, char)> {
+        self.peek();
+        self.chars.next()
+    }
+
+    fn expect(&mut self, expected: char) -> Result<(), JsonError> {
+        self.peek();
+        self.expect_exact(expected)
+    }
+
+    // Whitespace is not skipped
+    fn expect_exact(&mut self, expected: char) -> Result<(), JsonError> {
+        match self.chars.next_if(|(_, ch)| *ch == expected) {
+            Some(_) => Ok(()),
+            None => Err(self.error("Unexpected character")),
+        }
+    }
+
+    fn string(&mut self) -> Result<String, JsonError> {
+        self.expect('"')?;
+        let mut result = String::new();
+        loop {
+            let Some((_, ch)) = self.chars.next() else {
+                return Err(self.error("Unterminated string"));
+            };
+            match ch {
+                '"' => return Ok(result),
+                '\\' => result.push(self.escape()?),
+                ch if ch.is_control() => {
+                    return Err(self.error("Unescaped control character"));
+                },
+                ch => result.push(ch),
+            }
+        }
+    }
+
+    fn escape(&mut self) -> Result<char, JsonError> {
+        let escaped = match self.chars.next() {
+            Some((_, '"')) => '"',
+            Some((_, '\\')) => '\\',
+            Some((_, '/')) => '/',
+            Some((_, 'b')) => '\u{8}',
+            Some((_, 'f')) => '\u{c}',
+            Some((_, 'n')) => '\n',
+            Some((_, 'r')) => '\r',
+            Some((_, 't')) => '\t',
+            Some((_, 'u')) => {
+                let code = self.code_unit()?;
+                // Chars out of the BMP are written as a surrogate pair
+                let code = if (0xD800..0xDC00).contains(&code) {
+                    self.expect_exact('\\')?;
+                    self.expect_exact('u')?;
+                    let low = self.code_unit()?;
+                    if !(0xDC00..0xE000).contains(&low) {
+                        return Err(self.error("Invalid surrogate pair"));
+                    }
+                    0x10000 + ((code - 0xD800) << 10) + (low - 0xDC00)
+                } else {
+                    code
+                };
+                return char::from_u32(code).ok_or(self.error("Invalid unicode escape"));
+            },
+            _ => return Err(self.error("Invalid escape")),
+        };
+        Ok(escaped)
+    }
+
+    fn code_unit(&mut self) -> Result<u32, JsonError> {
+        let mut code = 0;
+        for _ in 0..4 {
+            let digit = self.chars.next().and_then(|(_, ch)| ch.to_digit(16));
+            let Some(digit) = digit else {
+                return Err(self.error("Invalid unicode escape"));
+            };
+            code = code * 16 + digit;
+        }
+        Ok(code)
+    }
+
+    fn error(&mut self, message: &'static str) -> JsonError {
+        let position = self.chars.peek().map_or(self.length, |(index, _)| *index);
+        JsonError { position, message }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_json_string() {
+        assert_eq!(json_string("a + b"), "\"a + b\"");
+        assert_eq!(
+            json_string("\"a\"\\b\n\tc\u{1}"),
+            r#""\"a\"\\b\n\tc\u0001""#
+        );
+    }
+
+    #[test]
+    fn test_syntax_error_json() {
+        let errors = [
+            syntax_error_json("UnknownToken", "Unknown token.", &(2..3), Some("$")),
+            syntax_error_json("UnmatchedParenthesis", "Unmatched.", &(0..1), None),
+        ];
+        assert_eq!(
+            json_array(errors),
+            concat!(
+                r#"[{"kind":"UnknownToken","message":"Unknown token.","start":2,"end":3,"value":"$"},"#,
+                r#"{"kind":"UnmatchedParenthesis","message":"Unmatched.","start":0,"end":1}]"#
+            )
+        );
+        assert_eq!(json_array(Vec::new()), "[]");
+    }
+
+    #[test]
+    fn test_string_object() {
+        let text = json_object([
+            ("code", json_string("a + \"b\"\n\u{1}😀")),
+            ("file", "null".to_string()),
+        ]);
+        assert_eq!(text, r#"{"code":"a + \"b\"\n\u0001😀","file":null}"#);
+        let Ok(fields) = parse_string_object(&text) else {
+            panic!("Failed to parse: {}", text)
+        };
+        assert_eq!(
+            fields,
+            vec![
+                ("code".to_string(), Some("a + \"b\"\n\u{1}😀".to_string())),
+                ("file".to_string(), None),
+            ]
+        );
+
+        let fields = parse_string_object(" { \"a\" : \"\\ud83d\\ude00\\/\" } \n");
+        assert_eq!(fields, Ok(vec![("a".to_string(), Some("😀/".to_string()))]));
+        assert_eq!(parse_string_object("{}"), Ok(Vec::new()));
+    }
+
+    #[test]
+    fn test_string_object_invalid() {
+        for text in [
+            "",
+            "{",
+            "{\"a\":5}",
+            "{\"a\":\"b\",}",
+            "{\"a\":\"b\"} x",
+            "{\"a\":\"\\x\"}",
+            "{\"a\":\"\\ud83d\"}",
+            "{\"a\":nul}",
+            "{\"a\":\"b",
+        ] {
+            assert!(parse_string_object(text).is_err(), "{}", text);
+        }
+        assert_eq!(
+            parse_string_object("{\"a\" 5}"),
+            Err(JsonError {
+                position: 5,
+                message: "Unexpected character"
+            })
+        );
+    }
+}
@@ -0,0 +1,6 @@
+//! Helpers shared by the labs, so every one of them has a single copy.
+
+pub mod config;
+pub mod json;
+pub mod terminal;
+pub mod text;
@@ -0,0 +1,8 @@
+use std::io::IsTerminal;
+
+/// Colors are off when the `NO_COLOR` variable is set (see https://no-color.org)
+/// or when stdout is not a terminal, e.g. piped to a file.
+pub fn is_color_allowed() -> bool {
+    let is_no_color = std::env::var_os("NO_COLOR").is_some_and(|value| !value.is_empty());
+    !is_no_color && std::io::stdout().is_terminal()
+}
@@ -0,0 +1,47 @@
+/// `word` for one and `word` with "s" for any other count: "1 error", "2 errors".
+pub fn plural(word: &str, count: usize) -> String {
+    match count {
+        1 => word.to_string(),
+        _ => format!("{}s", word),
+    }
+}
+
+const BOM: char = '\u{feff}';
+
+/// Strips a leading UTF-8 BOM and converts `\r\n` and `\r` line endings to `\n`,
+/// so pasted or opened code tokenizes like the LF-only version.
+pub fn normalize_source(text: &str) -> String {
+    let text = text.strip_prefix(BOM).unwrap_or(text);
+
+    text.replace("\r\n", "\n").replace('\r', "\n")
+}
+
+pub fn needs_normalization(text: &str) -> bool {
+    text.starts_with(BOM) || text.contains('\r')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_plural() {
+        assert_eq!(plural("error", 0), "errors");
+        assert_eq!(plural("error", 1), "error");
+        assert_eq!(plural("line", 2), "lines");
+    }
+
+    #[test]
+    fn test_normalize_source() {
+        let text = "\u{feff}a +\r\n b\r* c\r\n";
+        let expected = "a +\n b\n* c\n";
+        assert!(needs_normalization(text));
+        assert!(!needs_normalization(expected));
+        assert_eq!(normalize_source(text), expected);
+        // Tokens of both are compared by `test_normalize_source` of the lab tokenizers,
+        // which depend on this crate
+
+        // BOM in the middle is not touched
+        assert_eq!(normalize_source("a\u{feff}"), "a\u{feff}");
+    }
+}
@@ -0,0 +1,39 @@
+use criterion::{BenchmarkId, Criterion, Throughput, criterion_group, criterion_main};
+use std::hint::black_box;
+
+// The crate has no library target, so the tokenizer is compiled in directly
+#[allow(dead_code)]
+#[path = "../src/compiler/tokenizer.rs"]
+mod tokenizer;
+
+// Mix of all token kinds, `terms` operands long
+fn generate_input(terms: usize) -> String {
+    const OPERANDS: [&str; 6] = ["a", "var_2", "125", "0.71", "sin(x)", "A[i][j + 1]"];
+    const OPERATORS: [&str; 6] = [" + ", "-", " * ", "/", " % ", "\t&\n"];
+
+    let mut input = String::new();
+    for index in 0..terms {
+        if index > 0 {
+            input.push_str(OPERATORS[index % OPERATORS.len()]);
+        }
+        input.push_str(OPERANDS[(index * 5) % OPERANDS.len()]);
+    }
+    input
+}
+
+fn bench_tokenize(c: &mut Criterion) {
+    let mut group = c.benchmark_group("tokenize");
+
+    for terms in [10, 100, 1_000, 10_000] {
+        let input = generate_input(terms);
+        group.throughput(Throughput::Bytes(input.len() as u64));
+        group.bench_with_input(BenchmarkId::from_parameter(terms), &input, |b, input| {
+            b.iter(|| tokenizer::tokenize(black_box(input)))
+        });
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, bench_tokenize);
+criterion_main!(benches);
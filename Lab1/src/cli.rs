@@ -1,13 +1,49 @@
 use crate::error::Error;
 use crate::{compiler, io};
-use clap::Parser;
-use std::path::PathBuf;
+use clap::error::ErrorKind;
+use clap::{CommandFactory, Parser, ValueEnum};
+use colored::Colorize;
+use common::terminal::is_color_allowed;
+use common::text::plural;
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+const WATCH_INTERVAL: Duration = Duration::from_millis(300);
+const PROGRESS_INTERVAL: Duration = Duration::from_millis(500);
 
 #[derive(Parser, Debug)]
-#[command(author = "Alex Kovalov", version = "0.0.1")]
+#[command(
+    author = "Alex Kovalov",
+    version = "0.0.1",
+    after_help = "Exit codes: 0 - no syntax errors, 1 - I/O error, 2 - syntax errors found, 64 - invalid arguments."
+)]
 pub struct Cli {
-    #[arg(short = 'c', long, help = "Code file.")]
-    pub code_file: PathBuf,
+    #[arg(
+        short = 'c',
+        long,
+        required_unless_present_any = ["batch", "watch", "explain"],
+        conflicts_with = "batch",
+        help = "Code file."
+    )]
+    pub code_file: Option<PathBuf>,
+
+    #[arg(
+        short = 'b',
+        long,
+        help = "Batch file. Each line is analyzed as an independent expression."
+    )]
+    pub batch: Option<PathBuf>,
+
+    #[arg(
+        short = 'j',
+        long,
+        // `requires = "batch"` is not checked, as `batch` conflicts with `code_file`
+        conflicts_with_all = ["code_file", "watch", "explain"],
+        help = "Threads analyzing the batch lines, 0 for one per CPU. Defaults to 1, serial analysis."
+    )]
+    pub jobs: Option<usize>,
 
     #[arg(
         short = 'o',
@@ -18,18 +54,391 @@ pub struct Cli {
 
     #[arg(short = 'p', action, long, help = "Pretty print output.")]
     pub pretty: bool,
+
+    #[arg(
+        short = 'f',
+        long,
+        value_enum,
+        help = "Output format. Defaults to \"pretty\" in a terminal without NO_COLOR and \"text\" otherwise."
+    )]
+    pub format: Option<OutputFormat>,
+
+    #[arg(
+        long,
+        action,
+        help = "Only check the syntax, without printing the report. See exit codes."
+    )]
+    pub check_only: bool,
+
+    #[arg(
+        long,
+        action,
+        conflicts_with = "check_only",
+        help = "Print counts of token kinds and error kinds instead of the report."
+    )]
+    pub stats: bool,
+
+    #[arg(
+        short = 'w',
+        long,
+        conflicts_with_all = ["code_file", "batch", "output_file", "check_only"],
+        help = "Code file to analyze again on every change, until Ctrl-C."
+    )]
+    pub watch: Option<PathBuf>,
+
+    #[arg(
+        long,
+        value_name = "ERROR_KIND",
+        conflicts_with_all = ["code_file", "batch", "watch"],
+        help = "Explain a syntax error kind with an example, e.g. UnmatchedParenthesis."
+    )]
+    pub explain: Option<String>,
+
+    #[arg(
+        short = 'q',
+        long,
+        action,
+        conflicts_with = "verbose",
+        help = "Print no report and no batch progress if no syntax errors were found. See exit codes."
+    )]
+    pub quiet: bool,
+
+    #[arg(
+        short = 'v',
+        long,
+        action,
+        conflicts_with = "batch",
+        help = "Also print the token stream and the analysis time to stderr."
+    )]
+    pub verbose: bool,
+}
+
+/// Process exit codes, so the CLI can be used from scripts.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Status {
+    /// Syntax analysis found no errors.
+    Success = 0,
+    /// I/O error, e.g. the code file does not exist.
+    Failure = 1,
+    /// Syntax analysis found at least one error.
+    SyntaxErrors = 2,
+    /// Invalid arguments, e.g. an unknown flag. Same as `EX_USAGE` of sysexits.
+    Usage = 64,
+}
+
+impl Status {
+    pub fn from_validity(is_valid: bool) -> Self {
+        match is_valid {
+            true => Self::Success,
+            false => Self::SyntaxErrors,
+        }
+    }
+
+    /// Prints the error of clap, which would exit with 2, the code of syntax errors.
+    /// Help and version are errors for clap too, but they are printed successfully.
+    pub fn from_usage_error(error: &clap::Error) -> Self {
+        let _ = error.print();
+        match error.use_stderr() {
+            true => Self::Usage,
+            false => Self::Success,
+        }
+    }
+}
+
+impl From<Status> for ExitCode {
+    fn from(status: Status) -> Self {
+        ExitCode::from(status as u8)
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+pub enum OutputFormat {
+    Json,
+    Text,
+    Pretty,
+}
+
+impl OutputFormat {
+    pub fn resolve(format: Option<OutputFormat>) -> Self {
+        match format {
+            Some(format) => format,
+            None if is_color_allowed() => Self::Pretty,
+            None => Self::Text,
+        }
+    }
+}
+
+pub fn batch_summary(ok: usize, failed: usize, elapsed: Duration) -> String {
+    format!(
+        "Batch finished: {} {}, {} OK, {} with errors in {:.3} s.",
+        ok + failed,
+        plural("line", ok + failed),
+        ok,
+        failed,
+        elapsed.as_secs_f64()
+    )
+}
+
+// Report is silenced by `--quiet`, stats are not
+enum Output {
+    Report(String),
+    Stats(String),
 }
 
 impl Cli {
-    pub fn run() -> Result<(), Error> {
-        let context = Cli::parse();
+    pub fn run() -> Result<Status, Error> {
+        let context = match Cli::try_parse() {
+            Ok(context) => context,
+            Err(error) => return Ok(Status::from_usage_error(&error)),
+        };
+
+        // `--format pretty` still turns them on for the report
+        if !is_color_allowed() {
+            colored::control::set_override(false);
+        }
+
+        if let Some(path) = &context.watch {
+            context.watch(path);
+        }
+
+        if let Some(name) = &context.explain {
+            print!("{}", compiler::explain::explain(name)?);
+            return Ok(Status::Success);
+        }
+
+        let (output, is_valid) = match (&context.batch, &context.code_file) {
+            (Some(batch_file), _) => {
+                let lines = io::read_batch_file(batch_file)?;
+                let results = Self::analyze_batch(
+                    &lines,
+                    context.jobs.unwrap_or(1),
+                    context.quiet,
+                )?;
+                let is_valid = results.iter().all(|line| line.errors.is_empty());
+                let output = match (context.check_only, context.stats) {
+                    (true, _) => None,
+                    (false, true) => Some(Output::Stats(compiler::batch_stats(&results))),
+                    (false, false) => Some(Output::Report(compiler::report_batch(
+                        &results,
+                        OutputFormat::resolve(context.format),
+                    ))),
+                };
+                (output, is_valid)
+            },
+            (None, Some(code_file)) => {
+                let code = io::read_code_file(code_file)?;
+
+                let start = Instant::now();
+                let syntax_errors = compiler::analyze(&code);
+                let elapsed = start.elapsed();
+                let is_valid = syntax_errors.is_empty();
+                if context.verbose {
+                    Self::print_verbose(&code, elapsed, is_valid);
+                }
+
+                let format = OutputFormat::resolve(context.format);
+                let output = match (context.check_only, context.stats) {
+                    (true, _) => None,
+                    (false, true) => {
+                        Some(Output::Stats(compiler::stats(std::slice::from_ref(&code))))
+                    },
+                    (false, false) => Some(Output::Report(compiler::report_analysis(
+                        &code,
+                        syntax_errors,
+                        context.pretty,
+                        format,
+                    ))),
+                };
+                (output, is_valid)
+            },
+            // Rejected by clap already: `code_file` is required unless `batch`, `watch`
+            // or `explain` is present, and watching never returns
+            (None, None) => {
+                let error = Cli::command()
+                    .error(ErrorKind::MissingRequiredArgument, "no code file given");
+                return Ok(Status::from_usage_error(&error));
+            },
+        };
+
+        // Errors are still printed, so scripts can show them.
+        // Stats are asked for explicitly, so they are never silenced
+        let output = output.and_then(|output| match output {
+            Output::Report(_) if context.quiet && is_valid => None,
+            Output::Report(output) | Output::Stats(output) => Some(output),
+        });
+        if let Some(output) = output {
+            let output_destination = io::define_output_destination(context.output_file);
+            io::write_output(&output, output_destination)?;
+        }
+
+        Ok(Status::from_validity(is_valid))
+    }
+
+    // Progress goes to stderr, so stdout stays clean for piping the results
+    fn analyze_batch(
+        lines: &[String], jobs: usize, is_quiet: bool,
+    ) -> Result<Vec<compiler::BatchLine>, Error> {
+        let start = Instant::now();
+        let last_report = Mutex::new(start);
+        let progress = |processed: usize, total: usize| {
+            if is_quiet {
+                return;
+            }
+            let Ok(mut last_report) = last_report.lock() else {
+                return;
+            };
+            if last_report.elapsed() >= PROGRESS_INTERVAL {
+                *last_report = Instant::now();
+                eprintln!("processed {}/{}", processed, total);
+            }
+        };
+
+        let results = match jobs {
+            1 => compiler::analyze_batch_with_progress(lines, progress),
+            jobs => compiler::analyze_batch_parallel(lines, jobs, progress)?,
+        };
+
+        let failed = results
+            .iter()
+            .filter(|line| !line.errors.is_empty())
+            .count();
+        if !is_quiet || failed > 0 {
+            eprintln!(
+                "{}",
+                batch_summary(results.len() - failed, failed, start.elapsed())
+            );
+        }
+
+        Ok(results)
+    }
 
-        let code = io::read_code_file(&context.code_file)?;
+    // Goes to stderr along with the batch progress, so the report stays parsable
+    fn print_verbose(code: &str, elapsed: Duration, is_valid: bool) {
+        eprint!("{}", compiler::report_tokens(code));
+        eprintln!(
+            "Analysis time: {:.3} ms ({}).",
+            elapsed.as_secs_f64() * 1000.0,
+            match is_valid {
+                true => "no syntax errors",
+                false => "syntax errors found",
+            }
+        );
+    }
+
+    // Polls the modification time, so no watcher dependency is needed
+    fn watch(&self, path: &Path) -> ! {
+        let mut snapshot = io::FileSnapshot::of(path);
+        self.print_watched(path);
+
+        loop {
+            std::thread::sleep(WATCH_INTERVAL);
+
+            let current = io::FileSnapshot::of(path);
+            if io::FileSnapshot::is_changed(&snapshot, &current) {
+                snapshot = current;
+                self.print_watched(path);
+            }
+        }
+    }
+
+    fn print_watched(&self, path: &Path) {
+        // Clear the screen and move the cursor to the top
+        print!("\x1B[2J\x1B[1;1H");
+
+        match io::read_code_file(&path.to_path_buf()) {
+            Ok(code) if self.stats => println!("{}", compiler::stats(&[code])),
+            Ok(code) => {
+                let format = OutputFormat::resolve(self.format);
+                println!("{}", compiler::compile(&code, self.pretty, format));
+            },
+            Err(error) => eprintln!("{}. {error}", "Error".red().bold()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format_flag() {
+        let Ok(cli) = Cli::try_parse_from(["Lab1", "-c", "main.xai", "--format", "json"])
+        else {
+            panic!("Failed to parse arguments")
+        };
+        assert_eq!(cli.format, Some(OutputFormat::Json));
+
+        let Ok(cli) = Cli::try_parse_from(["Lab1", "-c", "main.xai", "-f", "text"])
+        else {
+            panic!("Failed to parse arguments")
+        };
+        assert_eq!(OutputFormat::resolve(cli.format), OutputFormat::Text);
 
-        let output = compiler::compile(&code, context.pretty);
+        let Ok(cli) = Cli::try_parse_from(["Lab1", "-c", "main.xai"]) else {
+            panic!("Failed to parse arguments")
+        };
+        assert_eq!(cli.format, None);
 
-        let output_destination = io::define_output_destination(context.output_file);
+        assert!(Cli::try_parse_from(["Lab1", "-c", "main.xai", "-f", "xml"]).is_err());
+    }
+
+    #[test]
+    fn test_watch_flag() {
+        let Ok(cli) = Cli::try_parse_from(["Lab1", "--watch", "main.xai", "-p"]) else {
+            panic!("Failed to parse arguments")
+        };
+        assert_eq!(cli.watch, Some(PathBuf::from("main.xai")));
+        assert_eq!(cli.code_file, None);
+
+        assert!(
+            Cli::try_parse_from(["Lab1", "-w", "main.xai", "-c", "main.xai"]).is_err()
+        );
+        assert!(Cli::try_parse_from(["Lab1", "-w", "main.xai", "--check-only"]).is_err());
+    }
+
+    #[test]
+    fn test_quiet_verbose_flags() {
+        let Ok(cli) = Cli::try_parse_from(["Lab1", "-c", "main.xai", "-q"]) else {
+            panic!("Failed to parse arguments")
+        };
+        assert!(cli.quiet && !cli.verbose);
+
+        let Ok(cli) = Cli::try_parse_from(["Lab1", "-c", "main.xai", "--verbose"]) else {
+            panic!("Failed to parse arguments")
+        };
+        assert!(cli.verbose && !cli.quiet);
+
+        assert!(Cli::try_parse_from(["Lab1", "-c", "main.xai", "-q", "-v"]).is_err());
+        assert!(Cli::try_parse_from(["Lab1", "-b", "batch.xai", "-v"]).is_err());
+        assert!(Cli::try_parse_from(["Lab1", "-b", "batch.xai", "-q"]).is_ok());
+    }
+
+    #[test]
+    fn test_jobs_flag() {
+        let Ok(cli) = Cli::try_parse_from(["Lab1", "-b", "batch.xai", "--jobs", "4"])
+        else {
+            panic!("Failed to parse arguments")
+        };
+        assert_eq!(cli.jobs, Some(4));
+
+        let Ok(cli) = Cli::try_parse_from(["Lab1", "-b", "batch.xai"]) else {
+            panic!("Failed to parse arguments")
+        };
+        assert_eq!(cli.jobs, None);
+
+        assert!(Cli::try_parse_from(["Lab1", "-c", "main.xai", "-j", "4"]).is_err());
+        assert!(Cli::try_parse_from(["Lab1", "-b", "batch.xai", "-j", "-1"]).is_err());
+    }
 
-        io::write_output(&output, output_destination)
+    #[test]
+    fn test_batch_summary() {
+        assert_eq!(
+            batch_summary(48_000, 2_000, Duration::from_millis(1_234)),
+            "Batch finished: 50000 lines, 48000 OK, 2000 with errors in 1.234 s."
+        );
+        assert_eq!(
+            batch_summary(0, 0, Duration::ZERO),
+            "Batch finished: 0 lines, 0 OK, 0 with errors in 0.000 s."
+        );
     }
 }
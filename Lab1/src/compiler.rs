@@ -1,13 +1,275 @@
+use crate::cli::OutputFormat;
 use crate::compiler::syntax::{SyntaxAnalyzer, SyntaxError};
+use crate::compiler::tokenizer::Token;
+use crate::error::Error;
 use crate::utils::StringExtension;
 use colored::Colorize;
+use common::json::{json_array, syntax_error_json};
+use common::text::plural;
+use rayon::prelude::*;
 use std::ops::Add;
+use std::sync::atomic::{self, AtomicUsize};
 
-pub fn compile(source: &str, is_pretty: bool) -> String {
+pub fn compile(source: &str, is_pretty: bool, format: OutputFormat) -> String {
+    report_analysis(source, analyze(source), is_pretty, format)
+}
+
+pub fn analyze(source: &str) -> Vec<SyntaxError> {
     let tokens = tokenizer::tokenize(source);
-    let syntax_errors = SyntaxAnalyzer::new(tokens).analyze();
+    SyntaxAnalyzer::new(tokens).analyze()
+}
+
+/// Report of the errors found by `analyze` in the source.
+pub fn report_analysis(
+    source: &str, syntax_errors: Vec<SyntaxError>, is_pretty: bool, format: OutputFormat,
+) -> String {
+    match format {
+        OutputFormat::Json => report_json(&syntax_errors),
+        OutputFormat::Text | OutputFormat::Pretty => {
+            override_colors(format);
+            report(source, syntax_errors, is_pretty)
+        },
+    }
+}
+
+// Text and pretty reports differ only in colors
+fn override_colors(format: OutputFormat) {
+    colored::control::set_override(format == OutputFormat::Pretty);
+}
+
+/// One token per line, e.g. "- Identifier `a` [Position: 1]".
+pub fn report_tokens(source: &str) -> String {
+    tokenizer::tokenize(source)
+        .iter()
+        .map(|token| {
+            let value = match &token.value {
+                Some(value) => format!(" `{}`", value),
+                None => String::new(),
+            };
+            format!("- {}{} {}\n", token.kind, value, token.display_position())
+        })
+        .collect()
+}
+
+/// Array of objects with the `kind`, `message`, `start` and `end` of every error.
+fn report_json(syntax_errors: &[SyntaxError]) -> String {
+    json_array(syntax_errors.iter().map(|error| {
+        syntax_error_json(
+            &format!("{:?}", error.kind),
+            &error.to_string(),
+            &error.token.position,
+            error.token.value.as_deref(),
+        )
+    }))
+}
+
+/// Token kinds and error kinds over all sources, e.g. all lines of a batch file.
+pub fn stats(sources: &[String]) -> String {
+    let mut tokens = Vec::new();
+    let mut errors = Vec::new();
+
+    for source in sources {
+        let source_tokens = tokenizer::tokenize(source);
+        errors.extend(SyntaxAnalyzer::new(source_tokens.clone()).analyze());
+        tokens.extend(source_tokens);
+    }
+
+    report_stats(&tokens, &errors)
+}
+
+/// Same as `stats`, but over the results of a batch that is already analyzed.
+pub fn batch_stats(results: &[BatchLine]) -> String {
+    report_stats(
+        results.iter().flat_map(|line| &line.tokens),
+        results.iter().flat_map(|line| &line.errors),
+    )
+}
+
+pub fn report_stats<'a>(
+    tokens: impl IntoIterator<Item = &'a Token>,
+    errors: impl IntoIterator<Item = &'a SyntaxError>,
+) -> String {
+    let mut result = String::new();
+
+    let token_kinds = tokens.into_iter().map(|token| token.kind.to_string());
+    result.push_str("Tokens:\n");
+    result.push_str(&histogram(count_in_order(token_kinds), "No tokens."));
+
+    let error_kinds = errors.into_iter().map(|error| format!("{:?}", error.kind));
+    result.push_str("\nErrors:\n");
+    result.push_str(&histogram(count_in_order(error_kinds), "No errors."));
+
+    result
+}
+
+// Counts, ordered by the first occurrence
+fn count_in_order(items: impl Iterator<Item = String>) -> Vec<(String, usize)> {
+    let mut counts: Vec<(String, usize)> = Vec::new();
+    for item in items {
+        match counts.iter_mut().find(|(key, _)| *key == item) {
+            Some((_, count)) => *count += 1,
+            None => counts.push((item, 1)),
+        }
+    }
+    counts
+}
+
+// Bars longer than this are scaled down, so big batches still fit the terminal
+const HISTOGRAM_WIDTH: usize = 50;
+
+fn histogram(counts: Vec<(String, usize)>, empty: &str) -> String {
+    let Some(max) = counts.iter().map(|(_, count)| *count).max() else {
+        return format!("{}\n", empty);
+    };
+
+    counts
+        .into_iter()
+        .map(|(key, count)| {
+            let bar = "#".repeat(bar_length(count, max));
+            format!("{:25} {:>4} {}\n", key, count, bar)
+        })
+        .collect()
+}
+
+// Rounded up, so every kind that occurs still gets at least one '#'
+fn bar_length(count: usize, max: usize) -> usize {
+    match max <= HISTOGRAM_WIDTH {
+        true => count,
+        false => (count * HISTOGRAM_WIDTH).div_ceil(max),
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub struct BatchLine {
+    pub number: usize,
+    pub tokens: Vec<Token>,
+    pub errors: Vec<SyntaxError>,
+}
+
+pub fn analyze_batch(lines: &[String]) -> Vec<BatchLine> {
+    analyze_batch_with_progress(lines, |_, _| {})
+}
+
+/// `progress` is called after each analyzed line with the number of analyzed lines
+/// and the total. Blank lines are not analyzed, so they are not counted.
+pub fn analyze_batch_with_progress(
+    lines: &[String], mut progress: impl FnMut(usize, usize),
+) -> Vec<BatchLine> {
+    let total = count_non_blank(lines);
+    lines
+        .iter()
+        .enumerate()
+        .filter_map(|(index, line)| analyze_batch_line(index, line))
+        .enumerate()
+        .inspect(|(analyzed, _)| progress(analyzed + 1, total))
+        .map(|(_, line)| line)
+        .collect()
+}
+
+/// Same as `analyze_batch_with_progress`, but the lines are analyzed by `jobs` threads,
+/// one per CPU for 0. Results are in the order of the lines.
+pub fn analyze_batch_parallel(
+    lines: &[String], jobs: usize, progress: impl Fn(usize, usize) + Sync,
+) -> Result<Vec<BatchLine>, Error> {
+    let pool = rayon::ThreadPoolBuilder::new()
+        .num_threads(jobs)
+        .build()
+        .map_err(|error| Error::ThreadPool(jobs, error))?;
+
+    let total = count_non_blank(lines);
+    let analyzed = AtomicUsize::new(0);
+    let results = pool.install(|| {
+        lines
+            .par_iter()
+            .enumerate()
+            .filter_map(|(index, line)| analyze_batch_line(index, line))
+            .inspect(|_| {
+                let count = analyzed.fetch_add(1, atomic::Ordering::Relaxed) + 1;
+                progress(count, total);
+            })
+            .collect()
+    });
 
-    report(source, syntax_errors, is_pretty)
+    Ok(results)
+}
+
+fn count_non_blank(lines: &[String]) -> usize {
+    lines.iter().filter(|line| !is_blank(line)).count()
+}
+
+fn is_blank(line: &str) -> bool {
+    line.trim().is_empty()
+}
+
+// Every line is analyzed on its own, so lines can be analyzed in any thread
+fn analyze_batch_line(index: usize, line: &str) -> Option<BatchLine> {
+    // Blank lines are skipped, but numbering still follows the file
+    if is_blank(line) {
+        return None;
+    }
+
+    let tokens = tokenizer::tokenize(line);
+    let errors = SyntaxAnalyzer::new(tokens.clone()).analyze();
+
+    Some(BatchLine {
+        number: index + 1,
+        tokens,
+        errors,
+    })
+}
+
+pub fn compile_batch(lines: &[String], format: OutputFormat) -> String {
+    report_batch(&analyze_batch(lines), format)
+}
+
+pub fn report_batch(results: &[BatchLine], format: OutputFormat) -> String {
+    match format {
+        OutputFormat::Json => report_batch_json(results),
+        OutputFormat::Text | OutputFormat::Pretty => {
+            override_colors(format);
+            report_batch_text(results)
+        },
+    }
+}
+
+/// Array of objects with the `line` number and the `errors` as in `report_json`.
+fn report_batch_json(results: &[BatchLine]) -> String {
+    json_array(results.iter().map(|line| {
+        format!(
+            "{{\"line\":{},\"errors\":{}}}",
+            line.number,
+            report_json(&line.errors)
+        )
+    }))
+}
+
+fn report_batch_text(results: &[BatchLine]) -> String {
+    let mut result = String::new();
+
+    for line in results {
+        let status = match line.errors.len() {
+            0 => "OK".bold().green().to_string(),
+            n => format!("{} {}", n, plural("error", n)).red().to_string(),
+        };
+        result.push_str(&format!("line {}: {}\n", line.number, status));
+    }
+
+    let failed = results
+        .iter()
+        .filter(|line| !line.errors.is_empty())
+        .count();
+    result.push_str(&format!(
+        "\n{}: {} {}, {} {}, {} {}.\n",
+        "Batch result".bold(),
+        results.len(),
+        plural("line", results.len()),
+        (results.len() - failed).to_string().green(),
+        "OK".green(),
+        failed.to_string().red(),
+        "with errors".red()
+    ));
+
+    result
 }
 
 fn report(source: &str, syntax_errors: Vec<SyntaxError>, is_pretty: bool) -> String {
@@ -20,7 +282,7 @@ fn report(source: &str, syntax_errors: Vec<SyntaxError>, is_pretty: bool) -> Str
                 "{}: Found {} {}.\n",
                 "Analysis result".bold(),
                 n.to_string().red(),
-                "errors".red()
+                plural("error", n).red()
             )
         },
     };
@@ -103,5 +365,126 @@ fn format_errors(syntax_errors: Vec<SyntaxError>) -> String {
     result
 }
 
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::io;
+
+    #[test]
+    fn test_batch() {
+        let path =
+            std::env::temp_dir().join(format!("lab1-batch-{}.xai", std::process::id()));
+        let written = std::fs::write(&path, "a + b\n*a + nb -\n\nsin(x)\n(a + b\n");
+        assert!(written.is_ok());
+
+        let lines = io::read_batch_file(&path);
+        let _ = std::fs::remove_file(&path);
+        let Ok(lines) = lines else {
+            panic!("Failed to read batch file")
+        };
+
+        let counts: Vec<(usize, usize)> = analyze_batch(&lines)
+            .iter()
+            .map(|line| (line.number, line.errors.len()))
+            .collect();
+        assert_eq!(counts, vec![(1, 0), (2, 2), (4, 0), (5, 1)]);
+
+        let mut progress = Vec::new();
+        analyze_batch_with_progress(&lines, |analyzed, total| {
+            progress.push((analyzed, total))
+        });
+        assert_eq!(progress, vec![(1, 4), (2, 4), (3, 4), (4, 4)]);
+
+        // Blank lines are empty expressions on their own, but the batch skips them
+        let non_blank: Vec<String> = lines
+            .iter()
+            .filter(|line| !is_blank(line))
+            .cloned()
+            .collect();
+        assert_eq!(batch_stats(&analyze_batch(&lines)), stats(&non_blank));
+    }
+
+    #[test]
+    fn test_batch_parallel() {
+        let mixed = [
+            "a + b",
+            "*a + nb -",
+            "",
+            "sin(x)",
+            "(a + b",
+            "a[1] * b",
+            "  ",
+        ];
+        let lines: Vec<String> = (0..1000)
+            .map(|index| mixed[index % mixed.len()].to_string())
+            .collect();
+
+        let serial = report_batch(&analyze_batch(&lines), OutputFormat::Text);
+        for jobs in [1, 4, 0] {
+            let Ok(results) = analyze_batch_parallel(&lines, jobs, |_, _| {}) else {
+                panic!("Failed to analyze with {} jobs", jobs)
+            };
+            assert_eq!(
+                report_batch(&results, OutputFormat::Text),
+                serial,
+                "{} jobs",
+                jobs
+            );
+        }
+    }
+
+    #[test]
+    fn test_stats() {
+        let tokens = tokenizer::tokenize("a+a+a");
+        let errors = SyntaxAnalyzer::new(tokens.clone()).analyze();
+        assert_eq!(
+            report_stats(&tokens, &errors),
+            concat!(
+                "Tokens:\n",
+                "Identifier                   3 ###\n",
+                "Plus                         2 ##\n",
+                "\nErrors:\n",
+                "No errors.\n",
+            )
+        );
+
+        let report = stats(&["a + b".to_string(), "*a + nb -".to_string()]);
+        assert!(report.contains("Identifier                   4 ####\n"));
+        assert!(report.contains("UnexpectedOperator           1 #\n"));
+        let report = stats(&["a+".repeat(200)]);
+        assert!(report.contains(&format!(
+            "Identifier                 200 {}\n",
+            "#".repeat(50)
+        )));
+        assert!(report.contains(&format!(
+            "Plus                       200 {}\n",
+            "#".repeat(50)
+        )));
+        assert!(report.contains("UnexpectedEndOfExpression    1 #\n"));
+    }
+
+    #[test]
+    fn test_bar_length() {
+        assert_eq!(bar_length(3, 3), 3);
+        assert_eq!(bar_length(50, 50), 50);
+        assert_eq!(bar_length(1000, 1000), 50);
+        assert_eq!(bar_length(500, 1000), 25);
+        assert_eq!(bar_length(1, 1000), 1);
+    }
+
+    #[test]
+    fn test_report_tokens() {
+        assert_eq!(
+            report_tokens("ab+1"),
+            concat!(
+                "- Identifier `ab` [Position: 1..2]\n",
+                "- Plus [Position: 3]\n",
+                "- Number `1` [Position: 4]\n",
+            )
+        );
+    }
+}
+
+pub mod explain;
 pub mod syntax;
 pub mod tokenizer;
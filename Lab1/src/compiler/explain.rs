@@ -0,0 +1,182 @@
+use crate::compiler::syntax::SyntaxErrorKind;
+use crate::error::Error;
+
+/// Description and a minimal example of every syntax error kind, for `--explain`.
+pub const EXPLANATIONS: [(SyntaxErrorKind, &str, &str); 22] = [
+    (
+        SyntaxErrorKind::EmptyBrackets,
+        "An array is accessed without an index. Put an expression between the brackets.",
+        "a[]",
+    ),
+    (
+        SyntaxErrorKind::EmptyExpression,
+        "The code has nothing but whitespace. Write an expression to analyze.",
+        "",
+    ),
+    (
+        SyntaxErrorKind::EmptyParentheses,
+        "Parentheses contain nothing. A grouping needs an expression inside.",
+        "1 + ()",
+    ),
+    (
+        SyntaxErrorKind::InvalidBinaryLiteral,
+        "A binary literal starts with '0b' and contains only the digits 0 and 1.",
+        "0b102",
+    ),
+    (
+        SyntaxErrorKind::InvalidFloat,
+        "A float has digits on both sides of the dot.",
+        "1.e",
+    ),
+    (
+        SyntaxErrorKind::InvalidFunctionName,
+        "Only a name can be called as a function. A number followed by parentheses is not a call.",
+        "2(a)",
+    ),
+    (
+        SyntaxErrorKind::InvalidHexLiteral,
+        "A hexadecimal literal starts with '0x' and contains only the digits 0-9 and a-f.",
+        "0xZ1",
+    ),
+    (
+        SyntaxErrorKind::InvalidVariableName,
+        "A variable name starts with a letter or an underscore, not with a digit.",
+        "2a",
+    ),
+    (
+        SyntaxErrorKind::MissingArgument,
+        "A function argument is missing between the commas or after the last one.",
+        "f(a, )",
+    ),
+    (
+        SyntaxErrorKind::UnexpectedBackslash,
+        "A backslash is found outside of a string, e.g. in a Windows path.",
+        "a \\ b",
+    ),
+    (
+        SyntaxErrorKind::UnexpectedBrackets,
+        "Brackets follow a value that is not an array name.",
+        "a + [1]",
+    ),
+    (
+        SyntaxErrorKind::UnexpectedComma,
+        "A comma separates function arguments and array indices. It is not allowed elsewhere.",
+        "a , b",
+    ),
+    (
+        SyntaxErrorKind::UnexpectedDot,
+        "A dot is only allowed inside a float, between its digits.",
+        ".5",
+    ),
+    (
+        SyntaxErrorKind::UnexpectedEndOfExpression,
+        "The expression ends where an operand is expected, e.g. after an operator.",
+        "a +",
+    ),
+    (
+        SyntaxErrorKind::UnexpectedNewLine,
+        "An expression must fit on one line.",
+        "a +\nb",
+    ),
+    (
+        SyntaxErrorKind::UnexpectedOperand,
+        "Two operands follow each other without an operator between them.",
+        "a b",
+    ),
+    (
+        SyntaxErrorKind::UnexpectedOperator,
+        "An operator is found where an operand is expected, e.g. two operators in a row.",
+        "a + * b",
+    ),
+    (
+        SyntaxErrorKind::UnexpectedParenthesis,
+        "A closing parenthesis is found where an operand is expected.",
+        "a + )",
+    ),
+    (
+        SyntaxErrorKind::UnknownToken,
+        "The character is not a part of the grammar.",
+        "$a",
+    ),
+    (
+        SyntaxErrorKind::UnmatchedBrackets,
+        "A bracket has no pair: every '[' needs a ']' and the other way around.",
+        "a]",
+    ),
+    (
+        SyntaxErrorKind::UnmatchedParenthesis,
+        "A parenthesis has no pair: every '(' needs a ')' and the other way around.",
+        "(a + b",
+    ),
+    (
+        SyntaxErrorKind::UnmatchedQuotationMark,
+        "A string is not closed by a quotation mark.",
+        "\"abc",
+    ),
+];
+
+/// Explanation of the kind, by the name of its variant, e.g. `UnmatchedParenthesis`.
+pub fn explain(name: &str) -> Result<String, Error> {
+    let Some((kind, description, example)) = EXPLANATIONS
+        .iter()
+        .find(|(kind, _, _)| format!("{:?}", kind) == name.trim())
+    else {
+        let kinds: Vec<String> = EXPLANATIONS
+            .iter()
+            .map(|(kind, _, _)| format!("{:?}", kind))
+            .collect();
+        return Err(Error::UnknownErrorKind(name.to_string(), kinds.join(", ")));
+    };
+
+    Ok(format!(
+        "{:?}\n\n{}\n\nExample:\n    {}\n",
+        kind,
+        description,
+        example.replace('\n', "\n    ")
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::compiler::syntax::SyntaxAnalyzer;
+    use crate::compiler::tokenizer;
+
+    fn is_example_valid(kind: &SyntaxErrorKind, example: &str) -> bool {
+        SyntaxAnalyzer::new(tokenizer::tokenize(example))
+            .analyze()
+            .iter()
+            .any(|error| error.kind == *kind)
+    }
+
+    #[test]
+    fn test_examples() {
+        for (kind, _, example) in &EXPLANATIONS {
+            assert!(
+                is_example_valid(kind, example),
+                "Example {:?} does not produce {:?}",
+                example,
+                kind
+            );
+        }
+    }
+
+    #[test]
+    fn test_explain() {
+        let Ok(explanation) = explain("UnexpectedNewLine") else {
+            panic!("Failed to explain a known kind")
+        };
+        assert_eq!(
+            explanation,
+            "UnexpectedNewLine\n\nAn expression must fit on one line.\n\nExample:\n    a +\n    b\n"
+        );
+
+        let Err(error) = explain("MissingOperator") else {
+            panic!("Unknown kind is explained")
+        };
+        let message = error.to_string();
+        assert!(message.starts_with("Unknown error kind: MissingOperator."));
+        assert!(message.contains("EmptyBrackets, EmptyExpression, EmptyParentheses,"));
+        assert!(message.ends_with("UnmatchedQuotationMark."));
+    }
+}
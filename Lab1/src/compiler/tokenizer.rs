@@ -1,4 +1,6 @@
+use std::iter::Peekable;
 use std::ops::Range;
+use std::str::CharIndices;
 use strum_macros::Display;
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -22,7 +24,7 @@ impl Token {
     }
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, Display)]
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Display)]
 pub enum TokenType {
     Identifier,
     Number,
@@ -45,6 +47,9 @@ pub enum TokenType {
     Dot,
     Comma,
 
+    // Only valid inside strings, e.g. a Windows path is an error
+    Backslash,
+
     QuotationMark,
 
     Space,
@@ -87,39 +92,23 @@ macro_rules! token {
 
 pub fn tokenize(input: &str) -> Vec<Token> {
     let mut tokens: Vec<Token> = Vec::new();
-    let chars: Vec<char> = input.chars().collect();
+    let mut cursor = Cursor::new(input);
 
-    for (index, symbol) in chars.iter().enumerate() {
-        if let Some(last_token) = tokens.last()
-            && last_token.position.end > index
-        {
-            continue;
-        }
+    while let Some((byte_start, symbol)) = cursor.next() {
+        let index = cursor.next_index - 1;
 
         let token = match symbol {
             symbol if symbol.is_alphabetic() || symbol.eq(&'_') => {
-                let start = index;
-                let mut end = index + 1;
-
-                while end < chars.len()
-                    && (chars[end].is_alphanumeric() || chars[end] == '_')
-                {
-                    end += 1;
-                }
+                let byte_end = cursor.skip_while(|c| c.is_alphanumeric() || c == '_');
 
-                let value: String = chars[start..end].iter().collect();
-                token!(TokenType::Identifier, value, start..end)
+                let value = input[byte_start..byte_end].to_string();
+                token!(TokenType::Identifier, value, index..cursor.next_index)
             },
             '0'..='9' => {
-                let start = index;
-                let mut end = index + 1;
+                let byte_end = cursor.skip_while(char::is_numeric);
 
-                while end < chars.len() && chars[end].is_numeric() {
-                    end += 1;
-                }
-
-                let value: String = chars[start..end].iter().collect();
-                token!(TokenType::Number, value, start..end)
+                let value = input[byte_start..byte_end].to_string();
+                token!(TokenType::Number, value, index..cursor.next_index)
             },
             '+' => token!(TokenType::Plus, index..index + 1),
             '-' => token!(TokenType::Minus, index..index + 1),
@@ -136,17 +125,13 @@ pub fn tokenize(input: &str) -> Vec<Token> {
             '.' => token!(TokenType::Dot, index..index + 1),
             ',' => token!(TokenType::Comma, index..index + 1),
             '"' => token!(TokenType::QuotationMark, index..index + 1),
+            '\\' => token!(TokenType::Backslash, index..index + 1),
             '\n' => token!(TokenType::NewLine, index..index + 1),
             c if c.eq(&'\t') => token!(TokenType::Tab, index..index + 1),
             c if c.is_whitespace() => {
-                let start = index;
-                let mut end = index + 1;
-
-                while end < chars.len() && chars[end].is_whitespace() {
-                    end += 1;
-                }
+                cursor.skip_while(char::is_whitespace);
 
-                token!(TokenType::Space, start..end)
+                token!(TokenType::Space, index..cursor.next_index)
             },
             c => token!(TokenType::Unknown, c.to_string(), index..index + 1),
         };
@@ -157,6 +142,43 @@ pub fn tokenize(input: &str) -> Vec<Token> {
     tokens
 }
 
+// Token positions count chars, while values are sliced from the input by bytes
+struct Cursor<'a> {
+    input: &'a str,
+    chars: Peekable<CharIndices<'a>>,
+    // Char index of the next symbol
+    next_index: usize,
+}
+
+impl<'a> Cursor<'a> {
+    fn new(input: &'a str) -> Self {
+        Self {
+            input,
+            chars: input.char_indices().peekable(),
+            next_index: 0,
+        }
+    }
+
+    fn next(&mut self) -> Option<(usize, char)> {
+        let next = self.chars.next()?;
+        self.next_index += 1;
+        Some(next)
+    }
+
+    // Returns the byte offset after the skipped symbols
+    fn skip_while(&mut self, predicate: impl Fn(char) -> bool) -> usize {
+        while let Some(&(_, symbol)) = self.chars.peek()
+            && predicate(symbol)
+        {
+            self.next();
+        }
+
+        self.chars
+            .peek()
+            .map_or(self.input.len(), |&(byte_index, _)| byte_index)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -1177,4 +1199,108 @@ mod tests {
 
         assert_eq!(tokens_actual, tokens_expected);
     }
+
+    // `tokenize` before the cursor, which collected the input into `Vec<char>`.
+    // Kept as it was, so the compared codes contain only the syntax it knew
+    fn tokenize_reference(input: &str) -> Vec<Token> {
+        let mut tokens: Vec<Token> = Vec::new();
+        let chars: Vec<char> = input.chars().collect();
+
+        for (index, symbol) in chars.iter().enumerate() {
+            if let Some(last_token) = tokens.last()
+                && last_token.position.end > index
+            {
+                continue;
+            }
+
+            let token = match symbol {
+                symbol if symbol.is_alphabetic() || symbol.eq(&'_') => {
+                    let start = index;
+                    let mut end = index + 1;
+
+                    while end < chars.len()
+                        && (chars[end].is_alphanumeric() || chars[end] == '_')
+                    {
+                        end += 1;
+                    }
+
+                    let value: String = chars[start..end].iter().collect();
+                    token!(TokenType::Identifier, value, start..end)
+                },
+                '0'..='9' => {
+                    let start = index;
+                    let mut end = index + 1;
+
+                    while end < chars.len() && chars[end].is_numeric() {
+                        end += 1;
+                    }
+
+                    let value: String = chars[start..end].iter().collect();
+                    token!(TokenType::Number, value, start..end)
+                },
+                '+' => token!(TokenType::Plus, index..index + 1),
+                '-' => token!(TokenType::Minus, index..index + 1),
+                '*' => token!(TokenType::Asterisk, index..index + 1),
+                '/' => token!(TokenType::Slash, index..index + 1),
+                '%' => token!(TokenType::Percent, index..index + 1),
+                '(' => token!(TokenType::LeftParenthesis, index..index + 1),
+                ')' => token!(TokenType::RightParenthesis, index..index + 1),
+                '[' => token!(TokenType::LeftBracket, index..index + 1),
+                ']' => token!(TokenType::RightBracket, index..index + 1),
+                '!' => token!(TokenType::ExclamationMark, index..index + 1),
+                '&' => token!(TokenType::Ampersand, index..index + 1),
+                '|' => token!(TokenType::Pipe, index..index + 1),
+                '.' => token!(TokenType::Dot, index..index + 1),
+                ',' => token!(TokenType::Comma, index..index + 1),
+                '"' => token!(TokenType::QuotationMark, index..index + 1),
+                '\n' => token!(TokenType::NewLine, index..index + 1),
+                c if c.eq(&'\t') => token!(TokenType::Tab, index..index + 1),
+                c if c.is_whitespace() => {
+                    let start = index;
+                    let mut end = index + 1;
+
+                    while end < chars.len() && chars[end].is_whitespace() {
+                        end += 1;
+                    }
+
+                    token!(TokenType::Space, start..end)
+                },
+                c => token!(TokenType::Unknown, c.to_string(), index..index + 1),
+            };
+
+            tokens.push(token);
+        }
+
+        tokens
+    }
+
+    #[test]
+    fn test_tokenize_matches_reference() {
+        let mut codes = vec![
+            "-a ++ b - 2v*func((t+2 -, sin(x/*2.01.2), )/8(-)**",
+            "*a + nb -",
+            "a ++ nb /* k -+/ g",
+            "a^b$c - d#h + q%t + !b&(z|t)",
+            "x + var1 + var_2 + _var_3 + var#4 + var!5 + 6var_ + $7 + ?8",
+            "125 + 2nb - 0xAB * 0x0R + 0b010 * 0b20 + ABh * 0Rh + 010b*20b",
+            "0.71/0.72.3 + .3 + 127.0.0.1*8. + 6.07ab - 9f.89hgt",
+            ")a+b( -(g+h)(g-k))*()) + (-b(t-2*x*(5) + A[7][2-x]",
+            "2(t) - f2(t) + g()/h(2, )*func(-t/q, f(4-t), -(x+2)*(y-2))",
+            "/a*b**c + m)*a*b + a*c - a*smn(j*k/m + m",
+            "-cos(-&t))/(*(*f)(127.0.0.1, \"/dev/null\", (t==0)?4more_errors:b^2) - .5",
+            "//(*0)- an*0p(a+b)-1.000.5//6(*f(-b, 1.8-0*(2-6) %1 + (++a)/(6x^2+4x-1) + d/dt*(smn(at+q)/(4cos(at)-ht^2)",
+            "-(-5x((int*)exp())/t - 3.14.15k/(2x^2-5x-1)*y - A[N*(i++)+j]",
+            "-(-exp(3et/4.0.2, 2i-1)/L + )((void*)*f()) + ((i++) + (++i/(i--))/k//) + 6.000.500.5",
+            "**f(*k, -p+1, ))2.1.1 + 1.8q((-5x ++ i)",
+            "/.1(2x^2-5x+7)-(-i)+ (j++)/0 - )(*f)(2, 7-x, )/q + send(-(2x+7)/A[j, i], 127.0.0.1 ) + )/",
+            "*101*1#(t-q)(t+q)//dt - (int*)f(8t, -(k/h)A[i+6.]), exp(), ))(t-k*8.00.1/.0",
+        ];
+        // Multibyte symbols, where char positions differ from byte offsets
+        codes.extend(["змінна + 2x - ч_1", "\tа\n  +   б\u{a0}"]);
+        let long = codes.concat();
+
+        for code in codes.into_iter().chain([long.as_str(), ""]) {
+            assert_eq!(tokenize(code), tokenize_reference(code), "Code: {}", code);
+        }
+    }
 }
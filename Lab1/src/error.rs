@@ -5,6 +5,12 @@ use thiserror::Error;
 pub enum Error {
     #[error("Type: I/O. {0}")]
     IO(IOError),
+
+    #[error("Unknown error kind: {0}. Expected one of: {1}.")]
+    UnknownErrorKind(String, String),
+
+    #[error("Failed to start {0} batch jobs. {1}")]
+    ThreadPool(usize, rayon::ThreadPoolBuildError),
 }
 
 #[derive(Debug, Error)]
@@ -12,6 +12,36 @@ pub fn read_code_file(path: &std::path::PathBuf) -> Result<String, Error> {
     })
 }
 
+/// Size and modification time of a file, compared by the watch mode.
+/// `None` if the file does not exist or cannot be read.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct FileSnapshot {
+    pub modified: std::time::SystemTime,
+    pub length: u64,
+}
+
+impl FileSnapshot {
+    pub fn of(path: &std::path::Path) -> Option<Self> {
+        let metadata = std::fs::metadata(path).ok()?;
+
+        Some(Self {
+            modified: metadata.modified().ok()?,
+            length: metadata.len(),
+        })
+    }
+
+    // Length is compared too, since the modification time may be coarse
+    pub fn is_changed(previous: &Option<Self>, current: &Option<Self>) -> bool {
+        previous != current
+    }
+}
+
+pub fn read_batch_file(path: &std::path::PathBuf) -> Result<Vec<String>, Error> {
+    let content = read_code_file(path)?;
+
+    Ok(content.lines().map(str::to_string).collect())
+}
+
 pub enum OutputDestination {
     Console,
     File(std::path::PathBuf),
@@ -43,3 +73,39 @@ pub fn write_output(
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::{Duration, SystemTime};
+
+    #[test]
+    fn test_file_snapshot() {
+        let snapshot = |seconds: u64, length: u64| {
+            Some(FileSnapshot {
+                modified: SystemTime::UNIX_EPOCH + Duration::from_secs(seconds),
+                length,
+            })
+        };
+
+        assert!(!FileSnapshot::is_changed(
+            &snapshot(10, 5),
+            &snapshot(10, 5)
+        ));
+        assert!(FileSnapshot::is_changed(&snapshot(10, 5), &snapshot(11, 5)));
+        assert!(FileSnapshot::is_changed(&snapshot(10, 5), &snapshot(10, 6)));
+        // Removed and created again
+        assert!(FileSnapshot::is_changed(&snapshot(10, 5), &None));
+        assert!(FileSnapshot::is_changed(&None, &snapshot(10, 5)));
+        assert!(!FileSnapshot::is_changed(&None, &None));
+
+        let path =
+            std::env::temp_dir().join(format!("lab1-watch-{}.xai", std::process::id()));
+        let written = std::fs::write(&path, "a + b");
+        let actual = FileSnapshot::of(&path);
+        let _ = std::fs::remove_file(&path);
+        assert!(written.is_ok());
+        assert_eq!(actual.map(|snapshot| snapshot.length), Some(5));
+        assert_eq!(FileSnapshot::of(&path), None);
+    }
+}
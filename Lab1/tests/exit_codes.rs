@@ -0,0 +1,88 @@
+use std::path::PathBuf;
+use std::process::Command;
+
+fn write_code(name: &str, code: &str) -> PathBuf {
+    let path =
+        std::env::temp_dir().join(format!("lab1-{}-{}.xai", name, std::process::id()));
+    let written = std::fs::write(&path, code);
+    assert!(written.is_ok());
+    path
+}
+
+fn exit_code(args: &[&str]) -> Option<i32> {
+    let Ok(output) = Command::new(env!("CARGO_BIN_EXE_Lab1")).args(args).output() else {
+        panic!("Failed to run the binary")
+    };
+    output.status.code()
+}
+
+fn stdout_and_code(args: &[&str]) -> (String, Option<i32>) {
+    let Ok(output) = Command::new(env!("CARGO_BIN_EXE_Lab1"))
+        .args(args)
+        .env("NO_COLOR", "1")
+        .output()
+    else {
+        panic!("Failed to run the binary")
+    };
+    (
+        String::from_utf8_lossy(&output.stdout).to_string(),
+        output.status.code(),
+    )
+}
+
+#[test]
+fn test_exit_codes() {
+    let valid = write_code("valid", "a + b * c");
+    let invalid = write_code("invalid", "*a + nb -");
+    let (valid_path, invalid_path) = (valid.to_string_lossy(), invalid.to_string_lossy());
+
+    let codes = [
+        exit_code(&["-c", &valid_path]),
+        exit_code(&["-c", &invalid_path]),
+        exit_code(&["-c", &invalid_path, "--check-only"]),
+        exit_code(&["-b", &invalid_path]),
+        exit_code(&["-c", &valid_path, "--bogus"]),
+        exit_code(&["-c", &valid_path, "--stats", "--check-only"]),
+        exit_code(&["--help"]),
+        exit_code(&["-c", "/nonexistent/lab1.xai"]),
+    ];
+    let _ = std::fs::remove_file(&valid);
+    let _ = std::fs::remove_file(&invalid);
+
+    assert_eq!(
+        codes,
+        [
+            Some(0),
+            Some(2),
+            Some(2),
+            Some(2),
+            Some(64),
+            Some(64),
+            Some(0),
+            Some(1)
+        ]
+    );
+}
+
+#[test]
+fn test_quiet() {
+    let valid = write_code("quiet-valid", "a + b * c");
+    let invalid = write_code("quiet-invalid", "*a + nb -");
+    let (valid_path, invalid_path) = (valid.to_string_lossy(), invalid.to_string_lossy());
+
+    let (valid_stdout, valid_code) = stdout_and_code(&["-c", &valid_path, "--quiet"]);
+    let (invalid_stdout, invalid_code) = stdout_and_code(&["-c", &invalid_path, "-q"]);
+    let (stats_stdout, _) = stdout_and_code(&["-c", &valid_path, "-q", "--stats"]);
+    let (batch_stdout, batch_code) = stdout_and_code(&["-b", &valid_path, "-q"]);
+    let _ = std::fs::remove_file(&valid);
+    let _ = std::fs::remove_file(&invalid);
+
+    assert_eq!(valid_stdout, "");
+    assert_eq!(valid_code, Some(0));
+    assert!(invalid_stdout.contains("Unexpected operator."));
+    assert_eq!(invalid_code, Some(2));
+    // Stats are asked for explicitly, so they are printed anyway
+    assert!(stats_stdout.starts_with("Tokens:\n"));
+    assert_eq!(batch_stdout, "");
+    assert_eq!(batch_code, Some(0));
+}
@@ -0,0 +1,32 @@
+use std::process::{Command, Output};
+
+fn explain(name: &str) -> Output {
+    let Ok(output) = Command::new(env!("CARGO_BIN_EXE_Lab1"))
+        .args(["--explain", name])
+        .output()
+    else {
+        panic!("Failed to run the binary")
+    };
+    output
+}
+
+#[test]
+fn test_explain_known_kind() {
+    let output = explain("UnmatchedParenthesis");
+    let stdout = String::from_utf8_lossy(&output.stdout);
+
+    assert_eq!(output.status.code(), Some(0));
+    assert!(stdout.starts_with("UnmatchedParenthesis\n\nA parenthesis has no pair"));
+    assert!(stdout.contains("Example:\n    (a + b\n"));
+}
+
+#[test]
+fn test_explain_unknown_kind() {
+    let output = explain("MissingOperator");
+    let stderr = String::from_utf8_lossy(&output.stderr);
+
+    assert_eq!(output.status.code(), Some(1));
+    assert!(output.stdout.is_empty());
+    assert!(stderr.contains("Unknown error kind: MissingOperator."));
+    assert!(stderr.contains("UnmatchedParenthesis"));
+}
@@ -1,15 +1,38 @@
-use crate::error::Error;
+use crate::error::{Error, IOError};
 use crate::logger::LogSettings;
 use crate::{compiler, io};
-use clap::Parser;
+use clap::error::ErrorKind;
+use clap::{CommandFactory, Parser, ValueEnum};
+use common::terminal::is_color_allowed;
 use log::LevelFilter;
+use std::io::{BufRead, IsTerminal, Write};
 use std::path::PathBuf;
+use std::process::ExitCode;
+use std::time::Instant;
 
 #[derive(Parser, Debug)]
-#[command(author = "Alex Kovalov", version = "0.0.1")]
+#[command(
+    author = "Alex Kovalov",
+    version = "0.0.1",
+    after_help = "Exit codes: 0 - no syntax errors, 1 - I/O or logger error, 2 - syntax errors found, 64 - invalid arguments."
+)]
 pub struct Cli {
-    #[arg(short = 'c', long, help = "Code file.")]
-    pub code_file: PathBuf,
+    #[arg(
+        short = 'c',
+        long,
+        required_unless_present_any = ["repl", "batch"],
+        conflicts_with_all = ["repl", "batch"],
+        help = "Code file."
+    )]
+    pub code_file: Option<PathBuf>,
+
+    #[arg(
+        short = 'b',
+        long,
+        conflicts_with = "repl",
+        help = "Batch file. Only the syntax of each line is analyzed, as an independent expression."
+    )]
+    pub batch: Option<PathBuf>,
 
     #[arg(
         short = 'o',
@@ -21,6 +44,23 @@ pub struct Cli {
     #[arg(short = 'p', action, long, help = "Pretty print output.")]
     pub pretty: bool,
 
+    #[arg(
+        short = 'f',
+        long,
+        value_enum,
+        conflicts_with = "repl",
+        help = "Output format. Defaults to \"pretty\" in a terminal without NO_COLOR and \"text\" otherwise. JSON has only the syntax errors."
+    )]
+    pub format: Option<OutputFormat>,
+
+    #[arg(
+        short = 'r',
+        action,
+        long,
+        help = "Interactive mode. Reads expressions from stdin until EOF or \":quit\"."
+    )]
+    pub repl: bool,
+
     #[arg(
         short = 'l',
         long,
@@ -28,21 +68,297 @@ pub struct Cli {
         help = "Set the logging level (Error, Warn, Info, Debug, Trace)."
     )]
     pub log_level: LevelFilter,
+
+    #[arg(
+        long,
+        action,
+        conflicts_with = "repl",
+        help = "Only check the syntax, without running the compiler. See exit codes."
+    )]
+    pub check_only: bool,
+
+    #[arg(
+        short = 'q',
+        long,
+        action,
+        conflicts_with_all = ["verbose", "repl"],
+        help = "Print only the errors of the compilation stages if no syntax errors were found. See exit codes."
+    )]
+    pub quiet: bool,
+
+    #[arg(
+        short = 'v',
+        long,
+        action,
+        conflicts_with_all = ["repl", "batch"],
+        help = "Also print the token stream and the compilation time to stderr."
+    )]
+    pub verbose: bool,
+}
+
+/// Process exit codes, so the CLI can be used from scripts.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Status {
+    /// Syntax analysis found no errors.
+    Success = 0,
+    /// I/O or logger error, e.g. the code file does not exist.
+    Failure = 1,
+    /// Syntax analysis found at least one error.
+    SyntaxErrors = 2,
+    /// Invalid arguments, e.g. an unknown flag. Same as `EX_USAGE` of sysexits.
+    Usage = 64,
+}
+
+impl Status {
+    pub fn from_validity(is_valid: bool) -> Self {
+        match is_valid {
+            true => Self::Success,
+            false => Self::SyntaxErrors,
+        }
+    }
+
+    /// Prints the error of clap, which would exit with 2, the code of syntax errors.
+    /// Help and version are errors for clap too, but they are printed successfully.
+    pub fn from_usage_error(error: &clap::Error) -> Self {
+        let _ = error.print();
+        match error.use_stderr() {
+            true => Self::Usage,
+            false => Self::Success,
+        }
+    }
+}
+
+impl From<Status> for ExitCode {
+    fn from(status: Status) -> Self {
+        ExitCode::from(status as u8)
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+pub enum OutputFormat {
+    Json,
+    Text,
+    Pretty,
+}
+
+impl OutputFormat {
+    pub fn resolve(format: Option<OutputFormat>) -> Self {
+        match format {
+            Some(format) => format,
+            None if is_color_allowed() => Self::Pretty,
+            None => Self::Text,
+        }
+    }
 }
 
 impl Cli {
-    pub fn run() -> Result<(), Error> {
-        let context = Cli::parse();
+    pub fn run() -> Result<Status, Error> {
+        let context = match Cli::try_parse() {
+            Ok(context) => context,
+            Err(error) => return Ok(Status::from_usage_error(&error)),
+        };
+
+        // Output file is never colored
+        let format = OutputFormat::resolve(context.format);
+        colored::control::set_override(
+            format == OutputFormat::Pretty && context.output_file.is_none(),
+        );
 
         LogSettings::default()
             .with_output_file(context.output_file)
             .with_level(context.log_level)
             .setup()?;
 
-        let code = io::read_code_file(&context.code_file)?;
+        if context.repl {
+            let stdin = std::io::stdin();
+            let is_interactive = stdin.is_terminal();
+            Repl::new(context.pretty, is_interactive).run(stdin.lock())?;
+            return Ok(Status::Success);
+        }
+
+        if let Some(batch_file) = &context.batch {
+            let lines = io::read_batch_file(batch_file)?;
+            let results = compiler::analyze_batch(&lines);
+            let is_valid = results.iter().all(|line| line.errors.is_empty());
+
+            // Errors are still printed, so scripts can show them
+            if !context.check_only && (!context.quiet || !is_valid) {
+                match format {
+                    OutputFormat::Json => compiler::report_batch_json(&results),
+                    OutputFormat::Text | OutputFormat::Pretty => {
+                        compiler::report_batch(&results)
+                    },
+                }
+            }
+            return Ok(Status::from_validity(is_valid));
+        }
+
+        // Rejected by clap already: `code_file` is required unless `repl` or `batch`
+        // is present
+        let Some(code_file) = &context.code_file else {
+            let error = Cli::command()
+                .error(ErrorKind::MissingRequiredArgument, "no code file given");
+            return Ok(Status::from_usage_error(&error));
+        };
+        let code = io::read_code_file(code_file)?;
+
+        let is_valid = compiler::is_valid(&code);
+
+        // Reports of the stages are logged as warnings and their failures as errors,
+        // so only the failures are left. Syntax errors are printed in full
+        if context.quiet && is_valid {
+            log::set_max_level(context.log_level.min(LevelFilter::Error));
+        }
+        if !context.check_only {
+            // Goes to stderr as the time below, not through the logger to stdout
+            if context.verbose {
+                eprintln!("{}", compiler::tokens_list(&code));
+            }
+
+            let start = Instant::now();
+            match format {
+                OutputFormat::Json => compiler::report_syntax_json(&code),
+                OutputFormat::Text | OutputFormat::Pretty => {
+                    compiler::compile(&code, context.pretty)
+                },
+            }
+            // Goes to stderr, so the report stays the same with and without it
+            if context.verbose {
+                eprintln!(
+                    "Compilation time: {:.3} ms.",
+                    start.elapsed().as_secs_f64() * 1000.0
+                );
+            }
+        }
+
+        Ok(Status::from_validity(is_valid))
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReplMode {
+    Report,
+    Tokens,
+    Ast,
+    Errors,
+}
+
+impl std::fmt::Display for ReplMode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let text = match self {
+            ReplMode::Report => "report",
+            ReplMode::Tokens => "tokens",
+            ReplMode::Ast => "ast",
+            ReplMode::Errors => "errors",
+        };
+        write!(f, "{}", text)
+    }
+}
+
+pub struct Repl {
+    mode: ReplMode,
+    is_pretty: bool,
+    is_interactive: bool,
+}
+
+impl Repl {
+    const PROMPT: &'static str = "> ";
 
-        compiler::compile(&code, context.pretty);
+    pub fn new(is_pretty: bool, is_interactive: bool) -> Self {
+        Self {
+            mode: ReplMode::Report,
+            is_pretty,
+            is_interactive,
+        }
+    }
+
+    pub fn run(mut self, input: impl BufRead) -> Result<(), Error> {
+        self.prompt();
+
+        for line in input.lines() {
+            let line = line
+                .map_err(IOError::FailedToReadInput)
+                .map_err(Error::IO)?;
+            let line = line.trim();
+
+            match line {
+                "" => {},
+                ":quit" => break,
+                ":report" => self.switch(ReplMode::Report),
+                ":tokens" => self.switch(ReplMode::Tokens),
+                ":ast" => self.switch(ReplMode::Ast),
+                ":errors" => self.switch(ReplMode::Errors),
+                command if command.starts_with(':') => log::error!(
+                    "Unknown command \"{}\". Available: :report, :tokens, :ast, :errors, :quit.",
+                    command
+                ),
+                code => self.evaluate(code),
+            }
+
+            self.prompt();
+        }
 
         Ok(())
     }
+
+    fn switch(&mut self, mode: ReplMode) {
+        self.mode = mode;
+        log::warn!("Mode: {}.", mode);
+    }
+
+    fn evaluate(&self, code: &str) {
+        match self.mode {
+            ReplMode::Report => compiler::compile(code, self.is_pretty),
+            ReplMode::Tokens => compiler::report_tokens(code),
+            ReplMode::Ast => compiler::report_ast(code, self.is_pretty),
+            ReplMode::Errors => compiler::report_syntax_errors(code, self.is_pretty),
+        }
+    }
+
+    fn prompt(&self) {
+        // Piped input gets no prompt, so the output stays clean
+        if self.is_interactive {
+            print!("{}", Self::PROMPT);
+            let _ = std::io::stdout().flush();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format_flag() {
+        let Ok(cli) = Cli::try_parse_from(["Lab2", "-c", "main.xai", "--format", "json"])
+        else {
+            panic!("Failed to parse arguments")
+        };
+        assert_eq!(cli.format, Some(OutputFormat::Json));
+
+        let Ok(cli) = Cli::try_parse_from(["Lab2", "-c", "main.xai", "-f", "text"])
+        else {
+            panic!("Failed to parse arguments")
+        };
+        assert_eq!(OutputFormat::resolve(cli.format), OutputFormat::Text);
+
+        assert!(Cli::try_parse_from(["Lab2", "-c", "main.xai", "-f", "xml"]).is_err());
+        assert!(Cli::try_parse_from(["Lab2", "--repl", "-f", "json"]).is_err());
+    }
+
+    #[test]
+    fn test_batch_flag() {
+        let Ok(cli) = Cli::try_parse_from(["Lab2", "-b", "batch.xai"]) else {
+            panic!("Failed to parse arguments")
+        };
+        assert_eq!(cli.batch, Some(PathBuf::from("batch.xai")));
+        assert_eq!(cli.code_file, None);
+
+        assert!(Cli::try_parse_from(["Lab2", "-b", "batch.xai", "-q"]).is_ok());
+        assert!(
+            Cli::try_parse_from(["Lab2", "-b", "batch.xai", "-c", "main.xai"]).is_err()
+        );
+        assert!(Cli::try_parse_from(["Lab2", "-b", "batch.xai", "--repl"]).is_err());
+        assert!(Cli::try_parse_from(["Lab2", "-b", "batch.xai", "-v"]).is_err());
+    }
 }
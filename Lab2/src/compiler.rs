@@ -1,6 +1,9 @@
 use crate::compiler::ast::tree::{AbstractSyntaxTree, AstParser};
 use crate::compiler::lexer::Lexer;
-use crate::compiler::syntax::SyntaxAnalyzer;
+use crate::compiler::syntax::{SyntaxAnalyzer, SyntaxError};
+use colored::Colorize;
+use common::json::json_array;
+use common::text::plural;
 
 pub fn compile(source: &str, is_pretty: bool) {
     // Lexical Analysis
@@ -96,6 +99,133 @@ pub fn compile(source: &str, is_pretty: bool) {
     };
 }
 
+pub fn is_valid(source: &str) -> bool {
+    let tokens = tokenizer::tokenize(source);
+    SyntaxAnalyzer::new(&tokens).analyze().is_empty()
+}
+
+pub fn report_tokens(source: &str) {
+    log::warn!("{}", tokens_list(source));
+}
+
+pub fn tokens_list(source: &str) -> String {
+    tokenizer::tokenize(source)
+        .iter()
+        .map(|token| {
+            format!(
+                "- {} `{}` {}",
+                token.kind,
+                token.display_value(),
+                token.display_position()
+            )
+        })
+        .collect::<Vec<String>>()
+        .join("\n")
+}
+
+/// Only the syntax errors, the other stages are not run.
+pub fn report_syntax_json(source: &str) {
+    let tokens = tokenizer::tokenize(source);
+    syntax::report_json(&SyntaxAnalyzer::new(&tokens).analyze());
+}
+
+pub fn report_syntax_errors(source: &str, is_pretty: bool) {
+    let tokens = tokenizer::tokenize(source);
+    let syntax_errors = SyntaxAnalyzer::new(&tokens).analyze();
+    syntax::report(source, syntax_errors, is_pretty);
+}
+
+pub fn report_ast(source: &str, is_pretty: bool) {
+    let tokens = tokenizer::tokenize(source);
+    let syntax_errors = SyntaxAnalyzer::new(&tokens).analyze();
+    if !syntax_errors.is_empty() {
+        syntax::report(source, syntax_errors, is_pretty);
+        return;
+    }
+
+    let lexemes = match Lexer::new(tokens).run() {
+        Ok(lexemes) => lexemes,
+        Err(error) => {
+            lexer::report_error(error);
+            return;
+        },
+    };
+
+    match AstParser::new(lexemes).parse() {
+        Ok(ast) => log::warn!("{}", ast.pretty_print()),
+        Err(error) => ast::tree::report_error(error),
+    }
+}
+
+pub struct BatchLine {
+    pub number: usize,
+    pub errors: Vec<SyntaxError>,
+}
+
+/// Only the syntax is analyzed, as in `is_valid`. Blank lines are skipped,
+/// but numbering still follows the file.
+pub fn analyze_batch(lines: &[String]) -> Vec<BatchLine> {
+    lines
+        .iter()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .map(|(index, line)| {
+            let tokens = tokenizer::tokenize(line);
+            BatchLine {
+                number: index + 1,
+                errors: SyntaxAnalyzer::new(&tokens).analyze(),
+            }
+        })
+        .collect()
+}
+
+pub fn report_batch(results: &[BatchLine]) {
+    log::warn!("{}", batch_text(results));
+}
+
+/// Array of objects with the `line` number and the `errors` as in `report_syntax_json`.
+pub fn report_batch_json(results: &[BatchLine]) {
+    log::warn!(
+        "{}",
+        json_array(results.iter().map(|line| {
+            format!(
+                "{{\"line\":{},\"errors\":{}}}",
+                line.number,
+                syntax::errors_json(&line.errors)
+            )
+        }))
+    );
+}
+
+fn batch_text(results: &[BatchLine]) -> String {
+    let mut result = String::new();
+
+    for line in results {
+        let status = match line.errors.len() {
+            0 => "OK".bold().green().to_string(),
+            n => format!("{} {}", n, plural("error", n)).red().to_string(),
+        };
+        result.push_str(&format!("line {}: {}\n", line.number, status));
+    }
+
+    let failed = results
+        .iter()
+        .filter(|line| !line.errors.is_empty())
+        .count();
+    result.push_str(&format!(
+        "\n{}: {} {}, {} {}, {} {}.",
+        "Batch result".bold(),
+        results.len(),
+        plural("line", results.len()),
+        (results.len() - failed).to_string().green(),
+        "OK".green(),
+        failed.to_string().red(),
+        "with errors".red()
+    ));
+
+    result
+}
+
 fn compute_run(tree: AbstractSyntaxTree, number: u8) -> Option<AbstractSyntaxTree> {
     // AST Math Optimization
     let ast_result = tree.compute();
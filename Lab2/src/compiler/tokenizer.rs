@@ -41,6 +41,7 @@ impl Token {
             TokenType::Pipe => "|",
             TokenType::Dot => ".",
             TokenType::Comma => ",",
+            TokenType::Backslash => "\\",
             TokenType::QuotationMark => "\"",
             TokenType::Space => " ",
             TokenType::Tab => "\\t",
@@ -52,7 +53,7 @@ impl Token {
     }
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, Display)]
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Display)]
 pub enum TokenType {
     Identifier,
     Number,
@@ -75,6 +76,9 @@ pub enum TokenType {
     Dot,
     Comma,
 
+    // Only valid inside strings, e.g. a Windows path is an error
+    Backslash,
+
     QuotationMark,
 
     Space,
@@ -171,6 +175,7 @@ pub fn tokenize(input: &str) -> Vec<Token> {
                 in_string = !in_string;
                 token!(TokenType::QuotationMark, index..index + 1)
             },
+            '\\' => token!(TokenType::Backslash, index..index + 1),
             '\n' => token!(TokenType::NewLine, index..index + 1),
             c if c.eq(&'\t') => token!(TokenType::Tab, index..index + 1),
             c if c.is_whitespace() => {
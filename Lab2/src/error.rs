@@ -17,6 +17,9 @@ pub enum IOError {
 
     #[error("Failed to read code file. {0}")]
     FailedToReadCodeFile(io::Error),
+
+    #[error("Failed to read input. {0}")]
+    FailedToReadInput(io::Error),
 }
 
 #[derive(Error, Debug)]
@@ -10,3 +10,9 @@ pub fn read_code_file(path: &std::path::PathBuf) -> Result<String, Error> {
         Error::IO(error)
     })
 }
+
+pub fn read_batch_file(path: &std::path::PathBuf) -> Result<Vec<String>, Error> {
+    let content = read_code_file(path)?;
+
+    Ok(content.lines().map(str::to_string).collect())
+}
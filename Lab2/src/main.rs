@@ -1,11 +1,16 @@
-use crate::cli::Cli;
+use crate::cli::{Cli, Status};
 use colored::Colorize;
+use std::process::ExitCode;
 
-fn main() {
+fn main() -> ExitCode {
     let run_result = Cli::run();
 
-    if let Err(e) = run_result {
-        eprintln!("{}. {e}", "Error".red().bold());
+    match run_result {
+        Ok(status) => status.into(),
+        Err(e) => {
+            eprintln!("{}. {e}", "Error".red().bold());
+            Status::Failure.into()
+        },
     }
 }
 
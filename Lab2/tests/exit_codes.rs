@@ -0,0 +1,139 @@
+use std::path::PathBuf;
+use std::process::Command;
+
+fn write_code(name: &str, code: &str) -> PathBuf {
+    let path =
+        std::env::temp_dir().join(format!("lab2-{}-{}.xai", name, std::process::id()));
+    let written = std::fs::write(&path, code);
+    assert!(written.is_ok());
+    path
+}
+
+fn exit_code(args: &[&str]) -> Option<i32> {
+    let Ok(output) = Command::new(env!("CARGO_BIN_EXE_Lab2")).args(args).output() else {
+        panic!("Failed to run the binary")
+    };
+    output.status.code()
+}
+
+fn stdout_and_code(args: &[&str]) -> (String, Option<i32>) {
+    let Ok(output) = Command::new(env!("CARGO_BIN_EXE_Lab2"))
+        .args(args)
+        .env("NO_COLOR", "1")
+        .output()
+    else {
+        panic!("Failed to run the binary")
+    };
+    (
+        String::from_utf8_lossy(&output.stdout).to_string(),
+        output.status.code(),
+    )
+}
+
+#[test]
+fn test_exit_codes() {
+    let valid = write_code("valid", "a + b * c");
+    let invalid = write_code("invalid", "*a + nb -");
+    let (valid_path, invalid_path) = (valid.to_string_lossy(), invalid.to_string_lossy());
+
+    let codes = [
+        exit_code(&["-c", &valid_path]),
+        exit_code(&["-c", &invalid_path]),
+        exit_code(&["-c", &invalid_path, "--check-only"]),
+        exit_code(&["-c", &valid_path, "--bogus"]),
+        exit_code(&["--help"]),
+        exit_code(&["-c", "/nonexistent/lab2.xai"]),
+    ];
+    let _ = std::fs::remove_file(&valid);
+    let _ = std::fs::remove_file(&invalid);
+
+    assert_eq!(
+        codes,
+        [Some(0), Some(2), Some(2), Some(64), Some(0), Some(1)]
+    );
+}
+
+#[test]
+fn test_quiet() {
+    let valid = write_code("quiet-valid", "a + b * c");
+    let invalid = write_code("quiet-invalid", "*a + nb -");
+    let (valid_path, invalid_path) = (valid.to_string_lossy(), invalid.to_string_lossy());
+
+    // No syntax errors, but the tree cannot be built
+    let failing = write_code("quiet-failing", "a + \"s\"");
+    let failing_path = failing.to_string_lossy();
+
+    let (valid_stdout, valid_code) = stdout_and_code(&["-c", &valid_path, "--quiet"]);
+    let (invalid_stdout, invalid_code) = stdout_and_code(&["-c", &invalid_path, "-q"]);
+    let (failing_stdout, failing_code) = stdout_and_code(&["-c", &failing_path, "-q"]);
+    let _ = std::fs::remove_file(&valid);
+    let _ = std::fs::remove_file(&invalid);
+    let _ = std::fs::remove_file(&failing);
+
+    assert_eq!(valid_stdout, "");
+    assert_eq!(valid_code, Some(0));
+    assert!(invalid_stdout.contains("Unexpected operator."));
+    assert_eq!(invalid_code, Some(2));
+    assert_eq!(
+        failing_stdout,
+        "AST error: String literal \"s\" outside function call.\n"
+    );
+    assert_eq!(failing_code, Some(0));
+}
+
+#[test]
+fn test_batch() {
+    let mixed = write_code("batch-mixed", "a + b\n*a + nb -\n\nsin(x)\n(a + b\n");
+    let valid = write_code("batch-valid", "a + b\n\nsin(x)\n");
+    let (mixed_path, valid_path) = (mixed.to_string_lossy(), valid.to_string_lossy());
+
+    let (mixed_stdout, mixed_code) = stdout_and_code(&["-b", &mixed_path]);
+    let (json_stdout, _) = stdout_and_code(&["-b", &valid_path, "-f", "json"]);
+    let (quiet_stdout, quiet_code) = stdout_and_code(&["-b", &valid_path, "-q"]);
+    let check_code = exit_code(&["-b", &mixed_path, "--check-only"]);
+    let _ = std::fs::remove_file(&mixed);
+    let _ = std::fs::remove_file(&valid);
+
+    // Errors of one line do not stop the others, blank lines are skipped
+    assert_eq!(
+        mixed_stdout,
+        concat!(
+            "line 1: OK\n",
+            "line 2: 2 errors\n",
+            "line 4: OK\n",
+            "line 5: 1 error\n",
+            "\n",
+            "Batch result: 4 lines, 2 OK, 2 with errors.\n",
+        )
+    );
+    assert_eq!(mixed_code, Some(2));
+    assert_eq!(
+        json_stdout,
+        "[{\"line\":1,\"errors\":[]},{\"line\":3,\"errors\":[]}]\n"
+    );
+    assert_eq!(quiet_stdout, "");
+    assert_eq!(quiet_code, Some(0));
+    assert_eq!(check_code, Some(2));
+}
+
+#[test]
+fn test_verbose() {
+    let code = write_code("verbose", "a + b");
+    let path = code.to_string_lossy();
+
+    let (plain_stdout, _) = stdout_and_code(&["-c", &path]);
+    let output = Command::new(env!("CARGO_BIN_EXE_Lab2"))
+        .args(["-c", &path, "--verbose"])
+        .env("NO_COLOR", "1")
+        .output();
+    let _ = std::fs::remove_file(&code);
+    let Ok(output) = output else {
+        panic!("Failed to run the binary")
+    };
+
+    // Tokens and time are on stderr only, so the report is the same
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert_eq!(String::from_utf8_lossy(&output.stdout), plain_stdout);
+    assert!(stderr.starts_with("- Identifier `a` "));
+    assert!(stderr.contains("\nCompilation time: "));
+}
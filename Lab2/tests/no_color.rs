@@ -0,0 +1,47 @@
+use std::path::PathBuf;
+use std::process::Command;
+
+fn write_code(name: &str, code: &str) -> PathBuf {
+    let path = std::env::temp_dir().join(format!(
+        "lab2-no-color-{}-{}.xai",
+        name,
+        std::process::id()
+    ));
+    let written = std::fs::write(&path, code);
+    assert!(written.is_ok());
+    path
+}
+
+// Output of the binary with `NO_COLOR`. `CLICOLOR_FORCE` would turn colors on otherwise
+fn output(args: &[&str]) -> String {
+    let Ok(output) = Command::new(env!("CARGO_BIN_EXE_Lab2"))
+        .args(args)
+        .env("NO_COLOR", "1")
+        .env("CLICOLOR_FORCE", "1")
+        .output()
+    else {
+        panic!("Failed to run the binary")
+    };
+    format!(
+        "{}{}",
+        String::from_utf8_lossy(&output.stdout),
+        String::from_utf8_lossy(&output.stderr)
+    )
+}
+
+#[test]
+fn test_no_color() {
+    let invalid = write_code("invalid", "*a + nb -");
+    let invalid_path = invalid.to_string_lossy();
+
+    let outputs = [
+        output(&["-c", &invalid_path]),
+        output(&["-c", "/nonexistent/lab2.xai"]),
+    ];
+    let _ = std::fs::remove_file(&invalid);
+
+    for output in outputs {
+        assert!(!output.is_empty());
+        assert!(!output.contains('\x1b'), "Escape sequence in: {:?}", output);
+    }
+}
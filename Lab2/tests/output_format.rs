@@ -0,0 +1,56 @@
+use std::process::Command;
+
+// Code file for every case, removed before the assertions
+fn stdout_of(name: &str, code: &str, args: &[&str]) -> String {
+    let path = std::env::temp_dir().join(format!(
+        "lab2-format-{}-{}.xai",
+        name,
+        std::process::id()
+    ));
+    assert!(std::fs::write(&path, code).is_ok());
+
+    let output = Command::new(env!("CARGO_BIN_EXE_Lab2"))
+        .args(args)
+        .arg(&path)
+        .env("NO_COLOR", "1")
+        .output();
+    let _ = std::fs::remove_file(&path);
+    let Ok(output) = output else {
+        panic!("Failed to run the binary")
+    };
+    String::from_utf8_lossy(&output.stdout).to_string()
+}
+
+#[test]
+fn test_text_and_json() {
+    let code = "*a + nb -";
+
+    assert_eq!(
+        stdout_of("text", code, &["--format", "text", "-c"]),
+        concat!(
+            "Lexical & syntax analysis: Found 2 errors.\n",
+            "Code:\n",
+            "*a + nb -\n",
+            "Unexpected operator.           [Position: 1]\n",
+            "Unexpected end of expression.  [Position: 9]\n",
+        )
+    );
+    assert_eq!(
+        stdout_of("json", code, &["--format", "json", "-c"]),
+        concat!(
+            r#"[{"kind":"UnexpectedOperator","message":"Unexpected operator.","start":0,"end":1},"#,
+            r#"{"kind":"UnexpectedEndOfExpression","message":"Unexpected end of expression.","start":8,"end":9}]"#,
+            "\n",
+        )
+    );
+    // Valid code has no errors, and the later stages are not run
+    assert_eq!(
+        stdout_of("json-valid", "a + b", &["--format", "json", "-c"]),
+        "[]\n"
+    );
+    // Not a terminal, so the default is plain text
+    assert!(
+        stdout_of("default", "(a + b", &["-c"])
+            .starts_with("Lexical & syntax analysis: Found 1 error.\n")
+    );
+}
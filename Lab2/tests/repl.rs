@@ -0,0 +1,52 @@
+use std::io::Write;
+use std::process::{Command, Stdio};
+
+#[test]
+fn test_repl() {
+    let Ok(mut child) = Command::new(env!("CARGO_BIN_EXE_Lab2"))
+        .arg("--repl")
+        .env("NO_COLOR", "1")
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()
+    else {
+        panic!("Failed to start REPL")
+    };
+
+    let Some(mut stdin) = child.stdin.take() else {
+        panic!("Failed to open REPL input")
+    };
+    let written = stdin.write_all(b"a + b\n:errors\n*a\n:quit\nc\n");
+    assert!(written.is_ok());
+    drop(stdin);
+
+    let Ok(output) = child.wait_with_output() else {
+        panic!("Failed to wait for REPL")
+    };
+    assert!(output.status.success());
+
+    let Ok(stdout) = String::from_utf8(output.stdout) else {
+        panic!("REPL output is not valid UTF-8")
+    };
+    let expected = concat!(
+        "Lexical & syntax analysis: OK!\n",
+        "Code:\n",
+        "a + b\n",
+        "Lexer successfully produced 3 lexemes.\n",
+        "Abstract-Syntax Tree generation success.\n",
+        "Computing constants of Abstract-Syntax Tree (Run #1) success.\n",
+        "Transformed Abstract-Syntax Tree generation success.\n",
+        "Computing constants of Abstract-Syntax Tree (Run #2) success.\n",
+        "Balanced Abstract-Syntax Tree generation success.\n",
+        "Computing constants of Abstract-Syntax Tree (Run #3) success.\n",
+        "Folding Abstract-Syntax Tree success.\n",
+        "Computing constants of Abstract-Syntax Tree (Run #4) success.\n",
+        "Mode: errors.\n",
+        "Lexical & syntax analysis: Found 1 error.\n",
+        "Code:\n",
+        "*a\n",
+        "Unexpected operator.  [Position: 1]\n",
+    );
+    // Everything after ":quit" is ignored
+    assert_eq!(stdout, expected);
+}
@@ -0,0 +1,43 @@
+use clap::Parser;
+use std::path::PathBuf;
+
+/// Unknown arguments are rejected, the UI is started otherwise.
+#[derive(Parser, Debug)]
+#[command(author = "Alex Kovalov", version = "0.0.1")]
+pub struct Cli {
+    #[arg(
+        long,
+        value_name = "PATH",
+        help = "Config file to load and save instead of config.toml. It must exist."
+    )]
+    pub config: Option<PathBuf>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_config_argument() {
+        let parse = |args: &[&str]| {
+            Cli::try_parse_from(std::iter::once("Lab3-4").chain(args.iter().copied()))
+        };
+
+        let Ok(cli) = parse(&["--config", "a.toml"]) else {
+            panic!("Failed to parse --config")
+        };
+        assert_eq!(cli.config, Some(PathBuf::from("a.toml")));
+        let Ok(cli) = parse(&["--config=b.toml"]) else {
+            panic!("Failed to parse --config=")
+        };
+        assert_eq!(cli.config, Some(PathBuf::from("b.toml")));
+        let Ok(cli) = parse(&[]) else {
+            panic!("Failed to parse no arguments")
+        };
+        assert_eq!(cli.config, None);
+
+        assert!(parse(&["--config"]).is_err());
+        assert!(parse(&["--unknown"]).is_err());
+        assert!(parse(&["code.txt"]).is_err());
+    }
+}
@@ -100,6 +100,31 @@ impl AbstractSyntaxTree {
 }
 
 impl Reporter {
+    pub fn equivalent_forms(
+        &self, source: &AbstractSyntaxTree, forms: &[String],
+    ) -> String {
+        let mut buffer = StringBuffer::default();
+
+        let labeled = [
+            ("Canonical form:", source.to_canonical_string()),
+            ("Minimal parentheses:", source.to_pretty_string()),
+            ("Full parentheses:", source.to_fully_parenthesized_string()),
+            ("Value:", "not a constant".to_string()),
+        ];
+        for (label, form) in labeled {
+            buffer.add_line(format!("{:22}{}", label, form));
+        }
+        buffer.add_line(String::new());
+
+        buffer.add(self.finding_equivalent_form(forms));
+
+        buffer.get()
+    }
+
+    pub fn constant_value(&self, value: f64) -> String {
+        format!("{:22}{:.2}\n", "Value:", value)
+    }
+
     pub fn finding_equivalent_form(&self, forms: &[String]) -> String {
         let mut buffer = StringBuffer::default();
 
@@ -115,3 +140,34 @@ impl Reporter {
         buffer.get()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::compiler::context::CompilerContext;
+
+    fn report(code: &str) -> String {
+        let context = CompilerContext {
+            code: code.to_string(),
+            pretty_output: false,
+        };
+        context.equivalent_forms_report()
+    }
+
+    #[test]
+    fn test_labeled_forms() {
+        let actual = report("a+(b)");
+        assert!(actual.starts_with(
+            "Canonical form:       (a + b)\n\
+             Minimal parentheses:  a + b\n\
+             Full parentheses:     (a + b)\n\
+             Value:                not a constant\n\
+             \n\
+             Found "
+        ));
+    }
+
+    #[test]
+    fn test_constant_value() {
+        assert_eq!(report("2+3"), "Value:                5.00\n");
+    }
+}
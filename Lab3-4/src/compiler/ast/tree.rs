@@ -254,6 +254,85 @@ impl AbstractSyntaxTree {
             },
         }
     }
+
+    /// Creates a string representation with every operation in parentheses,
+    /// so the order of evaluation is seen without the precedence rules.
+    pub fn to_fully_parenthesized_string(&self) -> String {
+        Self::node_to_parenthesized_string(&self.peek)
+    }
+
+    fn node_to_parenthesized_string(node: &AstNode) -> String {
+        match node {
+            AstNode::Number(_) | AstNode::Identifier(_) | AstNode::StringLiteral(_) => {
+                Self::node_to_pretty_string(node, 0)
+            },
+            AstNode::FunctionCall { name, arguments } => {
+                let args = arguments
+                    .iter()
+                    .map(Self::node_to_parenthesized_string)
+                    .collect::<Vec<String>>()
+                    .join(", ");
+                format!("{}({})", name, args)
+            },
+            AstNode::ArrayAccess {
+                identifier,
+                indices,
+            } => {
+                let idx = indices
+                    .iter()
+                    .map(Self::node_to_parenthesized_string)
+                    .map(|s| format!("[{}]", s))
+                    .collect::<String>();
+                format!("{}{}", identifier, idx)
+            },
+            AstNode::UnaryOperation {
+                operation,
+                expression,
+            } => format!(
+                "{}{}",
+                operation,
+                Self::node_to_parenthesized_string(expression)
+            ),
+            AstNode::BinaryOperation {
+                operation,
+                left,
+                right,
+            } => match (operation, left.as_ref(), right.as_ref()) {
+                // Same rewrites as in `to_pretty_string`:
+                // A + (-B) => A - B, (-A) + B => B - A
+                (
+                    BinaryOperationKind::Plus,
+                    _,
+                    AstNode::UnaryOperation {
+                        operation: UnaryOperationKind::Minus,
+                        expression: inner_right,
+                    },
+                ) => format!(
+                    "({} - {})",
+                    Self::node_to_parenthesized_string(left),
+                    Self::node_to_parenthesized_string(inner_right)
+                ),
+                (
+                    BinaryOperationKind::Plus,
+                    AstNode::UnaryOperation {
+                        operation: UnaryOperationKind::Minus,
+                        expression: inner_left,
+                    },
+                    _,
+                ) => format!(
+                    "({} - {})",
+                    Self::node_to_parenthesized_string(right),
+                    Self::node_to_parenthesized_string(inner_left)
+                ),
+                _ => format!(
+                    "({} {} {})",
+                    Self::node_to_parenthesized_string(left),
+                    operation,
+                    Self::node_to_parenthesized_string(right)
+                ),
+            },
+        }
+    }
 }
 
 impl BinaryOperationKind {
@@ -1,4 +1,4 @@
-use crate::compiler::ast::tree::{AbstractSyntaxTree, AstError, AstParser};
+use crate::compiler::ast::tree::{AbstractSyntaxTree, AstError, AstNode, AstParser};
 use crate::compiler::lexer::{Lexeme, Lexer, LexerError};
 use crate::compiler::reports::Reporter;
 use crate::compiler::syntax::{SyntaxAnalyzer, SyntaxError};
@@ -215,8 +215,22 @@ impl CompilerContext {
     }
 
     pub fn equivalent_forms_report(&self) -> String {
+        let source = match self.create_ast() {
+            Ok(Ok(value)) => value,
+            Ok(ast_creation_result) => return Reporter.tree_build(&ast_creation_result),
+            Err(error) => return error,
+        };
+
+        // Constants are solved by the first computing, there are no other forms
+        if let Ok(AbstractSyntaxTree {
+            peek: AstNode::Number(value),
+        }) = source.clone().compute()
+        {
+            return Reporter.constant_value(value);
+        }
+
         match self.find_equivalent_forms() {
-            Ok(forms) => Reporter.finding_equivalent_form(&forms),
+            Ok(forms) => Reporter.equivalent_forms(&source, &forms),
             Err(error) => error,
         }
     }
@@ -137,6 +137,7 @@ impl Lexer {
                 | TokenType::Space
                 | TokenType::Tab
                 | TokenType::NewLine
+                | TokenType::Backslash
                 | TokenType::Unknown => {
                     return Err(Error::NotExpectedToken(token.clone()));
                 },
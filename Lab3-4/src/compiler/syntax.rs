@@ -22,6 +22,27 @@ pub struct SyntaxError {
     pub kind: SyntaxErrorKind,
 }
 
+/// Errors are ordered by the start of the token, then by its end, then by the kind,
+/// so the report does not depend on the order of the checks.
+impl Ord for SyntaxError {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        self.token
+            .position
+            .start
+            .cmp(&other.token.position.start)
+            .then(self.token.position.end.cmp(&other.token.position.end))
+            .then(self.kind.ordinal().cmp(&other.kind.ordinal()))
+            .then_with(|| self.token.kind.cmp(&other.token.kind))
+            .then_with(|| self.token.value.cmp(&other.token.value))
+    }
+}
+
+impl PartialOrd for SyntaxError {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
 macro_rules! syntax_error {
     ($kind:ident, $token:expr) => {
         SyntaxError {
@@ -34,6 +55,7 @@ macro_rules! syntax_error {
 #[derive(Debug, PartialEq, Eq)]
 pub enum SyntaxErrorKind {
     EmptyBrackets,
+    EmptyExpression,
     EmptyParentheses,
     InvalidBinaryLiteral,
     InvalidFloat,
@@ -41,6 +63,7 @@ pub enum SyntaxErrorKind {
     InvalidHexLiteral,
     InvalidVariableName,
     MissingArgument,
+    UnexpectedBackslash,
     UnexpectedBrackets,
     UnexpectedComma,
     UnexpectedDot,
@@ -55,10 +78,42 @@ pub enum SyntaxErrorKind {
     UnmatchedQuotationMark,
 }
 
+impl SyntaxErrorKind {
+    /// Last tie-break of the `SyntaxError` ordering. The ranks are the same
+    /// in every lab, so the errors of one code are reported in the same order.
+    fn ordinal(&self) -> u8 {
+        match self {
+            SyntaxErrorKind::EmptyBrackets => 3,
+            SyntaxErrorKind::EmptyExpression => 4,
+            SyntaxErrorKind::EmptyParentheses => 5,
+            SyntaxErrorKind::InvalidBinaryLiteral => 6,
+            SyntaxErrorKind::InvalidFloat => 7,
+            SyntaxErrorKind::InvalidFunctionName => 8,
+            SyntaxErrorKind::InvalidHexLiteral => 9,
+            SyntaxErrorKind::InvalidVariableName => 10,
+            SyntaxErrorKind::MissingArgument => 12,
+            SyntaxErrorKind::UnexpectedBackslash => 15,
+            SyntaxErrorKind::UnexpectedBrackets => 16,
+            SyntaxErrorKind::UnexpectedComma => 17,
+            SyntaxErrorKind::UnexpectedDot => 18,
+            SyntaxErrorKind::UnexpectedEndOfExpression => 19,
+            SyntaxErrorKind::UnexpectedNewLine => 20,
+            SyntaxErrorKind::UnexpectedOperand => 21,
+            SyntaxErrorKind::UnexpectedOperator => 22,
+            SyntaxErrorKind::UnexpectedParenthesis => 23,
+            SyntaxErrorKind::UnknownToken => 26,
+            SyntaxErrorKind::UnmatchedBrackets => 27,
+            SyntaxErrorKind::UnmatchedParenthesis => 29,
+            SyntaxErrorKind::UnmatchedQuotationMark => 31,
+        }
+    }
+}
+
 impl std::fmt::Display for SyntaxError {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         let text = match self.kind {
             SyntaxErrorKind::EmptyBrackets => "Empty array access.",
+            SyntaxErrorKind::EmptyExpression => "Empty expression.",
             SyntaxErrorKind::EmptyParentheses => "Empty function or grouping.",
             SyntaxErrorKind::InvalidBinaryLiteral => match &self.token.value {
                 None => "Invalid binary literal.",
@@ -75,6 +130,9 @@ impl std::fmt::Display for SyntaxError {
             },
             SyntaxErrorKind::InvalidVariableName => "Invalid variable name.",
             SyntaxErrorKind::MissingArgument => "Missing function argument.",
+            SyntaxErrorKind::UnexpectedBackslash => {
+                "Unexpected backslash. It is only valid inside strings."
+            },
             SyntaxErrorKind::UnexpectedBrackets => "Unexpected brackets.",
             SyntaxErrorKind::UnexpectedComma => "Unexpected comma.",
             SyntaxErrorKind::UnexpectedDot => "Unexpected dot.",
@@ -125,6 +183,23 @@ impl SyntaxAnalyzer {
             in_string: false,
         };
 
+        // Nothing but whitespace, so there is nothing to analyze
+        if self.tokens.iter().all(|token| {
+            matches!(
+                token.kind,
+                TokenType::Space | TokenType::Tab | TokenType::NewLine
+            )
+        }) {
+            return vec![SyntaxError {
+                token: Token {
+                    kind: TokenType::Space,
+                    position: 0..1,
+                    value: None,
+                },
+                kind: SyntaxErrorKind::EmptyExpression,
+            }];
+        }
+
         while self.current_index < self.tokens.len() {
             let token = &self.tokens[self.current_index];
 
@@ -134,6 +209,16 @@ impl SyntaxAnalyzer {
                     if !self.status.in_string {
                         // Start mark. We're expecting an operand here.
                         if !self.status.expect_operand {
+                            let is_closed = self.tokens[self.current_index + 1..]
+                                .iter()
+                                .any(|next| next.kind == TokenType::QuotationMark);
+                            if !is_closed {
+                                // Stray mark: nothing closes it, so it doesn't open a string
+                                self.errors
+                                    .push(syntax_error!(UnmatchedQuotationMark, token));
+                                self.current_index += 1;
+                                continue;
+                            }
                             // If we didn't expect an operand, it's an error.
                             self.errors.push(syntax_error!(UnexpectedOperator, token));
                         }
@@ -452,8 +537,23 @@ impl SyntaxAnalyzer {
 
                     // Inside parentheses comma need to be after operand and before new operand
                     if self.status.expect_operand {
-                        // Empty argument
-                        self.errors.push(syntax_error!(UnexpectedComma, token));
+                        match self.peek_previous() {
+                            // Empty argument in the middle: `f(a, , b)`
+                            Some(previous) if previous.kind == TokenType::Comma => {
+                                self.errors.push(syntax_error!(MissingArgument, token));
+                            },
+                            // Empty first argument: `f(, b)`
+                            _ => self.errors.push(syntax_error!(UnexpectedComma, token)),
+                        }
+
+                        // Empty arguments are reported, the closing delimiter is fine: `f(,)`
+                        if let Some(next) = self.peek_next()
+                            && matches!(next.kind, TokenType::RightParenthesis)
+                        {
+                            self.status.expect_operand = false;
+                            self.status.expect_operator = true;
+                        }
+
                         self.current_index += 1;
                         continue;
                     }
@@ -481,6 +581,15 @@ impl SyntaxAnalyzer {
                     self.current_index += 1;
                     continue;
                 },
+                TokenType::Backslash => {
+                    // Outside of strings, e.g. a Windows path.
+                    // Continuing, but considering that operator was read
+                    self.errors.push(syntax_error!(UnexpectedBackslash, token));
+                    self.status.expect_operand = true;
+                    self.status.expect_operator = false;
+                    self.current_index += 1;
+                    continue;
+                },
                 TokenType::NewLine => {
                     // Unexpected newline is error, if we're not in string
                     if !self.status.in_string {
@@ -519,8 +628,7 @@ impl SyntaxAnalyzer {
                 .push(syntax_error!(UnmatchedQuotationMark, token));
         }
 
-        self.errors
-            .sort_by(|a, b| a.token.position.start.cmp(&b.token.position.start));
+        self.errors.sort();
 
         self.errors
     }
@@ -607,15 +715,25 @@ impl Reporter {
     }
 
     fn format_errors(&self, buffer: &mut StringBuffer, syntax_errors: &[SyntaxError]) {
+        let width = Self::message_width(syntax_errors);
         for error in syntax_errors {
             let error = format!(
-                "{:50} {}",
+                "{:width$} {}",
                 error.to_string(),
                 error.token.display_position()
             );
             buffer.add_line(error);
         }
     }
+
+    // Positions are lined up right after the longest message
+    fn message_width(syntax_errors: &[SyntaxError]) -> usize {
+        syntax_errors
+            .iter()
+            .map(|error| error.to_string().chars().count())
+            .max()
+            .unwrap_or_default()
+    }
 }
 
 #[cfg(test)]
@@ -684,8 +802,8 @@ mod tests {
             test_error!(InvalidFunctionName, TokenType::Number, 44, "8".to_string()),
             test_error!(UnexpectedOperator, TokenType::Minus, 46),
             test_error!(UnexpectedParenthesis, TokenType::RightParenthesis, 47),
-            test_error!(UnexpectedOperator, TokenType::Asterisk, 49),
             test_error!(UnexpectedEndOfExpression, TokenType::Asterisk, 49),
+            test_error!(UnexpectedOperator, TokenType::Asterisk, 49),
         ];
         assert_eq!(errors_actual, errors_expected);
     }
@@ -1091,8 +1209,8 @@ mod tests {
             test_error!(UnexpectedOperand, TokenType::Number, 81, "1".to_string()),
             test_error!(UnexpectedParenthesis, TokenType::RightParenthesis, 87),
             test_error!(UnmatchedParenthesis, TokenType::RightParenthesis, 87),
-            test_error!(UnexpectedOperator, TokenType::Slash, 88),
             test_error!(UnexpectedEndOfExpression, TokenType::Slash, 88),
+            test_error!(UnexpectedOperator, TokenType::Slash, 88),
         ];
         assert_eq!(errors_actual, errors_expected);
     }
@@ -1159,4 +1277,136 @@ mod tests {
         ];
         assert_eq!(errors_actual, errors_expected);
     }
+
+    #[test]
+    fn test_syntax_19() {
+        let code = "\"unterminated";
+        let errors_actual: Vec<SyntaxError> =
+            SyntaxAnalyzer::new(&Tokenizer::process(code)).analyze();
+        let errors_expected: Vec<SyntaxError> = vec![test_error!(
+            UnmatchedQuotationMark,
+            TokenType::QuotationMark,
+            0
+        )];
+        assert_eq!(errors_actual, errors_expected);
+    }
+
+    #[test]
+    fn test_syntax_20() {
+        let code = "foo\" bar";
+        let errors_actual: Vec<SyntaxError> =
+            SyntaxAnalyzer::new(&Tokenizer::process(code)).analyze();
+        let errors_expected: Vec<SyntaxError> = vec![
+            test_error!(UnmatchedQuotationMark, TokenType::QuotationMark, 3),
+            test_error!(
+                UnexpectedOperand,
+                TokenType::Identifier,
+                5..8,
+                "bar".to_string()
+            ),
+        ];
+        assert_eq!(errors_actual, errors_expected);
+    }
+
+    #[test]
+    fn test_syntax_empty() {
+        for code in ["", "   ", "\n"] {
+            let errors_actual: Vec<SyntaxError> =
+                SyntaxAnalyzer::new(&Tokenizer::process(code)).analyze();
+            let errors_expected: Vec<SyntaxError> =
+                vec![test_error!(EmptyExpression, TokenType::Space, 0)];
+            assert_eq!(errors_actual, errors_expected, "Code: {:?}", code);
+            assert_eq!(errors_actual[0].to_string(), "Empty expression.");
+        }
+    }
+
+    #[test]
+    fn test_syntax_backslash() {
+        let analyze = |code: &str| -> Vec<SyntaxError> {
+            SyntaxAnalyzer::new(&Tokenizer::process(code)).analyze()
+        };
+
+        let errors = analyze("a \\ b");
+        assert_eq!(
+            errors,
+            vec![test_error!(UnexpectedBackslash, TokenType::Backslash, 2)]
+        );
+
+        // Strings are not analyzed
+        assert_eq!(analyze("\"a\\n\""), vec![]);
+    }
+
+    #[test]
+    fn test_syntax_empty_arguments() {
+        let analyze = |code: &str| -> Vec<SyntaxError> {
+            SyntaxAnalyzer::new(&Tokenizer::process(code)).analyze()
+        };
+
+        assert_eq!(
+            analyze("f(a,,b)"),
+            vec![test_error!(MissingArgument, TokenType::Comma, 4)]
+        );
+        assert_eq!(
+            analyze("f(,)"),
+            vec![test_error!(UnexpectedComma, TokenType::Comma, 2)]
+        );
+        assert_eq!(
+            analyze("f(a,)"),
+            vec![test_error!(MissingArgument, TokenType::Comma, 3)]
+        );
+    }
+
+    #[test]
+    fn test_syntax_error_order() {
+        let wide = SyntaxError {
+            token: Token {
+                kind: TokenType::Identifier,
+                position: 4..7,
+                value: Some("abc".to_string()),
+            },
+            kind: SyntaxErrorKind::InvalidVariableName,
+        };
+        let mut errors_actual = [
+            test_error!(UnmatchedParenthesis, TokenType::RightParenthesis, 4),
+            wide,
+            test_error!(UnexpectedParenthesis, TokenType::RightParenthesis, 4),
+            test_error!(UnexpectedOperator, TokenType::Plus, 2),
+        ];
+        errors_actual.sort();
+
+        let kinds: Vec<&SyntaxErrorKind> =
+            errors_actual.iter().map(|error| &error.kind).collect();
+        assert_eq!(
+            kinds,
+            vec![
+                &SyntaxErrorKind::UnexpectedOperator,
+                &SyntaxErrorKind::UnexpectedParenthesis,
+                &SyntaxErrorKind::UnmatchedParenthesis,
+                &SyntaxErrorKind::InvalidVariableName,
+            ]
+        );
+
+        // Same span and kind: the token decides, as in the equality
+        let first = test_error!(UnknownToken, TokenType::Unknown, 0, "#".to_string());
+        let second = test_error!(UnknownToken, TokenType::Unknown, 0, "$".to_string());
+        assert_eq!(first.cmp(&second), std::cmp::Ordering::Less);
+        let dot = test_error!(UnexpectedOperand, TokenType::Dot, 0);
+        let comma = test_error!(UnexpectedOperand, TokenType::Comma, 0);
+        assert_eq!(dot.cmp(&comma), dot.token.kind.cmp(&comma.token.kind));
+        assert_ne!(dot.cmp(&comma), std::cmp::Ordering::Equal);
+    }
+
+    #[test]
+    fn test_message_width() {
+        let errors = SyntaxAnalyzer::new(&Tokenizer::process("*a + (b")).analyze();
+        let report = Reporter.syntax("*a + (b", false, &errors);
+        let lines: Vec<&str> = report.lines().collect();
+        assert_eq!(
+            lines[2..],
+            [
+                "Unexpected operator.   [Position: 1]",
+                "Unmatched parenthesis. [Position: 6]",
+            ]
+        );
+    }
 }
@@ -42,6 +42,7 @@ impl Token {
             TokenType::Pipe => "|",
             TokenType::Dot => ".",
             TokenType::Comma => ",",
+            TokenType::Backslash => "\\",
             TokenType::QuotationMark => "\"",
             TokenType::Space => " ",
             TokenType::Tab => "\\t",
@@ -53,7 +54,7 @@ impl Token {
     }
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, Display)]
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Display)]
 pub enum TokenType {
     Identifier,
     Number,
@@ -76,6 +77,9 @@ pub enum TokenType {
     Dot,
     Comma,
 
+    // Only valid inside strings, e.g. a Windows path is an error
+    Backslash,
+
     QuotationMark,
 
     Space,
@@ -175,6 +179,7 @@ impl Tokenizer {
                     in_string = !in_string;
                     token!(TokenType::QuotationMark, index..index + 1)
                 },
+                '\\' => token!(TokenType::Backslash, index..index + 1),
                 '\n' => token!(TokenType::NewLine, index..index + 1),
                 c if c.eq(&'\t') => token!(TokenType::Tab, index..index + 1),
                 c if c.is_whitespace() => {
@@ -221,6 +226,7 @@ impl Tokenizer {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use common::text::normalize_source;
 
     #[test]
     fn test_tokenize_01() {
@@ -1109,4 +1115,14 @@ mod tests {
 
         assert_eq!(tokens_actual, tokens_expected);
     }
+
+    #[test]
+    fn test_normalize_source() {
+        // BOM and CRLF of opened or pasted code, see `common::text::normalize_source`
+        let text = "\u{feff}a +\r\n b\r* c\r\n";
+        assert_eq!(
+            Tokenizer::process(&normalize_source(text)),
+            Tokenizer::process("a +\n b\n* c\n")
+        );
+    }
 }
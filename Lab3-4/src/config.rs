@@ -2,16 +2,19 @@ use crate::logs;
 use log::LevelFilter;
 use serde::{Deserialize, Serialize};
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use thiserror::Error;
+use toml_edit::DocumentMut;
 
-const FILE_NAME: &str = "config.toml";
+pub const FILE_NAME: &str = "config.toml";
 
 #[derive(Debug)]
 pub struct Config {
     pub log_format: String,
     pub log_level: LevelFilter,
     pub pretty_output: bool,
+    /// Where the config is loaded from and saved to.
+    pub file_path: PathBuf,
 }
 
 impl Default for Config {
@@ -21,34 +24,60 @@ impl Default for Config {
             log_level: logs::DEFAULT_SETTINGS.log_level,
             // TODO: Default pretty output value
             pretty_output: false,
+            file_path: PathBuf::from(FILE_NAME),
         }
     }
 }
 
 impl Config {
-    pub fn from_file() -> Result<Self, ConfigError> {
-        match fs::read_to_string(FILE_NAME) {
+    /// With `create_if_missing`, an unreadable file is replaced by the defaults,
+    /// which are used. Otherwise it is an error.
+    pub fn from_file(path: &Path, create_if_missing: bool) -> Result<Self, ConfigError> {
+        match fs::read_to_string(path) {
             Ok(text) => {
                 let dto: ConfigDto =
                     toml::from_str(&text).map_err(ConfigError::Deserialization)?;
-                Config::try_from(dto)
+                Ok(Config {
+                    file_path: path.to_path_buf(),
+                    ..Config::try_from(dto)?
+                })
             },
-            Err(_) => {
-                let config = Self::default();
+            Err(_) if create_if_missing => {
+                let config = Self {
+                    file_path: path.to_path_buf(),
+                    ..Self::default()
+                };
                 config.save_to_file()?;
                 Ok(config)
             },
+            Err(error) if error.kind() == std::io::ErrorKind::NotFound => {
+                Err(ConfigError::NotFound(path.to_path_buf()))
+            },
+            Err(error) => Err(ConfigError::IO(error)),
         }
     }
 
+    /// The file is shared with Lab5-6, so the other keys of it,
+    /// e.g. `version`, are kept with their comments.
     pub fn save_to_file(&self) -> Result<(), ConfigError> {
         let dto = ConfigDto::from(self);
 
-        let data = toml::to_string(&dto).map_err(ConfigError::Serialization)?;
+        let mut data = toml::to_string(&dto).map_err(ConfigError::Serialization)?;
+        if let Ok(text) = fs::read_to_string(&self.file_path) {
+            data = Self::merge_toml(&text, &data)?;
+        }
 
-        let path = PathBuf::from(FILE_NAME);
+        fs::write(&self.file_path, data).map_err(ConfigError::IO)
+    }
+
+    fn merge_toml(text: &str, data: &str) -> Result<String, ConfigError> {
+        let mut document: DocumentMut = text.parse()?;
+        let current: DocumentMut = data.parse()?;
+        for (key, item) in current.iter() {
+            document[key] = item.clone();
+        }
 
-        fs::write(path, data).map_err(ConfigError::IO)
+        Ok(document.to_string())
     }
 }
 
@@ -75,6 +104,7 @@ impl TryFrom<ConfigDto> for Config {
                 unknown => Err(Self::Error::UnknownLogLevel(unknown.to_string())),
             }?,
             pretty_output: value.pretty_output,
+            file_path: PathBuf::from(FILE_NAME),
         })
     }
 }
@@ -100,6 +130,52 @@ pub enum ConfigError {
     #[error("IO: {0}")]
     IO(#[from] std::io::Error),
 
+    #[error("Editing: {0}")]
+    Editing(#[from] toml_edit::TomlError),
+
+    #[error("File \"{}\" is not found", .0.display())]
+    NotFound(PathBuf),
+
     #[error("Unknown log level: {0}")]
     UnknownLogLevel(String),
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_save_shared_file() {
+        let path = std::env::temp_dir()
+            .join(format!("lab3-4-config-{}.toml", std::process::id()));
+        let _ = fs::write(
+            &path,
+            concat!(
+                "# Version of the config format\n",
+                "version = 1\n",
+                "log_format = \"$MESSAGE\"\n",
+                "log_level = \"off\"\n",
+                "pretty_output = false\n",
+                "language = \"uk\"\n",
+            ),
+        );
+
+        let config = Config::from_file(&path, false).map(|config| Config {
+            pretty_output: true,
+            ..config
+        });
+        let is_saved = config
+            .as_ref()
+            .is_ok_and(|config| config.save_to_file().is_ok());
+        let text = fs::read_to_string(&path).unwrap_or_default();
+        let saved = Config::from_file(&path, false);
+        let _ = fs::remove_file(&path);
+
+        assert!(is_saved);
+        assert!(text.starts_with("# Version of the config format\nversion = 1\n"));
+        assert!(text.contains("\npretty_output = true\n"));
+        assert!(text.contains("\nlanguage = \"uk\"\n"));
+        assert!(text.contains("\nlog_level = \"OFF\"\n"));
+        assert!(saved.is_ok_and(|config| config.pretty_output));
+    }
+}
@@ -1,13 +1,27 @@
 // Hide console window on Windows in release mode
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")]
 
+use crate::cli::Cli;
 use crate::config::Config;
 use crate::logs::Logger;
+use clap::Parser;
+use std::path::Path;
 
 pub const PROJECT_TITLE: &str = "Lab 3-4";
 
 fn main() {
-    let config = Config::from_file().unwrap_or_else(|err| {
+    let cli = Cli::parse();
+
+    // An explicit path must exist, only the default file is created
+    let path_override = common::config::path_override(
+        cli.config,
+        std::env::var_os(common::config::PATH_VARIABLE),
+    );
+    let config = match path_override {
+        Some(path) => Config::from_file(&path, false),
+        None => Config::from_file(Path::new(config::FILE_NAME), true),
+    }
+    .unwrap_or_else(|err| {
         eprintln!("Error. {err}");
         std::process::exit(1);
     });
@@ -32,6 +46,7 @@ fn main() {
     });
 }
 
+pub mod cli;
 pub mod compiler;
 pub mod config;
 pub mod context;
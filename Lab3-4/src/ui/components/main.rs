@@ -2,6 +2,7 @@ use crate::context::Context;
 use crate::errors::Error;
 use crate::io::IoError;
 use crate::ui::modals::error::ErrorModal;
+use common::text::{needs_normalization, normalize_source};
 use std::fs;
 use std::path::PathBuf;
 
@@ -26,6 +27,10 @@ impl MainComponent {
                 .add(egui::TextEdit::singleline(&mut self.code).desired_width(500.0))
                 .changed()
             {
+                // Pasted code
+                if needs_normalization(&self.code) {
+                    self.code = normalize_source(&self.code);
+                }
                 context.compiler.code = self.code.clone();
             };
 
@@ -78,7 +83,7 @@ impl MainComponent {
     fn read_file(&mut self, path: PathBuf, context: &mut Context) {
         match fs::read_to_string(&path) {
             Ok(text) => {
-                self.code = text;
+                self.code = normalize_source(&text);
                 context.compiler.code = self.code.clone();
                 self.opened_file = Some(path.clone());
             },
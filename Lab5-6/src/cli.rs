@@ -0,0 +1,154 @@
+use crate::compiler::context::CompilerContext;
+use crate::config::Config;
+use clap::Parser;
+use std::path::PathBuf;
+
+/// Without a command, the UI is started.
+#[derive(Parser, Debug)]
+#[command(author = "Alex Kovalov", version = "0.0.1")]
+pub struct Cli {
+    #[arg(
+        long,
+        value_name = "PATH",
+        help = "Config file to load and save instead of config.toml. It must exist."
+    )]
+    pub config: Option<PathBuf>,
+
+    #[arg(
+        long,
+        value_name = "CODE",
+        conflicts_with_all = ["equivalent", "metrics"],
+        help = "Print the expression as LaTeX and exit."
+    )]
+    pub latex: Option<String>,
+
+    #[arg(
+        long,
+        num_args = 2,
+        value_names = ["FIRST", "SECOND"],
+        conflicts_with = "metrics",
+        help = "Check whether two expressions are equivalent and exit."
+    )]
+    pub equivalent: Option<Vec<String>>,
+
+    #[arg(
+        long,
+        value_name = "CODE",
+        help = "Print the tree metrics of the expression, with a warning above the limits, and exit."
+    )]
+    pub metrics: Option<String>,
+
+    #[arg(
+        long,
+        value_name = "HEIGHT",
+        requires = "metrics",
+        help = "Tree height above which the metrics warn, 0 for no limit. Overrides max_tree_height of the config."
+    )]
+    pub max_depth: Option<usize>,
+
+    #[arg(
+        long,
+        value_name = "NODES",
+        requires = "metrics",
+        help = "Tree nodes above which the metrics warn, 0 for no limit. Overrides max_tree_nodes of the config."
+    )]
+    pub max_width: Option<usize>,
+}
+
+impl Cli {
+    /// Output of the given command, `None` if there is none.
+    /// Errors are the reports of the failed stage.
+    pub fn run_command(&self, config: &Config) -> Option<Result<String, String>> {
+        let mut context = CompilerContext::new(config);
+
+        if let Some(code) = &self.latex {
+            context.code = code.clone();
+            return Some(context.logged(CompilerContext::latex));
+        }
+        if let Some([first, second]) = self.equivalent.as_deref() {
+            context.code = first.clone();
+            context.compared_code = second.clone();
+            return Some(Ok(context.logged(CompilerContext::equivalence_report)));
+        }
+        if let Some(code) = &self.metrics {
+            context.code = code.clone();
+            context.max_tree_height = self.max_depth.unwrap_or(context.max_tree_height);
+            context.max_tree_nodes = self.max_width.unwrap_or(context.max_tree_nodes);
+            return Some(Ok(context.logged(CompilerContext::metrics_report)));
+        }
+
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn run(args: &[&str]) -> Option<Result<String, String>> {
+        let Ok(cli) =
+            Cli::try_parse_from(std::iter::once("Lab5-6").chain(args.iter().copied()))
+        else {
+            panic!("Failed to parse arguments: {:?}", args)
+        };
+        cli.run_command(&Config::default())
+    }
+
+    #[test]
+    fn test_commands() {
+        assert_eq!(run(&[]), None);
+        assert_eq!(run(&["--config", "a.toml"]), None);
+
+        assert_eq!(
+            run(&["--latex", "a / b"]),
+            Some(Ok("\\frac{a}{b}".to_string()))
+        );
+        assert!(matches!(run(&["--latex", "a +"]), Some(Err(_))));
+
+        let Some(Ok(report)) = run(&["--equivalent", "a + b", "b + a"]) else {
+            panic!("Failed to check the equivalence")
+        };
+        assert!(report.contains("equivalent"));
+
+        assert!(Cli::try_parse_from(["Lab5-6", "--equivalent", "a"]).is_err());
+        assert!(Cli::try_parse_from(["Lab5-6", "--max-depth", "2"]).is_err());
+        assert!(Cli::try_parse_from(["Lab5-6", "--bogus"]).is_err());
+    }
+
+    #[test]
+    fn test_metrics_limits() {
+        let warning = "The expression may be unreasonably nested.";
+        let metrics = |args: &[&str]| {
+            let Some(Ok(report)) = run(args) else {
+                panic!("Failed to print the metrics: {:?}", args)
+            };
+            report
+        };
+
+        // Height of `a + b + c` is 3, and it has 5 nodes
+        assert!(!metrics(&["--metrics", "a + b + c"]).contains(warning));
+        assert!(
+            !metrics(&["--metrics", "a + b + c", "--max-depth", "3"]).contains(warning)
+        );
+        assert!(
+            metrics(&["--metrics", "a + b + c", "--max-depth", "2"]).contains(warning)
+        );
+        assert!(
+            !metrics(&["--metrics", "a + b + c", "--max-width", "5"]).contains(warning)
+        );
+        assert!(
+            metrics(&["--metrics", "a + b + c", "--max-width", "4"]).contains(warning)
+        );
+        assert!(
+            !metrics(&[
+                "--metrics",
+                "a + b + c",
+                "--max-depth",
+                "0",
+                "--max-width",
+                "0"
+            ])
+            .contains(warning)
+        );
+    }
+}
@@ -1,17 +1,29 @@
 pub mod context;
 pub mod lexer;
 pub mod pcs;
+pub mod run_log;
+pub mod source;
 pub mod syntax;
+pub mod timings;
 pub mod tokenizer;
 
 pub mod ast {
     pub mod balancer;
+    pub mod canonical;
+    pub mod common_subexpressions;
+    pub mod export;
+    pub mod factoring;
     pub mod folding;
+    pub mod latex;
     pub mod math;
     pub mod transform;
     pub mod tree;
+    pub mod validation;
 
     pub mod equivalent_forms;
+
+    #[cfg(test)]
+    pub mod test_support;
 }
 
 pub mod laws {
@@ -1,7 +1,8 @@
 use crate::compiler::ast::tree::{
-    AbstractSyntaxTree, AstError, AstNode, BinaryOperationKind,
+    AbstractSyntaxTree, AstError, AstNode, BinaryOperationKind, UnaryOperationKind,
 };
 use crate::compiler::reports::Reporter;
+use crate::compiler::syntax::Severity;
 use crate::utils::StringBuffer;
 use std::collections::VecDeque;
 
@@ -55,6 +56,16 @@ impl AbstractSyntaxTree {
                 })
             },
 
+            AstNode::Ternary {
+                condition,
+                then_branch,
+                else_branch,
+            } => Ok(AstNode::Ternary {
+                condition: Box::new(Self::balance_tree(*condition)?),
+                then_branch: Box::new(Self::balance_tree(*then_branch)?),
+                else_branch: Box::new(Self::balance_tree(*else_branch)?),
+            }),
+
             // Main logic: Binary operations
             AstNode::BinaryOperation {
                 operation,
@@ -82,6 +93,35 @@ impl AbstractSyntaxTree {
                         Self::build_balanced_tree(balanced_operands, operation)
                     },
 
+                    // Subtraction and division are left-associative: `a - b - c - d`
+                    // is parsed as `((a - b) - c) - d`. The chain is flattened into
+                    // the first operand and a list of inverted ones, then rebuilt
+                    // with minimal height without changing the result.
+                    BinaryOperationKind::Minus | BinaryOperationKind::Divide => {
+                        let mut operands = Vec::new();
+                        Self::collect_left_chain(
+                            AstNode::BinaryOperation {
+                                operation: operation.clone(),
+                                left,
+                                right,
+                            },
+                            operation.clone(),
+                            &mut operands,
+                        );
+
+                        let mut balanced_operands = Vec::new();
+                        for (operand, is_inverted) in operands {
+                            balanced_operands
+                                .push((Self::balance_tree(operand)?, is_inverted));
+                        }
+
+                        let direct = match operation {
+                            BinaryOperationKind::Minus => BinaryOperationKind::Plus,
+                            _ => BinaryOperationKind::Multiply,
+                        };
+                        Self::build_balanced_chain(balanced_operands, direct, operation)
+                    },
+
                     // Other operations (And, Or, etc.) are not associative
                     // in the arithmetic context. Just return them
                     // with already balanced children.
@@ -179,6 +219,256 @@ impl AbstractSyntaxTree {
         // it is the root of the balanced tree.
         queue.pop_front().ok_or(AstError::FailedPopFromQueue)
     }
+
+    /// Making flatten left-associative chain.
+    /// Unfolds the left spine of a subtraction or division chain.
+    /// The first operand is kept as is, every other operand is marked as inverted.
+    /// For example, `((a - b) - c) - d` with `op_kind = Minus`
+    /// becomes `[(a, false), (b, true), (c, true), (d, true)]`.
+    pub fn collect_left_chain(
+        node: AstNode, op_kind: BinaryOperationKind, operands: &mut Vec<(AstNode, bool)>,
+    ) {
+        match node {
+            AstNode::BinaryOperation {
+                operation,
+                left,
+                right,
+            } if operation == op_kind => {
+                // Only the left side continues the chain,
+                // `a - (b - c)` has a different meaning.
+                Self::collect_left_chain(*left, op_kind.clone(), operands);
+                operands.push((*right, true));
+            },
+            _ => {
+                operands.push((node, false));
+            },
+        }
+    }
+
+    /// Building balanced left-associative chain.
+    /// Same queue-based algorithm as `build_balanced_tree`, so the height is minimal,
+    /// but every node also remembers whether it is inverted
+    /// (subtracted for `Minus`, in denominator for `Divide`).
+    /// Two nodes are combined by these rules (shown for `Minus`):
+    /// - `x`, `y` -> `x + y`;
+    /// - `x`, `-y` -> `x - y`;
+    /// - `-x`, `y` -> `y - x`;
+    /// - `-x`, `-y` -> `-(x + y)`, inverted sum.
+    ///
+    /// For example, `a - b - c - d` becomes `(a - b) - (c + d)`.
+    pub fn build_balanced_chain(
+        operands: Vec<(AstNode, bool)>, direct: BinaryOperationKind,
+        inverse: BinaryOperationKind,
+    ) -> Result<AstNode, AstError> {
+        if operands.is_empty() {
+            return Err(AstError::CannotBuildEmptyTree);
+        }
+
+        let node = |operation: &BinaryOperationKind, left: AstNode, right: AstNode| {
+            AstNode::BinaryOperation {
+                operation: operation.clone(),
+                left: Box::new(left),
+                right: Box::new(right),
+            }
+        };
+
+        let mut queue: VecDeque<(AstNode, bool)> = operands.into();
+
+        while queue.len() > 1 {
+            let level_size = queue.len();
+
+            for _ in 0..(level_size / 2) {
+                let (left, is_left_inverted) =
+                    queue.pop_front().ok_or(AstError::FailedPopFromQueue)?;
+                let (right, is_right_inverted) =
+                    queue.pop_front().ok_or(AstError::FailedPopFromQueue)?;
+
+                let new_node = match (is_left_inverted, is_right_inverted) {
+                    (false, false) => (node(&direct, left, right), false),
+                    (false, true) => (node(&inverse, left, right), false),
+                    (true, false) => (node(&inverse, right, left), false),
+                    (true, true) => (node(&direct, left, right), true),
+                };
+
+                queue.push_back(new_node);
+            }
+
+            if !level_size.is_multiple_of(2) {
+                let odd_one_out =
+                    queue.pop_front().ok_or(AstError::FailedPopFromQueue)?;
+                queue.push_back(odd_one_out);
+            }
+        }
+
+        // The first operand is never inverted, and a node that contains
+        // a non-inverted operand is never inverted too. So the root is not.
+        let (root, _) = queue.pop_front().ok_or(AstError::FailedPopFromQueue)?;
+        Ok(root)
+    }
+
+    /// Balancing that keeps the original operators.
+    /// `balance` works on the transformed tree, where `a - b - c` is a sum of negations
+    /// `a + (-b) + (-c)`, and balances it as an associative sum.
+    /// Here mixed chains of `+` and `-` (or `*` and `/`) are flattened into signed
+    /// operands, negations included, and rebuilt by `build_balanced_chain`.
+    /// So the tree has minimal height, but `Minus` and `Divide` nodes stay:
+    /// `100 - 1 - 2 - 3` becomes `(100 - 1) - (2 + 3)`, with or without `transform`.
+    pub fn balance_preserving_operators(self) -> Result<Self, AstError> {
+        let peek = Self::balance_preserving_tree(self.peek)?;
+
+        Ok(Self::from_node(peek))
+    }
+
+    fn balance_preserving_tree(node: AstNode) -> Result<AstNode, AstError> {
+        match node {
+            AstNode::BinaryOperation {
+                operation: BinaryOperationKind::Plus | BinaryOperationKind::Minus,
+                ..
+            }
+            | AstNode::UnaryOperation {
+                operation: UnaryOperationKind::Minus,
+                ..
+            } => Self::balance_signed_chain(
+                node,
+                BinaryOperationKind::Plus,
+                BinaryOperationKind::Minus,
+            ),
+            AstNode::BinaryOperation {
+                operation: BinaryOperationKind::Multiply | BinaryOperationKind::Divide,
+                ..
+            } => Self::balance_signed_chain(
+                node,
+                BinaryOperationKind::Multiply,
+                BinaryOperationKind::Divide,
+            ),
+
+            AstNode::Number(_) | AstNode::Identifier(_) | AstNode::StringLiteral(_) => {
+                Ok(node)
+            },
+            AstNode::UnaryOperation {
+                operation,
+                expression,
+            } => Ok(AstNode::UnaryOperation {
+                operation,
+                expression: Box::new(Self::balance_preserving_tree(*expression)?),
+            }),
+            AstNode::FunctionCall { name, arguments } => Ok(AstNode::FunctionCall {
+                name,
+                arguments: arguments
+                    .into_iter()
+                    .map(Self::balance_preserving_tree)
+                    .collect::<Result<Vec<AstNode>, AstError>>()?,
+            }),
+            AstNode::ArrayAccess {
+                identifier,
+                indices,
+            } => Ok(AstNode::ArrayAccess {
+                identifier,
+                indices: indices
+                    .into_iter()
+                    .map(Self::balance_preserving_tree)
+                    .collect::<Result<Vec<AstNode>, AstError>>()?,
+            }),
+            AstNode::Ternary {
+                condition,
+                then_branch,
+                else_branch,
+            } => Ok(AstNode::Ternary {
+                condition: Box::new(Self::balance_preserving_tree(*condition)?),
+                then_branch: Box::new(Self::balance_preserving_tree(*then_branch)?),
+                else_branch: Box::new(Self::balance_preserving_tree(*else_branch)?),
+            }),
+            // Modulo and logical operations are not chained
+            AstNode::BinaryOperation {
+                operation,
+                left,
+                right,
+            } => Ok(AstNode::BinaryOperation {
+                operation,
+                left: Box::new(Self::balance_preserving_tree(*left)?),
+                right: Box::new(Self::balance_preserving_tree(*right)?),
+            }),
+        }
+    }
+
+    fn balance_signed_chain(
+        node: AstNode, direct: BinaryOperationKind, inverse: BinaryOperationKind,
+    ) -> Result<AstNode, AstError> {
+        let mut operands = Vec::new();
+        Self::collect_signed_chain(node, &direct, &inverse, false, &mut operands);
+
+        let mut balanced_operands = Vec::new();
+        for (operand, is_inverted) in operands {
+            balanced_operands
+                .push((Self::balance_preserving_tree(operand)?, is_inverted));
+        }
+
+        // `-a - b` => `-(a + b)`, `build_balanced_chain` needs a direct operand
+        if balanced_operands
+            .iter()
+            .all(|(_, is_inverted)| *is_inverted)
+        {
+            let operands = balanced_operands
+                .into_iter()
+                .map(|(operand, _)| (operand, false))
+                .collect();
+            let chain = Self::build_balanced_chain(operands, direct.clone(), inverse)?;
+
+            return Ok(match direct {
+                BinaryOperationKind::Plus => AstNode::UnaryOperation {
+                    operation: UnaryOperationKind::Minus,
+                    expression: Box::new(chain),
+                },
+                _ => AstNode::BinaryOperation {
+                    operation: BinaryOperationKind::Divide,
+                    left: Box::new(AstNode::Number(1.0)),
+                    right: Box::new(chain),
+                },
+            });
+        }
+
+        Self::build_balanced_chain(balanced_operands, direct, inverse)
+    }
+
+    /// Making flatten signed chain.
+    /// Unlike `collect_left_chain`, both sides of an inverse operation are unfolded,
+    /// with the sign of the right one flipped: `a - (b - c)` becomes
+    /// `[(a, false), (b, true), (c, false)]`. For sums, negations flip the sign too.
+    fn collect_signed_chain(
+        node: AstNode, direct: &BinaryOperationKind, inverse: &BinaryOperationKind,
+        is_inverted: bool, operands: &mut Vec<(AstNode, bool)>,
+    ) {
+        match node {
+            AstNode::BinaryOperation {
+                operation,
+                left,
+                right,
+            } if operation == *direct || operation == *inverse => {
+                let is_right_inverted = is_inverted ^ (operation == *inverse);
+                Self::collect_signed_chain(*left, direct, inverse, is_inverted, operands);
+                Self::collect_signed_chain(
+                    *right,
+                    direct,
+                    inverse,
+                    is_right_inverted,
+                    operands,
+                );
+            },
+            AstNode::UnaryOperation {
+                operation: UnaryOperationKind::Minus,
+                expression,
+            } if *direct == BinaryOperationKind::Plus => {
+                Self::collect_signed_chain(
+                    *expression,
+                    direct,
+                    inverse,
+                    !is_inverted,
+                    operands,
+                );
+            },
+            _ => operands.push((node, is_inverted)),
+        }
+    }
 }
 
 impl Reporter {
@@ -190,23 +480,78 @@ impl Reporter {
                 buffer.add_line(
                     "Balanced Abstract-Syntax Tree generation succeed!\n".to_string(),
                 );
-                buffer.add_line(tree.pretty_print());
+                buffer.add_line(tree.pretty_print_with(&self.number_format));
             },
             Err(error) => buffer.add_line(format!("Balancing AST error: {}", error)),
         }
 
         buffer.get()
     }
+
+    pub fn metrics(
+        &self, before: &AbstractSyntaxTree, after: &AbstractSyntaxTree,
+    ) -> String {
+        let mut buffer = StringBuffer::default();
+
+        buffer.add_line("Abstract-Syntax Tree metrics (balancing):\n".to_string());
+        buffer.add_line(format!(
+            "Height: {} → {}, Nodes: {} → {}",
+            before.height(),
+            after.height(),
+            before.node_count(),
+            after.node_count()
+        ));
+
+        buffer.get()
+    }
+
+    /// Warnings for the tree above the complexity limits, empty if it is within them.
+    pub fn complexity(&self, tree: &AbstractSyntaxTree) -> String {
+        let mut buffer = StringBuffer::default();
+
+        let limits = &self.complexity_limits;
+        let (height, nodes) = (tree.height(), tree.node_count());
+        let mut warnings = Vec::new();
+        if limits.max_height > 0 && height > limits.max_height {
+            warnings.push(format!(
+                "Tree height {} is above the limit of {}.",
+                height, limits.max_height
+            ));
+        }
+        if limits.max_nodes > 0 && nodes > limits.max_nodes {
+            warnings.push(format!(
+                "Tree has {} nodes, above the limit of {}.",
+                nodes, limits.max_nodes
+            ));
+        }
+        if warnings.is_empty() {
+            return buffer.get();
+        }
+
+        buffer.add_line(String::new());
+        for warning in warnings {
+            buffer.add_line(format!("{}: {}", Severity::Warning.label(), warning));
+        }
+        buffer.add_line("The expression may be unreasonably nested.".to_string());
+
+        buffer.get()
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::compiler::ast::test_support::assert_ast_approx_eq;
+    use crate::compiler::ast::test_support::parse;
     use crate::compiler::ast::tree::AstNode::{BinaryOperation, Identifier, Number};
     use crate::compiler::ast::tree::AstParser;
+    use crate::compiler::context::CompilerContext;
     use crate::compiler::lexer::Lexer;
+    use crate::compiler::reports::ComplexityLimits;
     use crate::compiler::syntax::SyntaxAnalyzer;
     use crate::compiler::tokenizer::Tokenizer;
+    use crate::config::Config;
+    use std::collections::HashMap;
 
     fn process(code: &str) -> Option<AbstractSyntaxTree> {
         let tokens = Tokenizer::process(code);
@@ -693,7 +1038,7 @@ mod tests {
             left: Box::new(BinaryOperation {
                 operation: BinaryOperationKind::Plus,
                 left: Box::new(Identifier("i".to_string())),
-                right: Box::new(Number(-0.3999999999999999)),
+                right: Box::new(Number(-0.4)),
             }),
             right: Box::new(BinaryOperation {
                 operation: BinaryOperationKind::Divide,
@@ -702,7 +1047,7 @@ mod tests {
             }),
         });
 
-        assert_eq!(actual_ast, expected_ast);
+        assert_ast_approx_eq(&actual_ast, &expected_ast, 1e-9);
     }
 
     #[test]
@@ -822,4 +1167,215 @@ mod tests {
             }))
         );
     }
+
+    #[test]
+    fn test_12() {
+        let Ok(balanced) = parse("a-b-c-d").balance() else {
+            panic!("Failed to balance AST")
+        };
+        assert_eq!(balanced.to_pretty_string(), "a - b - (c + d)");
+        assert_eq!(balanced.height(), 3);
+
+        let Ok(balanced) = parse("a/b/c/d/e").balance() else {
+            panic!("Failed to balance AST")
+        };
+        assert_eq!(balanced.to_pretty_string(), "a / b / (c * d) / e");
+        assert_eq!(balanced.height(), 4);
+
+        // Not a chain, the right side is grouped
+        let Ok(balanced) = parse("a-(b-c)").balance() else {
+            panic!("Failed to balance AST")
+        };
+        assert_eq!(balanced.to_pretty_string(), "a - (b - c)");
+    }
+
+    #[test]
+    fn test_13() {
+        // Deterministic pseudo-random generator (LCG), so failures are reproducible
+        let mut seed: u64 = 42;
+        let mut next = || {
+            seed = seed
+                .wrapping_mul(6364136223846793005)
+                .wrapping_add(1442695040888963407);
+            seed >> 33
+        };
+
+        let operators = ["-", "/", "+", "*"];
+        for terms in 2..=20 {
+            for _ in 0..10 {
+                let mut code = String::from("x0");
+                let mut env = std::collections::HashMap::new();
+                env.insert("x0".to_string(), (next() % 90 + 10) as f64 / 10.0);
+                for index in 1..terms {
+                    let operator = operators[(next() % 4) as usize];
+                    code.push_str(&format!(" {} x{}", operator, index));
+                    env.insert(format!("x{}", index), (next() % 90 + 10) as f64 / 10.0);
+                }
+
+                let ast = parse(&code);
+                let Ok(expected) = ast.evaluate(&env) else {
+                    panic!("Failed to evaluate: {}", code)
+                };
+                let Ok(balanced) = ast.balance() else {
+                    panic!("Failed to balance: {}", code)
+                };
+                let Ok(actual) = balanced.evaluate(&env) else {
+                    panic!("Failed to evaluate balanced: {}", code)
+                };
+
+                let tolerance = 1e-9 * expected.abs().max(1.0);
+                assert!(
+                    (expected - actual).abs() <= tolerance,
+                    "{}: {} != {}",
+                    code,
+                    expected,
+                    actual
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_14() {
+        let ast = parse("a+b+c+d+e+f+g+h");
+        assert_eq!(ast.height(), 8);
+        assert_eq!(ast.node_count(), 15);
+
+        let Ok(balanced) = ast.clone().balance() else {
+            panic!("Failed to balance AST")
+        };
+        assert_eq!(balanced.height(), 4);
+        assert_eq!(balanced.node_count(), 15);
+
+        assert_eq!(
+            Reporter::default().metrics(&ast, &balanced),
+            "Abstract-Syntax Tree metrics (balancing):\n\nHeight: 8 → 4, Nodes: 15 → 15\n"
+        );
+    }
+
+    #[test]
+    fn test_complexity_warning() {
+        let ast = parse("a+b+c+d+e+f+g+h");
+        let reporter = |max_height: usize, max_nodes: usize| {
+            Reporter::default().with_complexity_limits(ComplexityLimits {
+                max_height,
+                max_nodes,
+            })
+        };
+
+        // At the limits
+        assert_eq!(reporter(8, 15).complexity(&ast), "");
+        assert_eq!(reporter(0, 0).complexity(&ast), "");
+
+        assert_eq!(
+            reporter(7, 15).complexity(&ast),
+            "\nWarning: Tree height 8 is above the limit of 7.\n\
+             The expression may be unreasonably nested.\n"
+        );
+        assert_eq!(
+            reporter(7, 14).complexity(&ast),
+            "\nWarning: Tree height 8 is above the limit of 7.\n\
+             Warning: Tree has 15 nodes, above the limit of 14.\n\
+             The expression may be unreasonably nested.\n"
+        );
+
+        // Checked on the parsed tree, which the balancing makes lower
+        let mut context = CompilerContext::new(&Config::default());
+        context.code = "a+b+c+d+e+f+g+h".to_string();
+        context.max_tree_height = 7;
+        assert!(
+            context
+                .metrics_report()
+                .ends_with("Nodes: 15 → 15\n\nWarning: Tree height 8 is above the limit of 7.\nThe expression may be unreasonably nested.\n")
+        );
+        context.max_tree_height = 8;
+        assert!(context.metrics_report().ends_with("Nodes: 15 → 15\n"));
+    }
+
+    fn has_unary_minus(node: &AstNode) -> bool {
+        matches!(
+            node,
+            AstNode::UnaryOperation {
+                operation: UnaryOperationKind::Minus,
+                ..
+            }
+        ) || node.children().into_iter().any(has_unary_minus)
+    }
+
+    #[test]
+    fn test_preserving_operators_01() {
+        let ast = parse("100-1-2-3");
+
+        let Ok(rewritten) = ast.clone().transform().and_then(|ast| ast.balance()) else {
+            panic!("Failed to balance")
+        };
+        let Ok(preserved) = ast.balance_preserving_operators() else {
+            panic!("Failed to balance preserving operators")
+        };
+
+        let expected_ast = AbstractSyntaxTree::from_node(BinaryOperation {
+            operation: BinaryOperationKind::Minus,
+            left: Box::new(BinaryOperation {
+                operation: BinaryOperationKind::Minus,
+                left: Box::new(Number(100.0)),
+                right: Box::new(Number(1.0)),
+            }),
+            right: Box::new(BinaryOperation {
+                operation: BinaryOperationKind::Plus,
+                left: Box::new(Number(2.0)),
+                right: Box::new(Number(3.0)),
+            }),
+        });
+        assert_eq!(preserved, expected_ast);
+        // Negations of the rewritten sum take an extra level
+        assert_eq!(preserved.height(), 3);
+        assert_eq!(rewritten.height(), 4);
+
+        let env = HashMap::new();
+        assert_eq!(rewritten.evaluate(&env), Ok(94.0));
+        assert_eq!(preserved.evaluate(&env), Ok(94.0));
+    }
+
+    #[test]
+    fn test_preserving_operators_02() {
+        let code = "-a - b + c*d/e/(f - g) - (h - k)";
+        let env: HashMap<String, f64> = [
+            ("a", 1.0),
+            ("b", 2.0),
+            ("c", 3.0),
+            ("d", 4.0),
+            ("e", 5.0),
+            ("f", 6.0),
+            ("g", 8.0),
+            ("h", 7.0),
+            ("k", 9.0),
+        ]
+        .into_iter()
+        .map(|(name, value)| (name.to_string(), value))
+        .collect();
+
+        let ast = parse(code);
+        let Ok(expected) = ast.evaluate(&env) else {
+            panic!("Failed to evaluate")
+        };
+        let Ok(preserved) = ast.balance_preserving_operators() else {
+            panic!("Failed to balance preserving operators")
+        };
+        // The negation is folded into the chain: `k + ((c*d/(e*(f - g)) - h) - (a + b))`
+        assert!(!has_unary_minus(&preserved.peek));
+        assert_eq!(preserved.height(), 7);
+        let Ok(actual) = preserved.evaluate(&env) else {
+            panic!("Failed to evaluate")
+        };
+        assert!((actual - expected).abs() < 1e-9);
+
+        // Transformed trees are brought back to `Minus` nodes too
+        let Ok(transformed) = parse(code).transform() else {
+            panic!("Failed to transform")
+        };
+        let Ok(preserved_transformed) = transformed.balance_preserving_operators() else {
+            panic!("Failed to balance preserving operators")
+        };
+        assert!(!has_unary_minus(&preserved_transformed.peek));
+    }
 }
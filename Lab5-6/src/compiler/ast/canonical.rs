@@ -0,0 +1,227 @@
+use crate::compiler::ast::tree::{
+    AbstractSyntaxTree, AstNode, BinaryOperationKind, NumberFormat,
+};
+
+impl AbstractSyntaxTree {
+    /// Returns the tree in the canonical shape, so equivalent forms
+    /// (e.g. before and after balancing) are equal nodes:
+    /// - chains of `+` and `-` are the sum of the added terms minus the subtracted ones,
+    ///   so `a - (c - b)` and `(b + a) - c` both become `(a + b) - c`;
+    /// - chains of `*` and `/` are the product of the factors divided by the divisors;
+    /// - chains of `|` and of `&` are flattened, but keep the order of the operands,
+    ///   as the right operand is not evaluated when the left one decides the result;
+    /// - operands in both groups of a `+` or `*` chain are sorted by
+    ///   `to_canonical_string`;
+    /// - all chains are joined left-associatively.
+    ///
+    /// Other nodes keep their shape, only their children are canonicalized.
+    pub fn canonicalize(&self) -> AbstractSyntaxTree {
+        Self::from_node(Self::canonicalize_node(&self.peek))
+    }
+
+    fn canonicalize_node(node: &AstNode) -> AstNode {
+        let (direct, inverse, is_commutative) = match node {
+            AstNode::BinaryOperation { operation, .. } => match operation {
+                BinaryOperationKind::Plus | BinaryOperationKind::Minus => (
+                    BinaryOperationKind::Plus,
+                    Some(BinaryOperationKind::Minus),
+                    true,
+                ),
+                BinaryOperationKind::Multiply | BinaryOperationKind::Divide => (
+                    BinaryOperationKind::Multiply,
+                    Some(BinaryOperationKind::Divide),
+                    true,
+                ),
+                BinaryOperationKind::Or => (BinaryOperationKind::Or, None, false),
+                BinaryOperationKind::And => (BinaryOperationKind::And, None, false),
+                BinaryOperationKind::Modulo
+                | BinaryOperationKind::Less
+                | BinaryOperationKind::LessOrEqual
+                | BinaryOperationKind::Greater
+                | BinaryOperationKind::GreaterOrEqual
+                | BinaryOperationKind::Equal
+                | BinaryOperationKind::NotEqual => {
+                    return Self::canonicalize_children(node);
+                },
+            },
+            _ => return Self::canonicalize_children(node),
+        };
+
+        let mut direct_operands = Vec::new();
+        let mut inverse_operands = Vec::new();
+        Self::collect_chain(
+            node,
+            (&direct, inverse.as_ref()),
+            false,
+            &mut direct_operands,
+            &mut inverse_operands,
+        );
+
+        if is_commutative {
+            direct_operands = Self::sorted(direct_operands);
+        }
+        // The leftmost operand of a chain is never inverted, so it is always there
+        let mut direct_operands = direct_operands.into_iter();
+        let Some(first) = direct_operands.next() else {
+            return Self::canonicalize_children(node);
+        };
+        let sum = Self::join(first, direct_operands, &direct);
+
+        match inverse {
+            Some(inverse) => Self::join(sum, Self::sorted(inverse_operands), &inverse),
+            None => sum,
+        }
+    }
+
+    fn canonicalize_children(node: &AstNode) -> AstNode {
+        let mut node = node.clone();
+        for child in node.children_mut() {
+            *child = Self::canonicalize_node(child);
+        }
+        node
+    }
+
+    // `is_inverted` is true under an odd number of right operands of the inverse operation
+    fn collect_chain(
+        node: &AstNode, group: (&BinaryOperationKind, Option<&BinaryOperationKind>),
+        is_inverted: bool, direct_operands: &mut Vec<AstNode>,
+        inverse_operands: &mut Vec<AstNode>,
+    ) {
+        let (direct, inverse) = group;
+
+        if let AstNode::BinaryOperation {
+            operation,
+            left,
+            right,
+        } = node
+        {
+            let is_right_inverted = match operation {
+                operation if operation == direct => Some(is_inverted),
+                operation if Some(operation) == inverse => Some(!is_inverted),
+                _ => None,
+            };
+
+            if let Some(is_right_inverted) = is_right_inverted {
+                Self::collect_chain(
+                    left,
+                    group,
+                    is_inverted,
+                    direct_operands,
+                    inverse_operands,
+                );
+                Self::collect_chain(
+                    right,
+                    group,
+                    is_right_inverted,
+                    direct_operands,
+                    inverse_operands,
+                );
+                return;
+            }
+        }
+
+        let operand = Self::canonicalize_node(node);
+        match is_inverted {
+            true => inverse_operands.push(operand),
+            false => direct_operands.push(operand),
+        }
+    }
+
+    fn sorted(operands: Vec<AstNode>) -> Vec<AstNode> {
+        let mut keyed: Vec<(String, AstNode)> = operands
+            .into_iter()
+            .map(|operand| {
+                let key = Self::from_node(operand.clone())
+                    .to_canonical_string_with(&NumberFormat::Full);
+                (key, operand)
+            })
+            .collect();
+        keyed.sort_by(|(left, _), (right, _)| left.cmp(right));
+
+        keyed.into_iter().map(|(_, operand)| operand).collect()
+    }
+
+    fn join(
+        first: AstNode, rest: impl IntoIterator<Item = AstNode>,
+        operation: &BinaryOperationKind,
+    ) -> AstNode {
+        rest.into_iter()
+            .fold(first, |left, right| AstNode::BinaryOperation {
+                operation: operation.clone(),
+                left: Box::new(left),
+                right: Box::new(right),
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::compiler::ast::test_support::parse;
+
+    fn assert_same_canonical(codes: &[&str]) {
+        let expected = parse(codes[0]).canonicalize();
+        for code in &codes[1..] {
+            assert_eq!(parse(code).canonicalize().peek, expected.peek, "{}", code);
+        }
+    }
+
+    #[test]
+    fn test_subtraction_chains() {
+        assert_same_canonical(&[
+            "a - b - c",
+            "a - (b + c)",
+            "a - c - b",
+            "(a - (c + b))",
+        ]);
+        assert_same_canonical(&["a - (c - b)", "(b + a) - c", "b - c + a"]);
+
+        // Added terms go first, then the subtracted ones, both left-leaning
+        assert_eq!(
+            parse("c - b - a").canonicalize().peek,
+            parse("c - a - b").peek
+        );
+        assert_eq!(
+            parse("d - b + c - a").canonicalize().peek,
+            parse("c + d - a - b").peek
+        );
+
+        // Balancing changes the shape, but not the canonical form
+        let code = "a - b - c - d - e - f";
+        let Ok(balanced) = parse(code).balance() else {
+            panic!("Failed to balance: {}", code)
+        };
+        assert_ne!(balanced.peek, parse(code).peek);
+        assert_eq!(
+            balanced.canonicalize().peek,
+            parse(code).canonicalize().peek
+        );
+    }
+
+    #[test]
+    fn test_commutative_operands() {
+        assert_same_canonical(&["b*a + c", "c + a*b", "(c + b*a)"]);
+        assert_same_canonical(&["a / b / c", "a / (c*b)", "a / c / b"]);
+        assert_same_canonical(&["x | y | z", "x | (y | z)", "(x | y) | z"]);
+        assert_same_canonical(&["x & (y & z)", "x & y & z"]);
+        assert_same_canonical(&["sin(b + a) % 2", "sin(a + b) % 2"]);
+
+        // Not equivalent, so not equal
+        assert_ne!(
+            parse("a - b").canonicalize().peek,
+            parse("b - a").canonicalize().peek
+        );
+        assert_ne!(
+            parse("a % b").canonicalize().peek,
+            parse("b % a").canonicalize().peek
+        );
+        // The right operand of `|` and `&` may be not evaluated
+        assert_ne!(
+            parse("x | y").canonicalize().peek,
+            parse("y | x").canonicalize().peek
+        );
+        assert_ne!(
+            parse("b & A[i]").canonicalize().peek,
+            parse("A[i] & b").canonicalize().peek
+        );
+    }
+}
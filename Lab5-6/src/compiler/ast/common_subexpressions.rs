@@ -0,0 +1,110 @@
+use crate::compiler::ast::tree::{AbstractSyntaxTree, AstNode};
+use crate::compiler::reports::Reporter;
+use crate::utils::StringBuffer;
+use common::text::plural;
+use std::collections::HashMap;
+
+impl AbstractSyntaxTree {
+    /// Returns every non-leaf subtree which appears more than once,
+    /// with the number of occurrences. Subtrees are compared by `to_canonical_string`,
+    /// so `a + b` and `b + a` are the same subexpression.
+    /// Entries are ordered by the first occurrence (pre-order).
+    pub fn find_common_subexpressions(&self) -> Vec<(String, usize)> {
+        let mut order: Vec<String> = Vec::new();
+        let mut counts: HashMap<String, usize> = HashMap::new();
+
+        Self::count_subexpressions(&self.peek, &mut order, &mut counts);
+
+        order
+            .into_iter()
+            .filter_map(|key| match counts.get(&key) {
+                Some(&count) if count > 1 => Some((key, count)),
+                _ => None,
+            })
+            .collect()
+    }
+
+    fn count_subexpressions(
+        node: &AstNode, order: &mut Vec<String>, counts: &mut HashMap<String, usize>,
+    ) {
+        // Leaves are not worth reusing
+        if let AstNode::Number(_) | AstNode::Identifier(_) | AstNode::StringLiteral(_) =
+            node
+        {
+            return;
+        }
+
+        let key = Self::from_node(node.clone()).to_canonical_string();
+        let count = counts.entry(key.clone()).or_insert(0);
+        if *count == 0 {
+            order.push(key);
+        }
+        *count += 1;
+
+        for child in node.children() {
+            Self::count_subexpressions(child, order, counts);
+        }
+    }
+}
+
+impl Reporter {
+    pub fn cse(&self, subexpressions: &[(String, usize)]) -> String {
+        let mut buffer = StringBuffer::default();
+
+        if subexpressions.is_empty() {
+            buffer.add_line("No common subexpressions found.".to_string());
+            return buffer.get();
+        }
+
+        buffer.add_line(format!(
+            "Found {} common {}!\n",
+            subexpressions.len(),
+            plural("subexpression", subexpressions.len())
+        ));
+        for (subexpression, count) in subexpressions {
+            buffer.add_line(format!(
+                "- {}: {} {}",
+                subexpression,
+                count,
+                plural("occurrence", *count)
+            ));
+        }
+
+        buffer.get()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::compiler::ast::test_support::parse;
+
+    #[test]
+    fn test_01() {
+        let subexpressions = parse("sin(x)*a + sin(x)*b").find_common_subexpressions();
+        assert_eq!(subexpressions, vec![("sin(x)".to_string(), 2)]);
+        assert_eq!(
+            Reporter::default().cse(&subexpressions),
+            "Found 1 common subexpression!\n\n- sin(x): 2 occurrences\n"
+        );
+    }
+
+    #[test]
+    fn test_02() {
+        // Commutative operands are the same subexpression
+        let subexpressions =
+            parse("(a + b) * c - (b + a) / f(a + b)").find_common_subexpressions();
+        assert_eq!(subexpressions, vec![("(a + b)".to_string(), 3)]);
+        assert_eq!(
+            Reporter::default().cse(&[("(a + b)".to_string(), 3), ("c".to_string(), 1),]),
+            "Found 2 common subexpressions!\n\n- (a + b): 3 occurrences\n- c: 1 occurrence\n"
+        );
+
+        let subexpressions = parse("a*b + c").find_common_subexpressions();
+        assert!(subexpressions.is_empty());
+        assert_eq!(
+            Reporter::default().cse(&subexpressions),
+            "No common subexpressions found.\n"
+        );
+    }
+}
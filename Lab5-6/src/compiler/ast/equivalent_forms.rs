@@ -1,9 +1,31 @@
-use crate::compiler::ast::tree::AbstractSyntaxTree;
+use crate::compiler::ast::tree::{AbstractSyntaxTree, NumberFormat, PrettyOptions};
 use crate::compiler::reports::Reporter;
 use crate::utils::StringBuffer;
 use std::collections::{HashSet, VecDeque};
 
 impl AbstractSyntaxTree {
+    /// Both trees are computed and folded, then compared by the canonical form:
+    /// `a + b` is equivalent to `b + a`, and `2 * 3` to `6`.
+    /// A tree which fails to compute is not equivalent to anything.
+    pub fn is_equivalent_to(&self, other: &Self) -> bool {
+        match (self.normalized(), other.normalized()) {
+            (Some(left), Some(right)) => {
+                left.to_canonical_string_with(&NumberFormat::Full)
+                    == right.to_canonical_string_with(&NumberFormat::Full)
+            },
+            _ => false,
+        }
+    }
+
+    fn normalized(&self) -> Option<Self> {
+        let computed = self.clone().compute().ok()?;
+        // Folding may mix up constants again, so computing once more
+        match computed.clone().fold() {
+            Ok(folded) => folded.compute().ok(),
+            Err(_) => Some(computed),
+        }
+    }
+
     pub fn find_equivalent_forms(&self) -> Vec<AbstractSyntaxTree> {
         let mut all_forms: Vec<AbstractSyntaxTree> = Vec::new();
         let mut visited: HashSet<String> = HashSet::new();
@@ -100,6 +122,46 @@ impl AbstractSyntaxTree {
 }
 
 impl Reporter {
+    /// Forms of the source expression, then the found equivalent forms.
+    pub fn equivalent_forms(
+        &self, source: &AbstractSyntaxTree, forms: &[String],
+    ) -> String {
+        let mut buffer = StringBuffer::default();
+
+        let options = PrettyOptions {
+            spaced_lists: true,
+            number_format: self.number_format,
+        };
+        let labeled = [
+            (
+                "Canonical form:",
+                source.to_canonical_string_with(&self.number_format),
+            ),
+            (
+                "Minimal parentheses:",
+                source.to_pretty_string_with(&options),
+            ),
+            (
+                "Full parentheses:",
+                source.to_fully_parenthesized_string_with(&options),
+            ),
+            ("Flat chains:", source.to_flat_string_with(&options)),
+            ("Value:", "not a constant".to_string()),
+        ];
+        for (label, form) in labeled {
+            buffer.add_line(format!("{:22}{}", label, form));
+        }
+        buffer.add_line(String::new());
+
+        buffer.add(self.finding_equivalent_form(forms));
+
+        buffer.get()
+    }
+
+    pub fn constant_value(&self, value: f64) -> String {
+        format!("{:22}{}\n", "Value:", self.number_format.apply(value))
+    }
+
     pub fn finding_equivalent_form(&self, forms: &[String]) -> String {
         let mut buffer = StringBuffer::default();
 
@@ -114,4 +176,52 @@ impl Reporter {
 
         buffer.get()
     }
+
+    pub fn equivalence(
+        &self, left: &AbstractSyntaxTree, right: &AbstractSyntaxTree,
+    ) -> String {
+        let mut buffer = StringBuffer::default();
+
+        let verdict = match left.is_equivalent_to(right) {
+            true => "Expressions are equivalent!",
+            false => "Expressions are not equivalent.",
+        };
+        let options = PrettyOptions {
+            number_format: self.number_format,
+            ..PrettyOptions::default()
+        };
+        buffer.add_line(format!("{}\n", verdict));
+        buffer.add_line(format!("1) {}", left.to_pretty_string_with(&options)));
+        buffer.add_line(format!("2) {}", right.to_pretty_string_with(&options)));
+
+        buffer.get()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::compiler::ast::test_support::parse;
+
+    #[test]
+    fn test_is_equivalent_to() {
+        let equivalent = [("a+b", "b+a"), ("x*2", "2*x"), ("2*3", "6"), ("a*1+0", "a")];
+        for (left, right) in equivalent {
+            assert!(
+                parse(left).is_equivalent_to(&parse(right)),
+                "{} and {}",
+                left,
+                right
+            );
+        }
+
+        let different = [("a-b", "b-a"), ("a/b", "b/a"), ("2*3", "5")];
+        for (left, right) in different {
+            assert!(
+                !parse(left).is_equivalent_to(&parse(right)),
+                "{} and {}",
+                left,
+                right
+            );
+        }
+    }
 }
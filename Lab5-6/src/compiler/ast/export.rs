@@ -0,0 +1,197 @@
+use crate::compiler::ast::tree::{
+    AbstractSyntaxTree, AstNode, NumberFormat, PrettyOptions,
+};
+use crate::utils::escape_string;
+use common::json::json_string;
+
+/// Formats the tree can be exported to, e.g. from the result panel menu.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExportFormat {
+    Text,
+    Json,
+    Dot,
+    Latex,
+}
+
+impl ExportFormat {
+    pub const ALL: [ExportFormat; 4] = [
+        ExportFormat::Text,
+        ExportFormat::Json,
+        ExportFormat::Dot,
+        ExportFormat::Latex,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            ExportFormat::Text => "Text",
+            ExportFormat::Json => "JSON",
+            ExportFormat::Dot => "DOT",
+            ExportFormat::Latex => "LaTeX",
+        }
+    }
+}
+
+impl AbstractSyntaxTree {
+    pub fn export(&self, format: ExportFormat, number_format: &NumberFormat) -> String {
+        match format {
+            ExportFormat::Text => self.to_pretty_string_with(&PrettyOptions {
+                spaced_lists: true,
+                number_format: *number_format,
+            }),
+            ExportFormat::Json => self.to_json(),
+            ExportFormat::Dot => self.to_dot_with(number_format),
+            ExportFormat::Latex => self.to_latex_with(number_format),
+        }
+    }
+
+    /// Nested objects with the `type` of every node. Numbers are not rounded.
+    pub fn to_json(&self) -> String {
+        Self::node_to_json(&self.peek)
+    }
+
+    fn node_to_json(node: &AstNode) -> String {
+        let list = |nodes: &[AstNode]| {
+            nodes
+                .iter()
+                .map(Self::node_to_json)
+                .collect::<Vec<String>>()
+                .join(",")
+        };
+
+        match node {
+            AstNode::Number(n) => format!("{{\"type\":\"Number\",\"value\":{}}}", n),
+            AstNode::Identifier(name) => {
+                format!("{{\"type\":\"Identifier\",\"name\":{}}}", json_string(name))
+            },
+            AstNode::StringLiteral(s) => {
+                format!(
+                    "{{\"type\":\"StringLiteral\",\"value\":{}}}",
+                    json_string(s)
+                )
+            },
+            AstNode::UnaryOperation {
+                operation,
+                expression,
+            } => format!(
+                "{{\"type\":\"UnaryOperation\",\"operation\":\"{}\",\"expression\":{}}}",
+                operation,
+                Self::node_to_json(expression)
+            ),
+            AstNode::BinaryOperation {
+                operation,
+                left,
+                right,
+            } => format!(
+                "{{\"type\":\"BinaryOperation\",\"operation\":\"{}\",\"left\":{},\"right\":{}}}",
+                operation,
+                Self::node_to_json(left),
+                Self::node_to_json(right)
+            ),
+            AstNode::FunctionCall { name, arguments } => format!(
+                "{{\"type\":\"FunctionCall\",\"name\":{},\"arguments\":[{}]}}",
+                json_string(name),
+                list(arguments)
+            ),
+            AstNode::ArrayAccess {
+                identifier,
+                indices,
+            } => format!(
+                "{{\"type\":\"ArrayAccess\",\"identifier\":{},\"indices\":[{}]}}",
+                json_string(identifier),
+                list(indices)
+            ),
+            AstNode::Ternary {
+                condition,
+                then_branch,
+                else_branch,
+            } => format!(
+                "{{\"type\":\"Ternary\",\"condition\":{},\"then\":{},\"else\":{}}}",
+                Self::node_to_json(condition),
+                Self::node_to_json(then_branch),
+                Self::node_to_json(else_branch)
+            ),
+        }
+    }
+
+    /// Graphviz digraph, nodes are numbered in pre-order.
+    pub fn to_dot(&self) -> String {
+        self.to_dot_with(&NumberFormat::Full)
+    }
+
+    pub fn to_dot_with(&self, format: &NumberFormat) -> String {
+        let mut lines = Vec::new();
+        let mut next_id = 0;
+        Self::node_to_dot(&self.peek, format, &mut next_id, &mut lines);
+
+        format!("digraph AST {{\n{}}}\n", lines.concat())
+    }
+
+    // Returns the id of the node
+    fn node_to_dot(
+        node: &AstNode, format: &NumberFormat, next_id: &mut usize,
+        lines: &mut Vec<String>,
+    ) -> usize {
+        let id = *next_id;
+        *next_id += 1;
+
+        let label = match node {
+            AstNode::Number(n) => format.apply(*n),
+            AstNode::Identifier(name) => name.clone(),
+            AstNode::StringLiteral(s) => escape_string(s),
+            AstNode::UnaryOperation { operation, .. } => operation.to_string(),
+            AstNode::BinaryOperation { operation, .. } => operation.to_string(),
+            AstNode::FunctionCall { name, .. } => format!("{}()", name),
+            AstNode::ArrayAccess { identifier, .. } => format!("{}[]", identifier),
+            AstNode::Ternary { .. } => "?:".to_string(),
+        };
+        lines.push(format!("    n{} [label={}];\n", id, json_string(&label)));
+
+        for child in node.children() {
+            let child_id = Self::node_to_dot(child, format, next_id, lines);
+            lines.push(format!("    n{} -> n{};\n", id, child_id));
+        }
+
+        id
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::compiler::ast::test_support::parse;
+
+    #[test]
+    fn test_export() {
+        let ast = parse("f(x) / 2.5");
+        let format = NumberFormat::Fixed(1);
+
+        assert_eq!(ast.export(ExportFormat::Text, &format), "f(x) / 2.5");
+        assert_eq!(
+            ast.export(ExportFormat::Json, &format),
+            concat!(
+                r#"{"type":"BinaryOperation","operation":"/","#,
+                r#""left":{"type":"FunctionCall","name":"f","arguments":"#,
+                r#"[{"type":"Identifier","name":"x"}]},"#,
+                r#""right":{"type":"Number","value":2.5}}"#,
+            )
+        );
+        assert_eq!(
+            ast.export(ExportFormat::Dot, &format),
+            concat!(
+                "digraph AST {\n",
+                "    n0 [label=\"/\"];\n",
+                "    n1 [label=\"f()\"];\n",
+                "    n2 [label=\"x\"];\n",
+                "    n1 -> n2;\n",
+                "    n0 -> n1;\n",
+                "    n3 [label=\"2.5\"];\n",
+                "    n0 -> n3;\n",
+                "}\n",
+            )
+        );
+        assert_eq!(
+            ast.export(ExportFormat::Latex, &format),
+            "\\frac{\\operatorname{f}(x)}{2.5}"
+        );
+    }
+}
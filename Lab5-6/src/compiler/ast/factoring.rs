@@ -0,0 +1,181 @@
+use crate::compiler::ast::tree::{
+    AbstractSyntaxTree, AstError, AstNode, BinaryOperationKind,
+};
+use crate::compiler::reports::Reporter;
+use crate::utils::StringBuffer;
+
+impl AbstractSyntaxTree {
+    pub fn factor_common_terms(self) -> Result<AbstractSyntaxTree, AstError> {
+        let factored = Self::factor_recursive(self.peek);
+
+        Ok(Self::from_node(factored))
+    }
+
+    fn factor_recursive(node: AstNode) -> AstNode {
+        match node {
+            AstNode::Number(_) | AstNode::Identifier(_) | AstNode::StringLiteral(_) => {
+                node
+            },
+            AstNode::UnaryOperation {
+                operation,
+                expression,
+            } => AstNode::UnaryOperation {
+                operation,
+                expression: Box::new(Self::factor_recursive(*expression)),
+            },
+            AstNode::BinaryOperation {
+                operation,
+                left,
+                right,
+            } => {
+                let left = Self::factor_recursive(*left);
+                let right = Self::factor_recursive(*right);
+
+                if operation == BinaryOperationKind::Plus
+                    && let Some(factored) = Self::factor_sum(&left, &right)
+                {
+                    return factored;
+                }
+
+                AstNode::BinaryOperation {
+                    operation,
+                    left: Box::new(left),
+                    right: Box::new(right),
+                }
+            },
+            AstNode::FunctionCall { name, arguments } => AstNode::FunctionCall {
+                name,
+                arguments: arguments.into_iter().map(Self::factor_recursive).collect(),
+            },
+            AstNode::ArrayAccess {
+                identifier,
+                indices,
+            } => AstNode::ArrayAccess {
+                identifier,
+                indices: indices.into_iter().map(Self::factor_recursive).collect(),
+            },
+            AstNode::Ternary {
+                condition,
+                then_branch,
+                else_branch,
+            } => AstNode::Ternary {
+                condition: Box::new(Self::factor_recursive(*condition)),
+                then_branch: Box::new(Self::factor_recursive(*then_branch)),
+                else_branch: Box::new(Self::factor_recursive(*else_branch)),
+            },
+        }
+    }
+
+    // a*b + a*c -> a*(b + c), b*a + c*a -> (b + c)*a
+    fn factor_sum(left: &AstNode, right: &AstNode) -> Option<AstNode> {
+        let (
+            AstNode::BinaryOperation {
+                operation: BinaryOperationKind::Multiply,
+                left: first_left,
+                right: first_right,
+            },
+            AstNode::BinaryOperation {
+                operation: BinaryOperationKind::Multiply,
+                left: second_left,
+                right: second_right,
+            },
+        ) = (left, right)
+        else {
+            return None;
+        };
+
+        let same = |a: &AstNode, b: &AstNode| {
+            Self::from_node(a.clone()).to_canonical_string()
+                == Self::from_node(b.clone()).to_canonical_string()
+        };
+
+        // (common factor, first rest, second rest, is common factor on the left)
+        let (common, first_rest, second_rest, is_left) = if same(first_left, second_left)
+        {
+            (first_left, first_right, second_right, true)
+        } else if same(first_left, second_right) {
+            (first_left, first_right, second_left, true)
+        } else if same(first_right, second_left) {
+            (first_right, first_left, second_right, false)
+        } else if same(first_right, second_right) {
+            (first_right, first_left, second_left, false)
+        } else {
+            return None;
+        };
+
+        let sum = Box::new(AstNode::BinaryOperation {
+            operation: BinaryOperationKind::Plus,
+            left: first_rest.clone(),
+            right: second_rest.clone(),
+        });
+
+        let (left, right) = match is_left {
+            true => (common.clone(), sum),
+            false => (sum, common.clone()),
+        };
+
+        Some(AstNode::BinaryOperation {
+            operation: BinaryOperationKind::Multiply,
+            left,
+            right,
+        })
+    }
+}
+
+impl Reporter {
+    pub fn factoring(&self, result: &Result<AbstractSyntaxTree, AstError>) -> String {
+        let mut buffer = StringBuffer::default();
+
+        match result {
+            Ok(tree) => {
+                buffer.add_line("Factoring Abstract-Syntax Tree success!\n".to_string());
+                buffer.add_line(tree.pretty_print_with(&self.number_format));
+            },
+            Err(error) => buffer.add_line(format!("Factoring AST error: {}", error)),
+        }
+
+        buffer.get()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::compiler::ast::test_support::parse;
+
+    fn process(code: &str) -> AbstractSyntaxTree {
+        let ast = parse(code);
+        let Ok(factored) = ast.factor_common_terms() else {
+            panic!("Failed to factor AST for: {}", code)
+        };
+        factored
+    }
+
+    #[test]
+    fn test_01() {
+        assert_eq!(process("a*b + a*c").to_pretty_string(), "a * (b + c)");
+    }
+
+    #[test]
+    fn test_02() {
+        assert_eq!(
+            process("2*x + 3*x").to_pretty_string(),
+            "(2.000 + 3.000) * x"
+        );
+    }
+
+    #[test]
+    fn test_03() {
+        assert_eq!(process("b*a + a*c").to_pretty_string(), "(b + c) * a");
+        assert_eq!(
+            process("f(a*b + a*c) - d").to_pretty_string(),
+            "f(a * (b + c)) - d"
+        );
+    }
+
+    #[test]
+    fn test_04() {
+        assert_eq!(process("a*b + c*d").to_pretty_string(), "a * b + c * d");
+        assert_eq!(process("a*b - a*c").to_pretty_string(), "a * b - a * c");
+    }
+}
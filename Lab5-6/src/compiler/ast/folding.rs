@@ -21,6 +21,21 @@ impl AbstractSyntaxTree {
                 expression,
             } => {
                 let folded_child = Self::fold_recursive(*expression.clone())?;
+
+                // --e => e, and !!e => e only if e is already `0` or `1`:
+                // `!!a` is 1 for a = 5. Numbers are left to `compute`.
+                if let AstNode::UnaryOperation {
+                    operation: inner_operation,
+                    expression: inner,
+                } = &folded_child
+                    && inner_operation == operation
+                    && !matches!(**inner, AstNode::Number(_))
+                    && (*operation == UnaryOperationKind::Minus
+                        || Self::is_boolean(inner))
+                {
+                    return Ok(*inner.clone());
+                }
+
                 Ok(AstNode::UnaryOperation {
                     operation: operation.clone(),
                     expression: Box::new(folded_child),
@@ -110,6 +125,15 @@ impl AbstractSyntaxTree {
                     indices: folded_indices?,
                 })
             },
+            AstNode::Ternary {
+                condition,
+                then_branch,
+                else_branch,
+            } => Ok(AstNode::Ternary {
+                condition: Box::new(Self::fold_recursive(*condition.clone())?),
+                then_branch: Box::new(Self::fold_recursive(*then_branch.clone())?),
+                else_branch: Box::new(Self::fold_recursive(*else_branch.clone())?),
+            }),
         }
     }
 }
@@ -121,7 +145,7 @@ impl Reporter {
         match result {
             Ok(tree) => {
                 buffer.add_line("Folding Abstract-Syntax Tree success!\n".to_string());
-                buffer.add_line(tree.pretty_print());
+                buffer.add_line(tree.pretty_print_with(&self.number_format));
             },
             Err(error) => buffer.add_line(format!("Folding AST error: {}", error)),
         }
@@ -133,6 +157,7 @@ impl Reporter {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::compiler::ast::test_support::assert_ast_approx_eq;
     use crate::compiler::ast::tree::AstNode::{BinaryOperation, Identifier, Number};
     use crate::compiler::ast::tree::AstParser;
     use crate::compiler::lexer::Lexer;
@@ -634,7 +659,7 @@ mod tests {
             left: Box::new(BinaryOperation {
                 operation: BinaryOperationKind::Minus,
                 left: Box::new(Identifier("i".to_string())),
-                right: Box::new(Number(0.3999999999999999)),
+                right: Box::new(Number(0.4)),
             }),
             right: Box::new(BinaryOperation {
                 operation: BinaryOperationKind::Divide,
@@ -643,12 +668,12 @@ mod tests {
             }),
         });
 
-        assert_eq!(actual_ast, expected_ast);
+        assert_ast_approx_eq(&actual_ast, &expected_ast, 1e-9);
     }
 
     #[test]
     fn test_11() {
-        let code = "a*2/0 + b/(b+b*0-1*b) - 1/(c*2*4.76*(1-2+1))";
+        let code = "a*2/0 + b/(b+b*0-1*b) - 1/(c*2*4.760*(1-2+1))";
 
         let tokens = Tokenizer::process(code);
         // Syntax Analysis
@@ -678,7 +703,7 @@ mod tests {
 
     #[test]
     fn test_11_1() {
-        let code = "1/(c*2*4.76*(1-2+1))";
+        let code = "1/(c*2*4.760*(1-2+1))";
 
         let tokens = Tokenizer::process(code);
         // Syntax Analysis
@@ -763,4 +788,31 @@ mod tests {
             }))
         );
     }
+
+    #[test]
+    fn test_12() {
+        let fold = |code: &str| {
+            let tokens = Tokenizer::process(code);
+            let Ok(lexemes) = Lexer::new(tokens).run() else {
+                panic!("Failed to create lexemes for: {}", code)
+            };
+            let Ok(ast) = AstParser::new(lexemes).parse() else {
+                panic!("Failed to build AST for: {}", code)
+            };
+            let Ok(ast) = ast.fold() else {
+                panic!("Failed to fold: {}", code)
+            };
+            ast.to_pretty_string()
+        };
+
+        assert_eq!(fold("-(-x)"), "x");
+        assert_eq!(fold("!!a"), "!!a");
+        assert_eq!(fold("-(-(-x))"), "-x");
+        assert_eq!(fold("!!(a & b) | c"), "a & b | c");
+        assert_eq!(fold("!!!a"), "!a");
+        assert_eq!(fold("!!(a + b)"), "!!(a + b)");
+        // Mixed operators and numbers are not touched
+        assert_eq!(fold("-!x"), "-!x");
+        assert_eq!(fold("!!5"), "!!5.000");
+    }
 }
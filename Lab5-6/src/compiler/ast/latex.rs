@@ -0,0 +1,240 @@
+use crate::compiler::ast::tree::{
+    AbstractSyntaxTree, AstNode, BinaryOperationKind, NumberFormat, UnaryOperationKind,
+};
+use crate::utils::escape_string;
+
+// Functions which have their own command in LaTeX: `sin(x)` => `\sin(x)`.
+// Others are printed with `\operatorname`.
+const LATEX_FUNCTIONS: [&str; 20] = [
+    "sin", "cos", "tan", "cot", "sec", "csc", "arcsin", "arccos", "arctan", "sinh",
+    "cosh", "tanh", "coth", "exp", "ln", "log", "lg", "max", "min", "gcd",
+];
+
+impl AbstractSyntaxTree {
+    /// LaTeX math-mode form of the expression, with numbers printed in full.
+    pub fn to_latex(&self) -> String {
+        self.to_latex_with(&NumberFormat::Full)
+    }
+
+    /// Same as `to_latex`, but with an explicit number format.
+    pub fn to_latex_with(&self, format: &NumberFormat) -> String {
+        Self::node_to_latex(&self.peek, 0, format)
+    }
+
+    // Parentheses follow the same precedence rules as `to_pretty_string`,
+    // except for fractions, which are grouped by themselves.
+    fn node_to_latex(
+        node: &AstNode, parent_precedence: u8, format: &NumberFormat,
+    ) -> String {
+        let to_latex = |node: &AstNode, precedence: u8| {
+            Self::node_to_latex(node, precedence, format)
+        };
+        let group = |text: String, precedence: u8| match precedence < parent_precedence {
+            true => format!("\\left({}\\right)", text),
+            false => text,
+        };
+
+        match node {
+            AstNode::Number(n) => format.apply(*n),
+            AstNode::Identifier(name) => Self::identifier_to_latex(name),
+            AstNode::StringLiteral(s) => {
+                format!("\\text{{{}}}", Self::text_to_latex(&escape_string(s)))
+            },
+            AstNode::FunctionCall { name, arguments } => {
+                let args = arguments
+                    .iter()
+                    .map(|arg| to_latex(arg, 0))
+                    .collect::<Vec<String>>()
+                    .join(", ");
+                match LATEX_FUNCTIONS.contains(&name.as_str()) {
+                    true => format!("\\{}({})", name, args),
+                    false => format!("\\operatorname{{{}}}({})", name, args),
+                }
+            },
+            AstNode::ArrayAccess {
+                identifier,
+                indices,
+            } => {
+                let idx = indices
+                    .iter()
+                    .map(|idx| to_latex(idx, 0))
+                    .collect::<Vec<String>>()
+                    .join(", ");
+                format!("{}[{}]", Self::identifier_to_latex(identifier), idx)
+            },
+            AstNode::Ternary {
+                condition,
+                then_branch,
+                else_branch,
+            } => {
+                let result = format!(
+                    "{} \\mathbin{{?}} {} : {}",
+                    to_latex(condition, 1),
+                    to_latex(then_branch, 0),
+                    to_latex(else_branch, 0)
+                );
+                match parent_precedence > 0 {
+                    true => format!("\\left({}\\right)", result),
+                    false => result,
+                }
+            },
+            AstNode::UnaryOperation {
+                operation,
+                expression,
+            } => {
+                let operator = match operation {
+                    UnaryOperationKind::Minus => "-",
+                    UnaryOperationKind::Not => "\\lnot ",
+                };
+                group(format!("{}{}", operator, to_latex(expression, 3)), 3)
+            },
+            AstNode::BinaryOperation {
+                operation,
+                left,
+                right,
+            } => {
+                let my_precedence = operation.precedence();
+
+                // Same rewrites as in `to_pretty_string`:
+                // A + (-B) => A - B, (-A) + B => B - A
+                if *operation == BinaryOperationKind::Plus {
+                    if let AstNode::UnaryOperation {
+                        operation: UnaryOperationKind::Minus,
+                        expression: inner_right,
+                    } = right.as_ref()
+                    {
+                        let result = format!(
+                            "{} - {}",
+                            to_latex(left, my_precedence),
+                            to_latex(inner_right, my_precedence + 1)
+                        );
+                        return group(result, my_precedence);
+                    }
+
+                    if let AstNode::UnaryOperation {
+                        operation: UnaryOperationKind::Minus,
+                        expression: inner_left,
+                    } = left.as_ref()
+                    {
+                        let result = format!(
+                            "{} - {}",
+                            to_latex(right, my_precedence),
+                            to_latex(inner_left, my_precedence + 1)
+                        );
+                        return group(result, my_precedence);
+                    }
+                }
+
+                let operator = match operation {
+                    BinaryOperationKind::Divide => {
+                        return format!(
+                            "\\frac{{{}}}{{{}}}",
+                            to_latex(left, 0),
+                            to_latex(right, 0)
+                        );
+                    },
+                    BinaryOperationKind::Plus => "+",
+                    BinaryOperationKind::Minus => "-",
+                    BinaryOperationKind::Multiply => "\\cdot",
+                    BinaryOperationKind::Modulo => "\\bmod",
+                    BinaryOperationKind::Or => "\\lor",
+                    BinaryOperationKind::And => "\\land",
+                    BinaryOperationKind::Less => "<",
+                    BinaryOperationKind::LessOrEqual => "\\le",
+                    BinaryOperationKind::Greater => ">",
+                    BinaryOperationKind::GreaterOrEqual => "\\ge",
+                    BinaryOperationKind::Equal => "=",
+                    BinaryOperationKind::NotEqual => "\\ne",
+                };
+
+                let right_precedence = match operation {
+                    BinaryOperationKind::Minus | BinaryOperationKind::Modulo => {
+                        my_precedence + 1
+                    },
+                    operation if operation.is_comparison() => my_precedence + 1,
+                    _ => my_precedence,
+                };
+
+                let result = format!(
+                    "{} {} {}",
+                    to_latex(left, my_precedence),
+                    operator,
+                    to_latex(right, right_precedence)
+                );
+                group(result, my_precedence)
+            },
+        }
+    }
+
+    // Characters that are commands in LaTeX are printed as text
+    fn text_to_latex(text: &str) -> String {
+        let mut result = String::with_capacity(text.len());
+        for ch in text.chars() {
+            match ch {
+                '\\' => result.push_str("\\textbackslash{}"),
+                '~' => result.push_str("\\textasciitilde{}"),
+                '^' => result.push_str("\\textasciicircum{}"),
+                '{' | '}' | '$' | '&' | '#' | '_' | '%' => {
+                    result.push('\\');
+                    result.push(ch);
+                },
+                ch => result.push(ch),
+            }
+        }
+        result
+    }
+
+    // Multi-letter names are set as one word, not as a product of letters
+    fn identifier_to_latex(name: &str) -> String {
+        let name = name.replace('_', "\\_");
+        match name.chars().count() {
+            1 => name,
+            _ => format!("\\mathit{{{}}}", name),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::compiler::ast::test_support::parse;
+
+    #[test]
+    fn test_01() {
+        assert_eq!(parse("a/b").to_latex(), "\\frac{a}{b}");
+        assert_eq!(parse("sin(x)").to_latex(), "\\sin(x)");
+        assert_eq!(parse("f(x, 2)").to_latex(), "\\operatorname{f}(x, 2)");
+        assert_eq!(parse("a*b").to_latex(), "a \\cdot b");
+    }
+
+    #[test]
+    fn test_02() {
+        // Nested fractions and unary minus
+        assert_eq!(
+            parse("(a/b)/(c/-d)").to_latex(),
+            "\\frac{\\frac{a}{b}}{\\frac{c}{-d}}"
+        );
+        assert_eq!(
+            parse("-(a+b)*c").to_latex(),
+            "-\\left(a + b\\right) \\cdot c"
+        );
+        assert_eq!(parse("a - (b - c)").to_latex(), "a - \\left(b - c\\right)");
+        assert_eq!(parse("a + -b").to_latex(), "a - b");
+        assert_eq!(
+            parse("!flag_1 | x % 2.5").to_latex(),
+            "\\lnot \\mathit{flag\\_1} \\lor x \\bmod 2.5"
+        );
+    }
+
+    #[test]
+    fn test_03() {
+        // String literals keep their escapes and are safe inside `\text`
+        assert_eq!(
+            parse(r#"f("50% & $_{x}", 1)"#).to_latex(),
+            r#"\operatorname{f}(\text{"50\% \& \$\_\{x\}"}, 1)"#
+        );
+        assert_eq!(
+            parse(r#"f("a\"b\\c", 1)"#).to_latex(),
+            r#"\operatorname{f}(\text{"a\textbackslash{}"b\textbackslash{}\textbackslash{}c"}, 1)"#
+        );
+    }
+}
@@ -1,8 +1,45 @@
 use crate::compiler::ast::tree::{
-    AbstractSyntaxTree, AstError, AstNode, BinaryOperationKind, UnaryOperationKind,
+    AbstractSyntaxTree, AstError, AstNode, BinaryOperationKind, NodeSpan, NumberFormat,
+    UnaryOperationKind,
 };
+use crate::compiler::ast::validation;
 use crate::compiler::reports::Reporter;
+use crate::compiler::source::SourceMap;
 use crate::utils::StringBuffer;
+use std::collections::{BTreeMap, HashMap};
+use std::ops::Range;
+
+/// Commonly used constants, see `substitute_constants`. The config starts with them,
+/// and an empty table turns them off, as `e` is also a usual variable name.
+pub const COMMON_CONSTANTS: [(&str, f64); 2] =
+    [("pi", std::f64::consts::PI), ("e", std::f64::consts::E)];
+
+pub fn common_constants() -> BTreeMap<String, f64> {
+    COMMON_CONSTANTS
+        .iter()
+        .map(|(name, value)| (name.to_string(), *value))
+        .collect()
+}
+
+/// Array of `evaluate_with_arrays`. Every dimension is one more level of nesting:
+/// `A[i][j]` takes the `j`-th item of the `i`-th one.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ArrayValue {
+    Number(f64),
+    Nested(Vec<ArrayValue>),
+}
+
+impl From<Vec<f64>> for ArrayValue {
+    fn from(value: Vec<f64>) -> Self {
+        ArrayValue::Nested(value.into_iter().map(ArrayValue::Number).collect())
+    }
+}
+
+impl From<Vec<Vec<f64>>> for ArrayValue {
+    fn from(value: Vec<Vec<f64>>) -> Self {
+        ArrayValue::Nested(value.into_iter().map(ArrayValue::from).collect())
+    }
+}
 
 impl AbstractSyntaxTree {
     pub fn compute(self) -> Result<AbstractSyntaxTree, AstError> {
@@ -24,39 +61,62 @@ impl AbstractSyntaxTree {
 
     fn compute_recursive(node: AstNode) -> Result<AstNode, AstError> {
         match &node {
-            AstNode::Number(_) | AstNode::Identifier(_) | AstNode::StringLiteral(_) => {
-                Ok(node)
-            },
+            AstNode::Number(number) => Self::constant(&node, *number),
+            AstNode::Identifier(_) | AstNode::StringLiteral(_) => Ok(node),
             AstNode::UnaryOperation {
                 operation: op,
                 expression,
             } => match &op {
                 UnaryOperationKind::Minus => {
                     let child = Self::compute_recursive(*expression.clone())?;
-                    if let AstNode::Number(number) = child {
-                        return Ok(AstNode::Number(-number));
-                    };
-
-                    if let AstNode::BinaryOperation {
-                        operation,
-                        left,
-                        right,
-                    } = child
-                        && operation == BinaryOperationKind::Minus
-                    {
-                        return Ok(AstNode::BinaryOperation {
+                    match child {
+                        AstNode::Number(number) => Self::constant(&node, -number),
+                        // -(-a) -> a
+                        AstNode::UnaryOperation {
+                            operation: UnaryOperationKind::Minus,
+                            expression,
+                        } => Ok(*expression),
+                        // -(a - b) -> b - a
+                        AstNode::BinaryOperation {
+                            operation: BinaryOperationKind::Minus,
+                            left,
+                            right,
+                        } => Ok(AstNode::BinaryOperation {
+                            operation: BinaryOperationKind::Minus,
+                            left: right,
+                            right: left,
+                        }),
+                        // -(a + b) -> -a - b
+                        AstNode::BinaryOperation {
                             operation: BinaryOperationKind::Plus,
+                            left,
+                            right,
+                        } => Ok(AstNode::BinaryOperation {
+                            operation: BinaryOperationKind::Minus,
                             left: Box::new(AstNode::UnaryOperation {
                                 operation: UnaryOperationKind::Minus,
                                 expression: left,
                             }),
                             right,
-                        });
+                        }),
+                        child => Ok(AstNode::UnaryOperation {
+                            operation: UnaryOperationKind::Minus,
+                            expression: Box::new(child),
+                        }),
+                    }
+                },
+                UnaryOperationKind::Not => {
+                    let child = Self::compute_recursive(*expression.clone())?;
+                    match child {
+                        AstNode::Number(number) => {
+                            Ok(Self::boolean_to_number(!Self::is_truthy(number)))
+                        },
+                        child => Ok(AstNode::UnaryOperation {
+                            operation: UnaryOperationKind::Not,
+                            expression: Box::new(child),
+                        }),
                     }
-
-                    Ok(node)
                 },
-                UnaryOperationKind::Not => Ok(node),
             },
             AstNode::BinaryOperation {
                 operation,
@@ -66,13 +126,26 @@ impl AbstractSyntaxTree {
                 BinaryOperationKind::Plus
                 | BinaryOperationKind::Minus
                 | BinaryOperationKind::Multiply
-                | BinaryOperationKind::Divide => {
+                | BinaryOperationKind::Divide
+                | BinaryOperationKind::Modulo => {
                     let computed_left = Self::compute_recursive(*left.clone())?;
                     let computed_right = Self::compute_recursive(*right.clone())?;
 
-                    // Case: (a + b) - (a + b) = 0
-                    // Or: (a + b) / (a + b) = 1
-                    if computed_left.eq(&computed_right) {
+                    // Strings are opaque, even "x" - "x" is left to `evaluate` to reject
+                    if [&computed_left, &computed_right]
+                        .iter()
+                        .any(|child| matches!(child, AstNode::StringLiteral(_)))
+                    {
+                        return Ok(AstNode::BinaryOperation {
+                            operation: operation.clone(),
+                            left: Box::new(computed_left),
+                            right: Box::new(computed_right),
+                        });
+                    }
+
+                    // Case: (a + b) - (b + a) = 0
+                    // Or: (a + b) / (b + a) = 1
+                    if Self::is_same_subtree(&computed_left, &computed_right) {
                         match operation {
                             BinaryOperationKind::Minus => {
                                 return Ok(AstNode::Number(0.0));
@@ -104,62 +177,37 @@ impl AbstractSyntaxTree {
                                     left_number / right_number
                                 }
                             },
+                            // Truncated remainder (Rust `%`): the sign follows the dividend,
+                            // so -7 % 3 = -1 (not 2, as `rem_euclid` would give).
+                            BinaryOperationKind::Modulo => {
+                                if *right_number == 0.0 {
+                                    return Err(AstError::DivisionByZero(node));
+                                } else {
+                                    left_number % right_number
+                                }
+                            },
                             _ => unreachable!(),
                         };
-                        Ok(AstNode::Number(result))
-                    } else if let AstNode::Number(number) = &computed_left {
-                        if number == &0.0 {
-                            if [
-                                BinaryOperationKind::Multiply,
-                                BinaryOperationKind::Divide,
-                            ]
-                            .contains(operation)
-                            {
-                                return Ok(AstNode::Number(0.0));
-                            }
-                            if BinaryOperationKind::Plus == *operation {
-                                return Ok(computed_right);
-                            }
-                            if BinaryOperationKind::Minus == *operation {
-                                return Ok(AstNode::UnaryOperation {
-                                    operation: UnaryOperationKind::Minus,
-                                    expression: Box::new(computed_right),
-                                });
-                            }
-                        }
-                        if number == &1.0 && BinaryOperationKind::Multiply == *operation {
-                            return Ok(computed_right);
-                        }
+                        return Self::constant(&node, result);
+                    }
 
-                        Ok(AstNode::BinaryOperation {
-                            operation: operation.clone(),
-                            left: Box::new(computed_left),
-                            right: Box::new(computed_right),
-                        })
-                    } else if let AstNode::Number(number) = &computed_right {
-                        if number == &0.0 {
-                            if BinaryOperationKind::Divide == *operation {
-                                return Err(AstError::DivisionByZero(node));
-                            }
-                            if BinaryOperationKind::Multiply == *operation {
-                                return Ok(AstNode::Number(0.0));
-                            }
-                            if [BinaryOperationKind::Plus, BinaryOperationKind::Minus]
-                                .contains(operation)
-                            {
-                                return Ok(computed_left);
-                            }
-                        }
-                        if number == &1.0
-                            && [
-                                BinaryOperationKind::Multiply,
-                                BinaryOperationKind::Divide,
-                            ]
+                    if let AstNode::Number(number) = &computed_right
+                        && *number == 0.0
+                        && [BinaryOperationKind::Divide, BinaryOperationKind::Modulo]
                             .contains(operation)
-                        {
-                            return Ok(computed_left);
-                        }
+                    {
+                        return Err(AstError::DivisionByZero(node));
+                    }
+
+                    if let Some(simplified) = Self::simplify_identity(
+                        operation,
+                        &computed_left,
+                        &computed_right,
+                    ) {
+                        return Ok(simplified);
+                    }
 
+                    if let AstNode::Number(number) = &computed_right {
                         if BinaryOperationKind::Minus == *operation
                             && let AstNode::UnaryOperation {
                                 operation: UnaryOperationKind::Minus,
@@ -200,24 +248,50 @@ impl AbstractSyntaxTree {
                             return Ok(AstNode::BinaryOperation {
                                 operation: BinaryOperationKind::Plus,
                                 left: new_left,
-                                right: Box::new(AstNode::Number(number)),
+                                right: Box::new(Self::constant(&node, number)?),
                             });
                         }
+                    }
 
-                        Ok(AstNode::BinaryOperation {
-                            operation: operation.clone(),
-                            left: Box::new(computed_left),
-                            right: Box::new(computed_right),
-                        })
-                    } else {
-                        Ok(AstNode::BinaryOperation {
-                            operation: operation.clone(),
-                            left: Box::new(computed_left),
-                            right: Box::new(computed_right),
-                        })
+                    Ok(AstNode::BinaryOperation {
+                        operation: operation.clone(),
+                        left: Box::new(computed_left),
+                        right: Box::new(computed_right),
+                    })
+                },
+                BinaryOperationKind::And | BinaryOperationKind::Or => {
+                    let computed_left = Self::compute_recursive(*left.clone())?;
+                    let computed_right = Self::compute_recursive(*right.clone())?;
+
+                    Ok(Self::simplify_logical(
+                        operation,
+                        computed_left,
+                        computed_right,
+                    ))
+                },
+                BinaryOperationKind::Less
+                | BinaryOperationKind::LessOrEqual
+                | BinaryOperationKind::Greater
+                | BinaryOperationKind::GreaterOrEqual
+                | BinaryOperationKind::Equal
+                | BinaryOperationKind::NotEqual => {
+                    let computed_left = Self::compute_recursive(*left.clone())?;
+                    let computed_right = Self::compute_recursive(*right.clone())?;
+
+                    if let (AstNode::Number(left_number), AstNode::Number(right_number)) =
+                        (&computed_left, &computed_right)
+                        && let Some(result) =
+                            Self::compare(operation, *left_number, *right_number)
+                    {
+                        return Ok(AstNode::Number(result));
                     }
+
+                    Ok(AstNode::BinaryOperation {
+                        operation: operation.clone(),
+                        left: Box::new(computed_left),
+                        right: Box::new(computed_right),
+                    })
                 },
-                _ => Ok(node),
             },
             AstNode::FunctionCall { name, arguments } => {
                 let mut computed_arguments = Vec::new();
@@ -245,6 +319,451 @@ impl AbstractSyntaxTree {
                     indices: computed_indices,
                 })
             },
+            AstNode::Ternary {
+                condition,
+                then_branch,
+                else_branch,
+            } => {
+                let condition = Self::compute_recursive(*condition.clone())?;
+
+                // Constant condition: only the chosen branch is left
+                if let AstNode::Number(number) = condition {
+                    return match Self::is_truthy(number) {
+                        true => Self::compute_recursive(*then_branch.clone()),
+                        false => Self::compute_recursive(*else_branch.clone()),
+                    };
+                }
+
+                Ok(AstNode::Ternary {
+                    condition: Box::new(condition),
+                    then_branch: Box::new(Self::compute_recursive(*then_branch.clone())?),
+                    else_branch: Box::new(Self::compute_recursive(*else_branch.clone())?),
+                })
+            },
+        }
+    }
+
+    // Folded constant. `-0.0` becomes `0.0`, infinity and NaN are errors,
+    // so they never get into the tree.
+    fn constant(node: &AstNode, number: f64) -> Result<AstNode, AstError> {
+        if !number.is_finite() {
+            return Err(AstError::NonFiniteResult(node.clone()));
+        }
+
+        match number == 0.0 {
+            true => Ok(AstNode::Number(0.0)),
+            false => Ok(AstNode::Number(number)),
+        }
+    }
+
+    // Subtrees are the same up to the order of commutative operands.
+    // Full number format, so `1.001` and `1.004` are different.
+    fn is_same_subtree(left: &AstNode, right: &AstNode) -> bool {
+        Self::from_node(left.clone()).to_canonical_string_with(&NumberFormat::Full)
+            == Self::from_node(right.clone())
+                .to_canonical_string_with(&NumberFormat::Full)
+    }
+
+    // Identity-element rules, `x` may be any computed subtree:
+    // x + 0, 0 + x, x - 0, x * 1, 1 * x, x / 1 -> x
+    // x * 0, 0 * x, 0 / x, 0 % x -> 0
+    // 0 - x -> -x
+    fn simplify_identity(
+        operation: &BinaryOperationKind, left: &AstNode, right: &AstNode,
+    ) -> Option<AstNode> {
+        if let AstNode::Number(number) = left {
+            if *number == 0.0 {
+                match operation {
+                    BinaryOperationKind::Multiply
+                    | BinaryOperationKind::Divide
+                    | BinaryOperationKind::Modulo => return Some(AstNode::Number(0.0)),
+                    BinaryOperationKind::Plus => return Some(right.clone()),
+                    BinaryOperationKind::Minus => {
+                        return Some(AstNode::UnaryOperation {
+                            operation: UnaryOperationKind::Minus,
+                            expression: Box::new(right.clone()),
+                        });
+                    },
+                    _ => {},
+                }
+            }
+            if *number == 1.0 && BinaryOperationKind::Multiply == *operation {
+                return Some(right.clone());
+            }
+        }
+
+        if let AstNode::Number(number) = right {
+            if *number == 0.0 {
+                match operation {
+                    BinaryOperationKind::Multiply => return Some(AstNode::Number(0.0)),
+                    BinaryOperationKind::Plus | BinaryOperationKind::Minus => {
+                        return Some(left.clone());
+                    },
+                    _ => {},
+                }
+            }
+            if *number == 1.0
+                && [BinaryOperationKind::Multiply, BinaryOperationKind::Divide]
+                    .contains(operation)
+            {
+                return Some(left.clone());
+            }
+        }
+
+        None
+    }
+
+    // Truthiness: `0` is false, any other number is true.
+    // Logical operations always produce `1` (true) or `0` (false).
+    fn is_truthy(number: f64) -> bool {
+        number != 0.0
+    }
+
+    fn boolean_to_number(value: bool) -> AstNode {
+        match value {
+            true => AstNode::Number(1.0),
+            false => AstNode::Number(0.0),
+        }
+    }
+
+    // `1` if the comparison holds, `0` otherwise. `None` for other operations
+    fn compare(operation: &BinaryOperationKind, left: f64, right: f64) -> Option<f64> {
+        let result = match operation {
+            BinaryOperationKind::Less => left < right,
+            BinaryOperationKind::LessOrEqual => left <= right,
+            BinaryOperationKind::Greater => left > right,
+            BinaryOperationKind::GreaterOrEqual => left >= right,
+            BinaryOperationKind::Equal => left == right,
+            BinaryOperationKind::NotEqual => left != right,
+            _ => return None,
+        };
+
+        Some(match result {
+            true => 1.0,
+            false => 0.0,
+        })
+    }
+
+    // Node that always evaluates to `0` or `1`
+    pub(crate) fn is_boolean(node: &AstNode) -> bool {
+        match node {
+            AstNode::Number(number) => [0.0, 1.0].contains(number),
+            AstNode::UnaryOperation { operation, .. } => {
+                UnaryOperationKind::Not.eq(operation)
+            },
+            AstNode::BinaryOperation { operation, .. } => {
+                [BinaryOperationKind::And, BinaryOperationKind::Or].contains(operation)
+                    || operation.is_comparison()
+            },
+            _ => false,
+        }
+    }
+
+    // Constant folding & short-circuit for `&` and `|`:
+    // 0 & x, x & 0 -> 0;  1 | x, x | 1 -> 1;
+    // x & 1, x | 0 -> x (only if `x` is already boolean)
+    fn simplify_logical(
+        operation: &BinaryOperationKind, left: AstNode, right: AstNode,
+    ) -> AstNode {
+        let is_and = BinaryOperationKind::And.eq(operation);
+
+        for (constant, other) in [(&left, &right), (&right, &left)] {
+            if let AstNode::Number(number) = constant {
+                let value = Self::is_truthy(*number);
+                // `false` for And, `true` for Or decides the result
+                if value != is_and {
+                    return Self::boolean_to_number(value);
+                }
+                if Self::is_boolean(other) {
+                    return other.clone();
+                }
+                if let AstNode::Number(other) = other {
+                    return Self::boolean_to_number(Self::is_truthy(*other));
+                }
+            }
+        }
+
+        AstNode::BinaryOperation {
+            operation: operation.clone(),
+            left: Box::new(left),
+            right: Box::new(right),
+        }
+    }
+
+    pub fn evaluate(&self, env: &HashMap<String, f64>) -> Result<f64, AstError> {
+        Self::evaluate_recursive(&self.peek, env, &HashMap::new(), false)
+    }
+
+    /// Array accesses are resolved against `arrays`, the other ones are not evaluable.
+    pub fn evaluate_with_arrays(
+        &self, env: &HashMap<String, f64>, arrays: &HashMap<String, ArrayValue>,
+    ) -> Result<f64, AstError> {
+        Self::evaluate_recursive(&self.peek, env, arrays, false)
+    }
+
+    fn evaluate_recursive(
+        node: &AstNode, env: &HashMap<String, f64>, arrays: &HashMap<String, ArrayValue>,
+        is_case_insensitive: bool,
+    ) -> Result<f64, AstError> {
+        match node {
+            AstNode::Number(number) => Ok(*number),
+            AstNode::Identifier(name) => env
+                .get(name)
+                .copied()
+                .ok_or(AstError::UndefinedVariable(name.clone())),
+            AstNode::StringLiteral(string) => {
+                Err(AstError::NotEvaluableString(string.clone()))
+            },
+            AstNode::UnaryOperation {
+                operation,
+                expression,
+            } => {
+                Self::reject_string(expression)?;
+                let value = Self::evaluate_recursive(
+                    expression,
+                    env,
+                    arrays,
+                    is_case_insensitive,
+                )?;
+                Ok(match operation {
+                    UnaryOperationKind::Minus => -value,
+                    UnaryOperationKind::Not => match Self::is_truthy(value) {
+                        true => 0.0,
+                        false => 1.0,
+                    },
+                })
+            },
+            AstNode::BinaryOperation {
+                operation,
+                left,
+                right,
+            } => {
+                Self::reject_string(left)?;
+                Self::reject_string(right)?;
+                let left_value =
+                    Self::evaluate_recursive(left, env, arrays, is_case_insensitive)?;
+
+                // Same short-circuit as in `compute`: the right side is not evaluated
+                match operation {
+                    BinaryOperationKind::And if !Self::is_truthy(left_value) => {
+                        return Ok(0.0);
+                    },
+                    BinaryOperationKind::Or if Self::is_truthy(left_value) => {
+                        return Ok(1.0);
+                    },
+                    _ => {},
+                }
+
+                let right_value =
+                    Self::evaluate_recursive(right, env, arrays, is_case_insensitive)?;
+
+                Ok(match operation {
+                    BinaryOperationKind::Plus => left_value + right_value,
+                    BinaryOperationKind::Minus => left_value - right_value,
+                    BinaryOperationKind::Multiply => left_value * right_value,
+                    BinaryOperationKind::Divide | BinaryOperationKind::Modulo
+                        if right_value == 0.0 =>
+                    {
+                        return Err(AstError::DivisionByZero(node.clone()));
+                    },
+                    BinaryOperationKind::Divide => left_value / right_value,
+                    BinaryOperationKind::Modulo => left_value % right_value,
+                    BinaryOperationKind::And | BinaryOperationKind::Or => {
+                        match Self::is_truthy(right_value) {
+                            true => 1.0,
+                            false => 0.0,
+                        }
+                    },
+                    BinaryOperationKind::Less
+                    | BinaryOperationKind::LessOrEqual
+                    | BinaryOperationKind::Greater
+                    | BinaryOperationKind::GreaterOrEqual
+                    | BinaryOperationKind::Equal
+                    | BinaryOperationKind::NotEqual => {
+                        Self::compare(operation, left_value, right_value)
+                            .unwrap_or_default()
+                    },
+                })
+            },
+            AstNode::FunctionCall { name, arguments } => {
+                let values = arguments
+                    .iter()
+                    .map(|argument| {
+                        Self::evaluate_recursive(
+                            argument,
+                            env,
+                            arrays,
+                            is_case_insensitive,
+                        )
+                    })
+                    .collect::<Result<Vec<f64>, AstError>>()?;
+
+                Self::apply_function(name, &values, is_case_insensitive)
+                    .ok_or(AstError::UnknownFunction(name.clone(), values.len()))
+            },
+            AstNode::ArrayAccess {
+                identifier,
+                indices,
+            } => {
+                let Some(array) = arrays.get(identifier) else {
+                    return Err(AstError::NotEvaluableArrayAccess(identifier.clone()));
+                };
+
+                let mut item = array;
+                for index in indices {
+                    let index = Self::evaluate_recursive(
+                        index,
+                        env,
+                        arrays,
+                        is_case_insensitive,
+                    )?;
+                    if index.fract() != 0.0 || !index.is_finite() {
+                        return Err(AstError::NonIntegerIndex(identifier.clone(), index));
+                    }
+
+                    // A number has no items to index
+                    let items: &[ArrayValue] = match item {
+                        ArrayValue::Nested(items) => items,
+                        ArrayValue::Number(_) => &[],
+                    };
+                    item = match index >= 0.0 {
+                        true => items.get(index as usize),
+                        false => None,
+                    }
+                    .ok_or(AstError::IndexOutOfBounds {
+                        identifier: identifier.clone(),
+                        index,
+                        length: items.len(),
+                    })?;
+                }
+
+                // Fewer indices than dimensions
+                match item {
+                    ArrayValue::Number(number) => Ok(*number),
+                    ArrayValue::Nested(_) => {
+                        Err(AstError::NotEvaluableArrayAccess(identifier.clone()))
+                    },
+                }
+            },
+            // Only the chosen branch is evaluated
+            AstNode::Ternary {
+                condition,
+                then_branch,
+                else_branch,
+            } => match Self::is_truthy(Self::evaluate_recursive(
+                condition,
+                env,
+                arrays,
+                is_case_insensitive,
+            )?) {
+                true => Self::evaluate_recursive(
+                    then_branch,
+                    env,
+                    arrays,
+                    is_case_insensitive,
+                ),
+                false => Self::evaluate_recursive(
+                    else_branch,
+                    env,
+                    arrays,
+                    is_case_insensitive,
+                ),
+            },
+        }
+    }
+
+    // Strings are opaque values, operations don't take them
+    fn reject_string(operand: &AstNode) -> Result<(), AstError> {
+        match operand {
+            AstNode::StringLiteral(string) => {
+                Err(AstError::StringInArithmetic(string.clone()))
+            },
+            _ => Ok(()),
+        }
+    }
+
+    // Functions of `KNOWN_FUNCTIONS`, `None` for other names or numbers of arguments
+    fn apply_function(
+        name: &str, values: &[f64], is_case_insensitive: bool,
+    ) -> Option<f64> {
+        let name = validation::lookup_name(name, is_case_insensitive);
+        validation::KNOWN_FUNCTIONS
+            .iter()
+            .find(|function| function.name == name)
+            .and_then(|function| function.apply(values))
+    }
+
+    /// Replaces identifiers named as constants with their values, so `compute` folds them.
+    /// Names of functions and arrays are not identifiers and stay untouched.
+    pub fn substitute_constants(self, constants: &BTreeMap<String, f64>) -> Self {
+        self.substitute_constants_with_case(constants, false)
+    }
+
+    /// With `is_case_insensitive`, `PI` and `Pi` are replaced as `pi`.
+    pub fn substitute_constants_with_case(
+        mut self, constants: &BTreeMap<String, f64>, is_case_insensitive: bool,
+    ) -> Self {
+        let lookup = |name: &str| validation::lookup_name(name, is_case_insensitive);
+        self.for_each_mut(|node| {
+            if let AstNode::Identifier(name) = node
+                && let Some((_, value)) = constants
+                    .iter()
+                    .find(|(known, _)| lookup(known) == lookup(name))
+            {
+                *node = AstNode::Number(*value);
+            }
+        });
+        self
+    }
+
+    /// Variables of `env` override the constants with the same name.
+    /// With `is_case_insensitive`, known functions and constants are matched
+    /// as in `substitute_constants_with_case`: `SIN(PI)` is `sin(pi)`.
+    /// Variables are still case-sensitive.
+    pub fn evaluate_with_constants(
+        &self, env: &HashMap<String, f64>, constants: &BTreeMap<String, f64>,
+        is_case_insensitive: bool,
+    ) -> Result<f64, AstError> {
+        let constants: BTreeMap<String, f64> = constants
+            .iter()
+            .filter(|(name, _)| !env.contains_key(*name))
+            .map(|(name, value)| (name.clone(), *value))
+            .collect();
+        let tree = self
+            .clone()
+            .substitute_constants_with_case(&constants, is_case_insensitive);
+
+        Self::evaluate_recursive(&tree.peek, env, &HashMap::new(), is_case_insensitive)
+    }
+
+    /// Source range of the divisor of the division `error` comes from,
+    /// when `compute` of this tree fails with `DivisionByZero`.
+    /// Only parsed trees have the spans.
+    pub fn zero_divisor_span(&self, error: &AstError) -> Option<Range<usize>> {
+        let AstError::DivisionByZero(division) = error else {
+            return None;
+        };
+        Self::find_division(&self.peek, self.spans.as_ref()?, division)
+    }
+
+    // The failed node is a subtree of the computed one, unless an earlier
+    // pass of `compute` has already rewritten it. Children first, as there
+    // the leftmost innermost division fails first
+    fn find_division(
+        node: &AstNode, span: &NodeSpan, division: &AstNode,
+    ) -> Option<Range<usize>> {
+        let found = node
+            .children()
+            .into_iter()
+            .zip(&span.children)
+            .find_map(|(child, span)| Self::find_division(child, span, division));
+        if found.is_some() {
+            return found;
+        }
+
+        match node == division {
+            true => span.children.get(1).map(|span| span.position.clone()),
+            false => None,
         }
     }
 
@@ -268,7 +787,7 @@ impl Reporter {
                     "Computing constants of Abstract-Syntax Tree (Run #{}) succeed!\n",
                     run
                 ));
-                buffer.add_line(tree.pretty_print());
+                buffer.add_line(tree.pretty_print_with(&self.number_format));
             },
             Err(error) => buffer.add_line(format!(
                 "Computing constants of Abstract-Syntax Tree error: {}",
@@ -279,9 +798,437 @@ impl Reporter {
         buffer.get()
     }
 
+    /// Source with the divisor underlined, it is given without the parentheses.
+    pub fn division_by_zero(&self, code: &str, divisor: Range<usize>) -> String {
+        let mut buffer = StringBuffer::default();
+
+        let source = SourceMap::new(code);
+        let divisor = source.parenthesized(divisor);
+
+        buffer.add_line(format!(
+            "Divisor \"{}\" computes to zero:\n",
+            source.snippet(divisor.clone())
+        ));
+        for (text, underline) in source.underlined_lines(&[divisor], self.tab_width) {
+            buffer.add_line(text);
+            buffer.add_line(underline);
+        }
+
+        buffer.get()
+    }
+
     pub fn computing_finalization(&self) -> String {
         String::from(
             "Tree is fully solved by computation. Further optimization is not needed",
         )
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::compiler::ast::test_support::{parse, positioned_lexemes};
+    use crate::compiler::ast::tree::AstParser;
+    use crate::compiler::context::CompilerContext;
+    use crate::config::Config;
+
+    fn compute(code: &str) -> String {
+        let ast = parse(code);
+        let Ok(computed) = ast.compute() else {
+            panic!("Failed to compute AST for: {}", code)
+        };
+        computed.to_pretty_string()
+    }
+
+    #[test]
+    fn test_logical_constants() {
+        assert_eq!(compute("!0"), "1.000");
+        assert_eq!(compute("!5"), "0.000");
+        assert_eq!(compute("1 & 0"), "0.000");
+        assert_eq!(compute("1 | 0"), "1.000");
+        assert_eq!(compute("2 & 3"), "1.000");
+        assert_eq!(compute("!(1 - 1) & 1"), "1.000");
+    }
+
+    #[test]
+    fn test_comparisons() {
+        assert_eq!(compute("1 < 2"), "1.000");
+        assert_eq!(compute("2 <= 1"), "0.000");
+        assert_eq!(compute("3 > 3"), "0.000");
+        assert_eq!(compute("3 >= 3"), "1.000");
+        assert_eq!(compute("2 == 1 + 1"), "1.000");
+        assert_eq!(compute("2 != 1 + 1"), "0.000");
+        assert_eq!(compute("a + 0 < 1 + 1"), "a < 2.000");
+
+        assert_eq!(
+            evaluate("a < b & b < 5", &[("a", 1.0), ("b", 2.0)]),
+            Ok(1.0)
+        );
+        assert_eq!(evaluate("a != a", &[("a", 1.0)]), Ok(0.0));
+    }
+
+    #[test]
+    fn test_logical_short_circuit() {
+        assert_eq!(compute("0 & f(x)"), "0.000");
+        assert_eq!(compute("1 | f(x)"), "1.000");
+        assert_eq!(compute("f(x) & 0"), "0.000");
+        assert_eq!(compute("f(x) & 1"), "f(x) & 1.000");
+        assert_eq!(compute("!a | 0"), "!a");
+    }
+
+    #[test]
+    fn test_logical_mixed() {
+        assert_eq!(compute("a & 1 | 0"), "a & 1.000");
+        assert_eq!(compute("a & 1 | 1"), "1.000");
+        assert_eq!(compute("a & (1 | b)"), "a & 1.000");
+        assert_eq!(compute("(a | b) & 1"), "a | b");
+    }
+
+    #[test]
+    fn test_same_subtrees() {
+        // Identifiers
+        assert_eq!(compute("a - a"), "0.000");
+        assert_eq!(compute("a / a"), "1.000");
+
+        // Function calls
+        assert_eq!(compute("sin(x) - sin(x)"), "0.000");
+        assert_eq!(compute("max(a, b) / max(a, b)"), "1.000");
+        assert_eq!(compute("f(a) - f(b)"), "f(a) - f(b)");
+
+        // Nested subtrees, compared up to commutative operands
+        assert_eq!(compute("a*b - a*b"), "0.000");
+        assert_eq!(compute("a*b - b*a"), "0.000");
+        assert_eq!(compute("(a + b*c) / (c*b + a)"), "1.000");
+        assert_eq!(compute("x + (a*b - b*a)"), "x");
+        assert_eq!(compute("(a - b) - (b - a)"), "a - b - (b - a)");
+    }
+
+    #[test]
+    fn test_same_subtrees_division_by_zero() {
+        for code in ["0/0", "(1 - 1)/(2 - 2)", "(a - a)/(b - b)"] {
+            let ast = parse(code);
+            assert!(
+                matches!(ast.compute(), Err(AstError::DivisionByZero(_))),
+                "Code: {}",
+                code
+            );
+        }
+    }
+
+    fn evaluate(code: &str, env: &[(&str, f64)]) -> Result<f64, AstError> {
+        let ast = parse(code);
+        let env: HashMap<String, f64> = env
+            .iter()
+            .map(|(name, value)| (name.to_string(), *value))
+            .collect();
+        ast.evaluate(&env)
+    }
+
+    #[test]
+    fn test_evaluate() {
+        let env = [("a", 3.0), ("b", 4.0)];
+
+        assert!(matches!(evaluate("a*b + 2", &env), Ok(14.0)));
+        assert!(matches!(evaluate("max(a, b) - sqrt(b)", &env), Ok(2.0)));
+        assert!(matches!(evaluate("0 & c | a", &env), Ok(1.0)));
+    }
+
+    #[test]
+    fn test_evaluate_with_arrays() {
+        let evaluate = |code: &str| {
+            let ast = parse(code);
+            let env = HashMap::from([("i".to_string(), 1.0)]);
+            let arrays = HashMap::from([
+                ("A".to_string(), ArrayValue::from(vec![10.0, 20.0, 30.0])),
+                (
+                    "M".to_string(),
+                    ArrayValue::from(vec![vec![1.0, 2.0], vec![3.0, 4.0]]),
+                ),
+            ]);
+            ast.evaluate_with_arrays(&env, &arrays)
+        };
+
+        assert_eq!(evaluate("A[1]"), Ok(20.0));
+        assert_eq!(evaluate("A[i + 1] - A[0]"), Ok(20.0));
+        assert_eq!(evaluate("M[1][0] + M[i, i]"), Ok(7.0));
+
+        assert_eq!(
+            evaluate("A[3]"),
+            Err(AstError::IndexOutOfBounds {
+                identifier: "A".to_string(),
+                index: 3.0,
+                length: 3,
+            })
+        );
+        assert!(matches!(
+            evaluate("A[-1]"),
+            Err(AstError::IndexOutOfBounds { length: 3, .. })
+        ));
+        assert!(matches!(
+            evaluate("A[1][0]"),
+            Err(AstError::IndexOutOfBounds { length: 0, .. })
+        ));
+        assert_eq!(
+            evaluate("A[i / 2]"),
+            Err(AstError::NonIntegerIndex("A".to_string(), 0.5))
+        );
+        assert_eq!(
+            evaluate("M[0]"),
+            Err(AstError::NotEvaluableArrayAccess("M".to_string()))
+        );
+        assert_eq!(
+            evaluate("B[0]"),
+            Err(AstError::NotEvaluableArrayAccess("B".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_string_policy() {
+        // Passes through the computing unchanged
+        assert_eq!(compute("f(a, \"x\")"), "f(a, \"x\")");
+        assert_eq!(compute("f(2 + 3, \"x\")"), "f(5.000, \"x\")");
+
+        let string = || Box::new(AstNode::StringLiteral("x".to_string()));
+        let addition = AbstractSyntaxTree::from_node(AstNode::BinaryOperation {
+            operation: BinaryOperationKind::Plus,
+            left: string(),
+            right: Box::new(AstNode::Number(1.0)),
+        });
+        let subtraction = AbstractSyntaxTree::from_node(AstNode::BinaryOperation {
+            operation: BinaryOperationKind::Minus,
+            left: string(),
+            right: string(),
+        });
+        let negation = AbstractSyntaxTree::from_node(AstNode::UnaryOperation {
+            operation: UnaryOperationKind::Minus,
+            expression: string(),
+        });
+
+        for tree in [addition, subtraction, negation] {
+            let Ok(computed) = tree.clone().compute() else {
+                panic!("Failed to compute: {:?}", tree)
+            };
+            assert_eq!(computed.peek, tree.peek);
+            assert_eq!(
+                tree.evaluate(&HashMap::new()),
+                Err(AstError::StringInArithmetic("x".to_string()))
+            );
+        }
+
+        // Not an operand of an operation
+        let env = [("a", 1.0)];
+        assert!(matches!(
+            evaluate("f(a, \"x\")", &env),
+            Err(AstError::NotEvaluableString(string)) if string == "x"
+        ));
+    }
+
+    #[test]
+    fn test_evaluate_errors() {
+        let env = [("a", 3.0)];
+
+        let Err(AstError::UndefinedVariable(name)) = evaluate("a*b + 2", &env) else {
+            panic!("Expected undefined variable error")
+        };
+        assert_eq!(name, "b");
+
+        assert!(matches!(
+            evaluate("f(a) + 1", &env),
+            Err(AstError::UnknownFunction(name, 1)) if name == "f"
+        ));
+        assert!(matches!(
+            evaluate("A[a] * 2", &env),
+            Err(AstError::NotEvaluableArrayAccess(name)) if name == "A"
+        ));
+        assert!(matches!(
+            evaluate("a / (a - 3)", &env),
+            Err(AstError::DivisionByZero(_))
+        ));
+    }
+
+    #[test]
+    fn test_non_finite_result() {
+        // Numbers in the source cannot overflow yet, so the tree is constructed
+        let overflow = AstNode::BinaryOperation {
+            operation: BinaryOperationKind::Multiply,
+            left: Box::new(AstNode::Number(1e308)),
+            right: Box::new(AstNode::Number(1e10)),
+        };
+        let ast = AbstractSyntaxTree::from_node(AstNode::BinaryOperation {
+            operation: BinaryOperationKind::Plus,
+            left: Box::new(AstNode::Identifier("x".to_string())),
+            right: Box::new(overflow.clone()),
+        });
+        assert_eq!(ast.compute(), Err(AstError::NonFiniteResult(overflow)));
+
+        let ast = AbstractSyntaxTree::from_node(AstNode::Number(f64::NAN));
+        assert!(matches!(ast.compute(), Err(AstError::NonFiniteResult(_))));
+    }
+
+    #[test]
+    fn test_negative_zero() {
+        assert_eq!(compute("-0.0"), "0.000");
+        assert_eq!(compute("x + (-0.0)"), "x");
+        assert_eq!(compute("x * (0 - 0) - 0.0 * 5"), "0.000");
+
+        let ast = AbstractSyntaxTree::from_node(AstNode::UnaryOperation {
+            operation: UnaryOperationKind::Minus,
+            expression: Box::new(AstNode::Number(0.0)),
+        });
+        let Ok(AbstractSyntaxTree {
+            peek: AstNode::Number(number),
+            ..
+        }) = ast.compute()
+        else {
+            panic!("Failed to fold -0")
+        };
+        assert!(number.is_sign_positive());
+    }
+
+    #[test]
+    fn test_constants() {
+        let ast = parse("2*pi + A[e] + max(pi, x)");
+
+        let Ok(computed) = ast
+            .clone()
+            .substitute_constants(&common_constants())
+            .compute()
+        else {
+            panic!("Failed to compute AST")
+        };
+        let expected = format!(
+            "{:.3} + A[{:.3}] + max({:.3}, x)",
+            2.0 * std::f64::consts::PI,
+            std::f64::consts::E,
+            std::f64::consts::PI
+        );
+        assert_eq!(computed.to_pretty_string(), expected);
+
+        // The config starts with them
+        assert_eq!(Config::default().constants, common_constants());
+
+        // Without constants, the names stay variables
+        let Ok(computed) = ast.substitute_constants(&BTreeMap::new()).compute() else {
+            panic!("Failed to compute AST")
+        };
+        assert_eq!(
+            computed.to_pretty_string(),
+            "2.000 * pi + A[e] + max(pi, x)"
+        );
+    }
+
+    #[test]
+    fn test_constants_override() {
+        let ast = parse("2*pi + e");
+        let constants = common_constants();
+
+        let env = HashMap::from([("pi".to_string(), 3.0)]);
+        assert_eq!(
+            ast.evaluate_with_constants(&env, &constants, false),
+            Ok(6.0 + std::f64::consts::E)
+        );
+        assert_eq!(
+            ast.evaluate_with_constants(&HashMap::new(), &constants, false),
+            Ok(2.0 * std::f64::consts::PI + std::f64::consts::E)
+        );
+        assert!(matches!(
+            ast.evaluate_with_constants(&env, &BTreeMap::new(), false),
+            Err(AstError::UndefinedVariable(name)) if name == "e"
+        ));
+    }
+
+    #[test]
+    fn test_case_insensitive_names() {
+        let ast = parse("SIN(0) + Sqrt(4)*x + 2*PI + f(0)");
+
+        // Case-sensitive by default: the calls are left as they are
+        let Ok(computed) = ast
+            .clone()
+            .substitute_constants(&common_constants())
+            .compute()
+        else {
+            panic!("Failed to compute AST")
+        };
+        assert_eq!(
+            computed.to_pretty_string(),
+            "SIN(0.000) + Sqrt(4.000) * x + 2.000 * PI + f(0.000)"
+        );
+
+        // Constants are substituted in any case, calls are not computed
+        let Ok(computed) = ast
+            .clone()
+            .substitute_constants_with_case(&common_constants(), true)
+            .compute()
+        else {
+            panic!("Failed to compute AST")
+        };
+        assert_eq!(
+            computed.to_pretty_string(),
+            format!(
+                "SIN(0.000) + Sqrt(4.000) * x + {:.3} + f(0.000)",
+                2.0 * std::f64::consts::PI
+            )
+        );
+        assert_eq!(compute("sqrt(4) * a"), "sqrt(4.000) * a");
+
+        // Evaluation finds the known functions and constants in any case
+        let sin = parse("SIN(0) + Sqrt(4) * PI");
+        let env = HashMap::new();
+        assert_eq!(
+            sin.evaluate_with_constants(&env, &common_constants(), true),
+            Ok(2.0 * std::f64::consts::PI)
+        );
+        assert!(matches!(
+            sin.evaluate_with_constants(&env, &common_constants(), false),
+            Err(AstError::UnknownFunction(name, 1)) if name == "SIN"
+        ));
+        // Variables are still case-sensitive
+        let x = parse("X + pi");
+        let env = HashMap::from([("x".to_string(), 1.0)]);
+        assert!(matches!(
+            x.evaluate_with_constants(&env, &common_constants(), true),
+            Err(AstError::UndefinedVariable(name)) if name == "X"
+        ));
+    }
+
+    #[test]
+    fn test_escaped_strings() {
+        // One argument with quotes inside is not the same call as two arguments
+        assert_eq!(
+            compute(r#"f("a\", \"b", 1) - f("a", "b", 1)"#),
+            r#"f("a\", \"b", 1.000) - f("a", "b", 1.000)"#
+        );
+        assert_eq!(compute(r#"f("a\"b", 1) - f("a\"b", 1)"#), "0.000");
+    }
+
+    #[test]
+    fn test_zero_divisor_span() {
+        let mut context = CompilerContext::new(&Config::default());
+        context.code = "b + 1/(a - a)".to_string();
+        let report = context.compute_1_report();
+        let Some((_, highlighted)) = report.split_once("\n\n") else {
+            panic!("No highlighted source in:\n{}", report)
+        };
+        assert!(highlighted.starts_with(concat!(
+            "Divisor \"(a - a)\" computes to zero:\n",
+            "\n",
+            "b + 1/(a - a)\n",
+            "      ^-----^",
+        )));
+
+        // Innermost divisor, the whole subtree is not zero
+        assert_eq!(process_divisor("x / (2 + y % (pi - pi))"), Some(14..21));
+        assert_eq!(process_divisor("a / b"), None);
+        assert_eq!(process_divisor("a / 0"), Some(4..5));
+    }
+
+    fn process_divisor(code: &str) -> Option<Range<usize>> {
+        let Ok(ast) = AstParser::with_positions(positioned_lexemes(code)).parse() else {
+            panic!("Failed to build AST for: {}", code)
+        };
+        match ast.clone().compute() {
+            Err(error) => ast.zero_divisor_span(&error),
+            Ok(_) => None,
+        }
+    }
+}
@@ -0,0 +1,123 @@
+use crate::compiler::ast::tree::{AbstractSyntaxTree, AstNode, AstParser};
+use crate::compiler::lexer::{Lexeme, Lexer, PositionedLexeme};
+use crate::compiler::tokenizer::Tokenizer;
+
+/// Tree of the code, without positions and with the default parser settings.
+pub fn parse(code: &str) -> AbstractSyntaxTree {
+    let Ok(tree) = AstParser::new(lexemes(code)).parse() else {
+        panic!("Failed to build AST for: {}", code)
+    };
+    tree
+}
+
+pub fn lexemes(code: &str) -> Vec<Lexeme> {
+    let Ok(lexemes) = Lexer::new(Tokenizer::process(code)).run() else {
+        panic!("Failed to create lexemes for: {}", code)
+    };
+    lexemes
+}
+
+pub fn positioned_lexemes(code: &str) -> Vec<PositionedLexeme> {
+    let Ok(lexemes) = Lexer::new(Tokenizer::process(code)).run_with_positions() else {
+        panic!("Failed to create lexemes for: {}", code)
+    };
+    lexemes
+}
+
+/// Same as `assert_eq!` for trees, but numbers may differ by `epsilon`,
+/// so results of float computations like `2 - 4.8/2` are compared safely.
+pub fn assert_ast_approx_eq(
+    actual: &AbstractSyntaxTree, expected: &AbstractSyntaxTree, epsilon: f64,
+) {
+    assert!(
+        nodes_approx_eq(&actual.peek, &expected.peek, epsilon),
+        "Trees are not equal (epsilon = {})\n  left: {:?}\n right: {:?}",
+        epsilon,
+        actual.peek,
+        expected.peek
+    );
+}
+
+pub fn nodes_approx_eq(left: &AstNode, right: &AstNode, epsilon: f64) -> bool {
+    let is_same_node = match (left, right) {
+        (AstNode::Number(left), AstNode::Number(right)) => {
+            return (left - right).abs() <= epsilon;
+        },
+        (
+            AstNode::FunctionCall { name: left, .. },
+            AstNode::FunctionCall { name: right, .. },
+        ) => left == right,
+        (
+            AstNode::ArrayAccess {
+                identifier: left, ..
+            },
+            AstNode::ArrayAccess {
+                identifier: right, ..
+            },
+        ) => left == right,
+        (
+            AstNode::UnaryOperation {
+                operation: left, ..
+            },
+            AstNode::UnaryOperation {
+                operation: right, ..
+            },
+        ) => left == right,
+        (
+            AstNode::BinaryOperation {
+                operation: left, ..
+            },
+            AstNode::BinaryOperation {
+                operation: right, ..
+            },
+        ) => left == right,
+        (AstNode::Ternary { .. }, AstNode::Ternary { .. }) => true,
+        // Identifiers and strings, or nodes of different kinds
+        _ => left == right,
+    };
+
+    let (left_children, right_children) = (left.children(), right.children());
+    is_same_node
+        && left_children.len() == right_children.len()
+        && left_children
+            .iter()
+            .zip(right_children.iter())
+            .all(|(left, right)| nodes_approx_eq(left, right, epsilon))
+}
+
+mod tests {
+    use super::*;
+    use crate::compiler::ast::tree::BinaryOperationKind;
+
+    #[test]
+    fn test_nodes_approx_eq() {
+        let tree =
+            |number: f64, operation: BinaryOperationKind| AstNode::BinaryOperation {
+                operation,
+                left: Box::new(AstNode::Identifier("i".to_string())),
+                right: Box::new(AstNode::Number(number)),
+            };
+
+        let exact = tree(-0.4, BinaryOperationKind::Plus);
+        assert!(nodes_approx_eq(
+            &tree(-0.3999999999999999, BinaryOperationKind::Plus),
+            &exact,
+            1e-9
+        ));
+        assert!(!nodes_approx_eq(
+            &tree(-0.39, BinaryOperationKind::Plus),
+            &exact,
+            1e-9
+        ));
+        assert!(!nodes_approx_eq(
+            &tree(-0.4, BinaryOperationKind::Minus),
+            &exact,
+            1e-9
+        ));
+        assert!(!nodes_approx_eq(
+            &AstNode::Identifier("i".to_string()),
+            &exact,
+            1e-9
+        ));
+    }
+}
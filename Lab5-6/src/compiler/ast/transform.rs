@@ -144,6 +144,16 @@ impl AbstractSyntaxTree {
                     indices: transformed_indices,
                 })
             },
+
+            AstNode::Ternary {
+                condition,
+                then_branch,
+                else_branch,
+            } => Ok(AstNode::Ternary {
+                condition: Box::new(Self::transform_recursive(*condition)?),
+                then_branch: Box::new(Self::transform_recursive(*then_branch)?),
+                else_branch: Box::new(Self::transform_recursive(*else_branch)?),
+            }),
         }
     }
 
@@ -240,7 +250,7 @@ impl Reporter {
                 buffer.add_line(
                     "Transformed Abstract-Syntax Tree generation success!\n".to_string(),
                 );
-                buffer.add_line(tree.pretty_print());
+                buffer.add_line(tree.pretty_print_with(&self.number_format));
             },
             Err(error) => buffer.add_line(format!(
                 "Transformed Abstract-Syntax Tree generation error: {}",
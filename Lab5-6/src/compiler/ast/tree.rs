@@ -1,42 +1,93 @@
-use crate::compiler::lexer::Lexeme;
+use crate::compiler::ast::validation;
+use crate::compiler::lexer::{Lexeme, PositionedLexeme};
 use crate::compiler::reports::Reporter;
-use crate::utils::StringBuffer;
+use crate::utils::{StringBuffer, escape_string};
+use std::collections::BTreeMap;
+use std::ops::Range;
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone)]
 pub struct AbstractSyntaxTree {
     pub peek: AstNode,
+    /// Source positions of the nodes. Only trees parsed from positioned lexemes
+    /// have them, transformed trees are built without.
+    pub spans: Option<NodeSpan>,
+}
+
+// Trees are equal by their nodes, positions don't matter
+impl PartialEq for AbstractSyntaxTree {
+    fn eq(&self, other: &Self) -> bool {
+        self.peek == other.peek
+    }
+}
+
+/// Source range of a node. Children follow the order of `AstNode::children`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct NodeSpan {
+    pub position: Range<usize>,
+    pub children: Vec<NodeSpan>,
 }
 
 impl AbstractSyntaxTree {
     pub fn from_node(node: AstNode) -> Self {
-        Self { peek: node }
+        Self {
+            peek: node,
+            spans: None,
+        }
     }
 
     pub fn pretty_print(&self) -> String {
+        self.pretty_print_with(&NumberFormat::default())
+    }
+
+    pub fn pretty_print_with(&self, format: &NumberFormat) -> String {
         let mut buffer = StringBuffer::default();
-        Self::print_recursive(&self.peek, &mut buffer, "".to_string(), true);
+        for line in self.pretty_print_lines_with(format) {
+            buffer.add_line(format!("{}{}", line.prefix, line.text));
+        }
         buffer.get()
     }
 
+    pub fn pretty_print_lines(&self) -> Vec<PrettyLine> {
+        self.pretty_print_lines_with(&NumberFormat::default())
+    }
+
+    pub fn pretty_print_lines_with(&self, format: &NumberFormat) -> Vec<PrettyLine> {
+        let mut lines = Vec::new();
+        Self::print_recursive(&self.peek, &mut lines, "".to_string(), true, format);
+        lines
+    }
+
     fn print_recursive(
-        node: &AstNode, buffer: &mut StringBuffer, prefix: String, is_last: bool,
+        node: &AstNode, lines: &mut Vec<PrettyLine>, prefix: String, is_last: bool,
+        format: &NumberFormat,
     ) {
         let connector = if is_last { "└── " } else { "├── " };
 
-        buffer.add(format!("{}{}", prefix, connector));
-
-        let node_text = match node {
-            AstNode::Number(n) => format!("{n:.3}"),
-            AstNode::Identifier(s) => s.to_string(),
-            AstNode::StringLiteral(s) => format!("\"{}\"", s),
-            AstNode::UnaryOperation { operation, .. } => operation.to_string(),
-            AstNode::BinaryOperation { operation, .. } => operation.to_string(),
-            AstNode::FunctionCall { name, .. } => format!("{}(...)", name),
+        let (text, kind) = match node {
+            AstNode::Number(n) => (format.apply(*n), PrettyNodeKind::Number),
+            AstNode::Identifier(s) => (s.to_string(), PrettyNodeKind::Identifier),
+            AstNode::StringLiteral(s) => {
+                (escape_string(s), PrettyNodeKind::StringLiteral)
+            },
+            AstNode::UnaryOperation { operation, .. } => {
+                (operation.to_string(), PrettyNodeKind::Operator)
+            },
+            AstNode::BinaryOperation { operation, .. } => {
+                (operation.to_string(), PrettyNodeKind::Operator)
+            },
+            AstNode::FunctionCall { name, .. } => {
+                (format!("{}(...)", name), PrettyNodeKind::FunctionCall)
+            },
             AstNode::ArrayAccess { identifier, .. } => {
-                format!("{}[...]", identifier)
+                (format!("{}[...]", identifier), PrettyNodeKind::ArrayAccess)
             },
+            AstNode::Ternary { .. } => ("?:".to_string(), PrettyNodeKind::Operator),
         };
-        buffer.add_line(node_text);
+        lines.push(PrettyLine {
+            prefix: format!("{}{}", prefix, connector),
+            text,
+            kind,
+        });
 
         let new_prefix = prefix + if is_last { "    " } else { "│   " };
 
@@ -44,19 +95,25 @@ impl AbstractSyntaxTree {
             AstNode::Number(_) | AstNode::Identifier(_) | AstNode::StringLiteral(_) => {},
 
             AstNode::UnaryOperation { expression, .. } => {
-                Self::print_recursive(expression, buffer, new_prefix, true);
+                Self::print_recursive(expression, lines, new_prefix, true, format);
             },
 
             AstNode::BinaryOperation { left, right, .. } => {
-                Self::print_recursive(left, buffer, new_prefix.clone(), false);
-                Self::print_recursive(right, buffer, new_prefix, true);
+                Self::print_recursive(left, lines, new_prefix.clone(), false, format);
+                Self::print_recursive(right, lines, new_prefix, true, format);
             },
 
             AstNode::FunctionCall { arguments, .. } => {
                 let arg_count = arguments.len();
                 for (i, arg) in arguments.iter().enumerate() {
                     let is_last_arg = i == arg_count - 1;
-                    Self::print_recursive(arg, buffer, new_prefix.clone(), is_last_arg);
+                    Self::print_recursive(
+                        arg,
+                        lines,
+                        new_prefix.clone(),
+                        is_last_arg,
+                        format,
+                    );
                 }
             },
 
@@ -67,21 +124,104 @@ impl AbstractSyntaxTree {
                 let dimensions = indices.len();
                 for (i, index) in indices.iter().enumerate() {
                     let is_last_arg = i == dimensions - 1;
-                    Self::print_recursive(index, buffer, new_prefix.clone(), is_last_arg);
+                    Self::print_recursive(
+                        index,
+                        lines,
+                        new_prefix.clone(),
+                        is_last_arg,
+                        format,
+                    );
                 }
             },
+
+            AstNode::Ternary {
+                condition,
+                then_branch,
+                else_branch,
+            } => {
+                Self::print_recursive(
+                    condition,
+                    lines,
+                    new_prefix.clone(),
+                    false,
+                    format,
+                );
+                Self::print_recursive(
+                    then_branch,
+                    lines,
+                    new_prefix.clone(),
+                    false,
+                    format,
+                );
+                Self::print_recursive(else_branch, lines, new_prefix, true, format);
+            },
+        }
+    }
+
+    /// Number of levels in the tree, a single leaf has height 1.
+    pub fn height(&self) -> usize {
+        Self::node_height(&self.peek)
+    }
+
+    fn node_height(node: &AstNode) -> usize {
+        1 + node
+            .children()
+            .into_iter()
+            .map(Self::node_height)
+            .max()
+            .unwrap_or(0)
+    }
+
+    pub fn node_count(&self) -> usize {
+        Self::count_nodes(&self.peek)
+    }
+
+    fn count_nodes(node: &AstNode) -> usize {
+        1 + node
+            .children()
+            .into_iter()
+            .map(Self::count_nodes)
+            .sum::<usize>()
+    }
+
+    /// Nodes in pre-order: a node goes before its children,
+    /// and the children follow the order of `AstNode::children`.
+    pub fn iter(&self) -> impl Iterator<Item = &AstNode> {
+        let mut stack = vec![&self.peek];
+
+        std::iter::from_fn(move || {
+            let node = stack.pop()?;
+            stack.extend(node.children().into_iter().rev());
+            Some(node)
+        })
+    }
+
+    /// Calls `f` on every node in the order of `iter`.
+    /// Children are visited after `f`, so the ones of a replaced node are the new ones.
+    pub fn for_each_mut(&mut self, mut f: impl FnMut(&mut AstNode)) {
+        Self::node_for_each_mut(&mut self.peek, &mut f);
+    }
+
+    fn node_for_each_mut(node: &mut AstNode, f: &mut impl FnMut(&mut AstNode)) {
+        f(node);
+        for child in node.children_mut() {
+            Self::node_for_each_mut(child, f);
         }
     }
 
     pub fn to_canonical_string(&self) -> String {
-        Self::node_to_canonical_string(&self.peek)
+        self.to_canonical_string_with(&NumberFormat::default())
     }
 
-    fn node_to_canonical_string(node: &AstNode) -> String {
+    pub fn to_canonical_string_with(&self, format: &NumberFormat) -> String {
+        Self::node_to_canonical_string(&self.peek, format)
+    }
+
+    fn node_to_canonical_string(node: &AstNode, format: &NumberFormat) -> String {
         match node {
-            AstNode::Number(n) => format!("{:.2}", n),
+            AstNode::Number(n) => format.apply(*n),
             AstNode::Identifier(s) => s.clone(),
-            AstNode::StringLiteral(s) => format!("\"{}\"", s),
+            AstNode::StringLiteral(s) => escape_string(s),
             AstNode::UnaryOperation {
                 operation,
                 expression,
@@ -89,13 +229,13 @@ impl AbstractSyntaxTree {
                 format!(
                     "({}{})",
                     operation,
-                    Self::node_to_canonical_string(expression)
+                    Self::node_to_canonical_string(expression, format)
                 )
             },
             AstNode::FunctionCall { name, arguments } => {
                 let args = arguments
                     .iter()
-                    .map(Self::node_to_canonical_string)
+                    .map(|node| Self::node_to_canonical_string(node, format))
                     .collect::<Vec<String>>()
                     .join(", ");
                 format!("{}({})", name, args)
@@ -106,18 +246,28 @@ impl AbstractSyntaxTree {
             } => {
                 let idx = indices
                     .iter()
-                    .map(Self::node_to_canonical_string)
+                    .map(|node| Self::node_to_canonical_string(node, format))
                     .map(|s| format!("[{}]", s))
                     .collect::<String>();
                 format!("{}{}", identifier, idx)
             },
+            AstNode::Ternary {
+                condition,
+                then_branch,
+                else_branch,
+            } => format!(
+                "({} ? {} : {})",
+                Self::node_to_canonical_string(condition, format),
+                Self::node_to_canonical_string(then_branch, format),
+                Self::node_to_canonical_string(else_branch, format)
+            ),
             AstNode::BinaryOperation {
                 operation,
                 left,
                 right,
             } => {
-                let l_str = Self::node_to_canonical_string(left);
-                let r_str = Self::node_to_canonical_string(right);
+                let l_str = Self::node_to_canonical_string(left, format);
+                let r_str = Self::node_to_canonical_string(right, format);
 
                 // Sorting for commutative operations
                 match operation {
@@ -137,37 +287,225 @@ impl AbstractSyntaxTree {
     /// Creates a readable string representation, adding parentheses only
     /// when required by operator precedence.
     pub fn to_pretty_string(&self) -> String {
+        self.to_pretty_string_with(&PrettyOptions::default())
+    }
+
+    /// Same as `to_pretty_string`, but with explicit formatting options.
+    pub fn to_pretty_string_with(&self, options: &PrettyOptions) -> String {
         // Start recursion with the lowest parent precedence (0).
-        Self::node_to_pretty_string(&self.peek, 0)
+        Self::node_to_pretty_string(&self.peek, 0, options)
+    }
+
+    /// Like `to_pretty_string`, but every binary operation and ternary is
+    /// parenthesized, so the grouping never depends on precedence rules.
+    pub fn to_fully_parenthesized_string(&self) -> String {
+        self.to_fully_parenthesized_string_with(&PrettyOptions::default())
+    }
+
+    pub fn to_fully_parenthesized_string_with(&self, options: &PrettyOptions) -> String {
+        Self::node_to_parenthesized_string(&self.peek, options)
+    }
+
+    fn node_to_parenthesized_string(node: &AstNode, options: &PrettyOptions) -> String {
+        let to_string =
+            |node: &AstNode| Self::node_to_parenthesized_string(node, options);
+
+        match node {
+            AstNode::Number(_) | AstNode::Identifier(_) | AstNode::StringLiteral(_) => {
+                Self::node_to_pretty_string(node, 0, options)
+            },
+            AstNode::FunctionCall { name, arguments } => {
+                let args = arguments
+                    .iter()
+                    .map(to_string)
+                    .collect::<Vec<String>>()
+                    .join(options.separator());
+                format!("{}({})", name, args)
+            },
+            AstNode::ArrayAccess {
+                identifier,
+                indices,
+            } => {
+                let idx = indices
+                    .iter()
+                    .map(to_string)
+                    .collect::<Vec<String>>()
+                    .join(options.separator());
+                format!("{}[{}]", identifier, idx)
+            },
+            AstNode::Ternary {
+                condition,
+                then_branch,
+                else_branch,
+            } => format!(
+                "({} ? {} : {})",
+                to_string(condition),
+                to_string(then_branch),
+                to_string(else_branch)
+            ),
+            AstNode::UnaryOperation {
+                operation,
+                expression,
+            } => format!("{}{}", operation, to_string(expression)),
+            AstNode::BinaryOperation {
+                operation,
+                left,
+                right,
+            } => match (operation, left.as_ref(), right.as_ref()) {
+                // Same rewrites as in `to_pretty_string`:
+                // A + (-B) => A - B, (-A) + B => B - A
+                (
+                    BinaryOperationKind::Plus,
+                    _,
+                    AstNode::UnaryOperation {
+                        operation: UnaryOperationKind::Minus,
+                        expression: inner_right,
+                    },
+                ) => format!("({} - {})", to_string(left), to_string(inner_right)),
+                (
+                    BinaryOperationKind::Plus,
+                    AstNode::UnaryOperation {
+                        operation: UnaryOperationKind::Minus,
+                        expression: inner_left,
+                    },
+                    _,
+                ) => format!("({} - {})", to_string(right), to_string(inner_left)),
+                _ => format!("({} {} {})", to_string(left), operation, to_string(right)),
+            },
+        }
+    }
+
+    /// Like `to_fully_parenthesized_string`, but a whole chain of `+` or `*`
+    /// is printed in one pair of parentheses, however it is nested in the tree:
+    /// `((a + b) + (c + d)) * e` => `(a + b + c + d) * e`.
+    pub fn to_flat_string(&self) -> String {
+        self.to_flat_string_with(&PrettyOptions::default())
+    }
+
+    pub fn to_flat_string_with(&self, options: &PrettyOptions) -> String {
+        Self::node_to_flat_string(&self.peek, options)
+    }
+
+    fn node_to_flat_string(node: &AstNode, options: &PrettyOptions) -> String {
+        let to_string = |node: &AstNode| Self::node_to_flat_string(node, options);
+        // Operands are parenthesized, unless they are atomic
+        let operand = |node: &AstNode| match node {
+            AstNode::BinaryOperation { .. } | AstNode::Ternary { .. } => {
+                format!("({})", to_string(node))
+            },
+            _ => to_string(node),
+        };
+
+        match node {
+            AstNode::Number(_) | AstNode::Identifier(_) | AstNode::StringLiteral(_) => {
+                Self::node_to_pretty_string(node, 0, options)
+            },
+            AstNode::FunctionCall { name, arguments } => {
+                let args = arguments
+                    .iter()
+                    .map(to_string)
+                    .collect::<Vec<String>>()
+                    .join(options.separator());
+                format!("{}({})", name, args)
+            },
+            AstNode::ArrayAccess {
+                identifier,
+                indices,
+            } => {
+                let idx = indices
+                    .iter()
+                    .map(to_string)
+                    .collect::<Vec<String>>()
+                    .join(options.separator());
+                format!("{}[{}]", identifier, idx)
+            },
+            AstNode::Ternary {
+                condition,
+                then_branch,
+                else_branch,
+            } => format!(
+                "{} ? {} : {}",
+                operand(condition),
+                operand(then_branch),
+                operand(else_branch)
+            ),
+            AstNode::UnaryOperation {
+                operation,
+                expression,
+            } => format!("{}{}", operation, operand(expression)),
+            AstNode::BinaryOperation {
+                operation:
+                    operation @ (BinaryOperationKind::Plus | BinaryOperationKind::Multiply),
+                ..
+            } => {
+                let mut operands = Vec::new();
+                Self::collect_operands(node.clone(), operation.clone(), &mut operands);
+                operands
+                    .iter()
+                    .map(operand)
+                    .collect::<Vec<String>>()
+                    .join(&format!(" {} ", operation))
+            },
+            AstNode::BinaryOperation {
+                operation,
+                left,
+                right,
+            } => format!("{} {} {}", operand(left), operation, operand(right)),
+        }
     }
 
     /// Recursive helper for `to_pretty_string`.
-    fn node_to_pretty_string(node: &AstNode, parent_precedence: u8) -> String {
+    fn node_to_pretty_string(
+        node: &AstNode, parent_precedence: u8, options: &PrettyOptions,
+    ) -> String {
         match node {
             // Atomic nodes just return their string.
-            AstNode::Number(n) => format!("{n:.2}"),
+            AstNode::Number(n) => options.number_format.apply(*n),
             AstNode::Identifier(s) => s.clone(),
-            AstNode::StringLiteral(s) => format!("\"{}\"", s),
+            AstNode::StringLiteral(s) => escape_string(s),
 
             AstNode::FunctionCall { name, arguments } => {
                 let args = arguments
                     .iter()
-                    .map(|arg| Self::node_to_pretty_string(arg, 0))
+                    .map(|arg| Self::node_to_pretty_string(arg, 0, options))
                     .collect::<Vec<String>>()
-                    .join(", ");
+                    .join(options.separator());
                 format!("{}({})", name, args)
             },
 
+            // All dimensions are printed in one pair of brackets: `A[i][j]` => `A[i, j]`
             AstNode::ArrayAccess {
                 identifier,
                 indices,
             } => {
                 let idx = indices
                     .iter()
-                    .map(|idx| Self::node_to_pretty_string(idx, 0))
-                    .map(|s| format!("[{}]", s))
-                    .collect::<String>();
-                format!("{}{}", identifier, idx)
+                    .map(|idx| Self::node_to_pretty_string(idx, 0, options))
+                    .collect::<Vec<String>>()
+                    .join(options.separator());
+                format!("{}[{}]", identifier, idx)
+            },
+
+            // Lowest precedence: parenthesized inside any operation.
+            // The condition is parenthesized if it is a ternary itself,
+            // the branches are not, since the operator is right-associative.
+            AstNode::Ternary {
+                condition,
+                then_branch,
+                else_branch,
+            } => {
+                let result = format!(
+                    "{} ? {} : {}",
+                    Self::node_to_pretty_string(condition, 1, options),
+                    Self::node_to_pretty_string(then_branch, 0, options),
+                    Self::node_to_pretty_string(else_branch, 0, options)
+                );
+
+                if parent_precedence > 0 {
+                    format!("({})", result)
+                } else {
+                    result
+                }
             },
 
             AstNode::UnaryOperation {
@@ -175,7 +513,8 @@ impl AbstractSyntaxTree {
                 expression,
             } => {
                 let my_precedence = 3;
-                let expr_str = Self::node_to_pretty_string(expression, my_precedence);
+                let expr_str =
+                    Self::node_to_pretty_string(expression, my_precedence, options);
                 let result = format!("{}{}", operation, expr_str);
 
                 if my_precedence < parent_precedence {
@@ -199,9 +538,13 @@ impl AbstractSyntaxTree {
                         expression: inner_right,
                     } = right.as_ref()
                     {
-                        let l_str = Self::node_to_pretty_string(left, my_precedence);
-                        let r_str =
-                            Self::node_to_pretty_string(inner_right, my_precedence + 1);
+                        let l_str =
+                            Self::node_to_pretty_string(left, my_precedence, options);
+                        let r_str = Self::node_to_pretty_string(
+                            inner_right,
+                            my_precedence + 1,
+                            options,
+                        );
                         let result = format!("{} - {}", l_str, r_str);
                         if my_precedence < parent_precedence {
                             return format!("({})", result);
@@ -217,9 +560,13 @@ impl AbstractSyntaxTree {
                     } = left.as_ref()
                     {
                         // We format this as "B - A"
-                        let l_str_inner =
-                            Self::node_to_pretty_string(inner_left, my_precedence + 1);
-                        let r_str = Self::node_to_pretty_string(right, my_precedence);
+                        let l_str_inner = Self::node_to_pretty_string(
+                            inner_left,
+                            my_precedence + 1,
+                            options,
+                        );
+                        let r_str =
+                            Self::node_to_pretty_string(right, my_precedence, options);
                         // Note the swap: r_str - l_str_inner
                         let result = format!("{} - {}", r_str, l_str_inner);
                         if my_precedence < parent_precedence {
@@ -231,18 +578,22 @@ impl AbstractSyntaxTree {
                 }
 
                 let (left_prec, right_prec) = match operation {
-                    // For `A - B` or `A / B`, the right side (B)
+                    // For `A - B`, `A / B` or `A % B`, the right side (B)
                     // needs parentheses if it has the same precedence.
                     // e.g., A - (B - C) must keep its parentheses.
-                    BinaryOperationKind::Minus | BinaryOperationKind::Divide => {
+                    BinaryOperationKind::Minus
+                    | BinaryOperationKind::Divide
+                    | BinaryOperationKind::Modulo => (my_precedence, my_precedence + 1),
+                    // `a < (b < c)` as well
+                    operation if operation.is_comparison() => {
                         (my_precedence, my_precedence + 1)
                     },
                     // For associative ops `+` and `*`, just pass our own precedence.
                     _ => (my_precedence, my_precedence),
                 };
 
-                let l_str = Self::node_to_pretty_string(left, left_prec);
-                let r_str = Self::node_to_pretty_string(right, right_prec);
+                let l_str = Self::node_to_pretty_string(left, left_prec, options);
+                let r_str = Self::node_to_pretty_string(right, right_prec, options);
 
                 let result = format!("{} {} {}", l_str, operation, r_str);
 
@@ -258,10 +609,119 @@ impl AbstractSyntaxTree {
 
 impl BinaryOperationKind {
     /// Returns the precedence level for this operator.
-    fn precedence(&self) -> u8 {
+    pub(crate) fn precedence(&self) -> u8 {
         match self {
+            Self::Less
+            | Self::LessOrEqual
+            | Self::Greater
+            | Self::GreaterOrEqual
+            | Self::Equal
+            | Self::NotEqual => 0,
             Self::Plus | Self::Minus | Self::Or => 1,
-            Self::Multiply | Self::Divide | Self::And => 2,
+            Self::Multiply | Self::Divide | Self::Modulo | Self::And => 2,
+        }
+    }
+
+    pub(crate) fn is_comparison(&self) -> bool {
+        self.precedence() == 0
+    }
+}
+
+/// How numbers are printed by `pretty_print`, `to_canonical_string`
+/// and `to_pretty_string`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NumberFormat {
+    /// Fixed amount of decimal places: `0.125` is `0.13` with 2 places.
+    Fixed(usize),
+    /// Shortest representation without losing precision: `0.3999999999999999`.
+    Full,
+}
+
+impl NumberFormat {
+    /// Decimal places of the default format, used by every printer and report.
+    pub const DEFAULT_PRECISION: usize = 3;
+    /// An `f64` has only about 15 significant decimal digits.
+    pub const MAX_PRECISION: usize = 15;
+
+    pub fn apply(&self, number: f64) -> String {
+        match self {
+            Self::Fixed(precision) => {
+                // `{:.2}` alone rounds ties to even (0.125 -> 0.12),
+                // so rounding half away from zero is done first.
+                let factor = 10f64.powi(*precision as i32);
+                let rounded = (number * factor).round() / factor;
+                let rounded = if rounded.is_finite() { rounded } else { number };
+                format!("{:.*}", precision, rounded)
+            },
+            Self::Full => number.to_string(),
+        }
+    }
+}
+
+impl Default for NumberFormat {
+    fn default() -> Self {
+        Self::Fixed(Self::DEFAULT_PRECISION)
+    }
+}
+
+impl std::fmt::Display for NumberFormat {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Fixed(precision) => write!(f, "{}", precision),
+            Self::Full => write!(f, "full"),
+        }
+    }
+}
+
+impl std::str::FromStr for NumberFormat {
+    type Err = String;
+
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        match text.trim().to_lowercase().as_str() {
+            "full" => Ok(Self::Full),
+            precision => match precision.parse() {
+                Ok(places) if places <= Self::MAX_PRECISION => Ok(Self::Fixed(places)),
+                _ => Err(precision.to_string()),
+            },
+        }
+    }
+}
+
+/// Formatting options for `to_pretty_string_with`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PrettyOptions {
+    /// Put a space after commas between function arguments and array indices.
+    pub spaced_lists: bool,
+    pub number_format: NumberFormat,
+}
+
+impl Default for PrettyOptions {
+    fn default() -> Self {
+        Self::spaced()
+    }
+}
+
+impl PrettyOptions {
+    /// `f(a, b)`, `A[i, j]`
+    pub fn spaced() -> Self {
+        Self {
+            spaced_lists: true,
+            number_format: NumberFormat::default(),
+        }
+    }
+
+    /// `f(a,b)`, `A[i,j]`
+    pub fn compact() -> Self {
+        Self {
+            spaced_lists: false,
+            number_format: NumberFormat::default(),
+        }
+    }
+
+    fn separator(&self) -> &'static str {
+        match self.spaced_lists {
+            true => ", ",
+            false => ",",
         }
     }
 }
@@ -288,6 +748,48 @@ pub enum AstNode {
         identifier: String,
         indices: Vec<AstNode>,
     },
+    Ternary {
+        condition: Box<AstNode>,
+        then_branch: Box<AstNode>,
+        else_branch: Box<AstNode>,
+    },
+}
+
+impl AstNode {
+    /// Direct children in the order they are printed.
+    pub fn children(&self) -> Vec<&AstNode> {
+        match self {
+            AstNode::Number(_) | AstNode::Identifier(_) | AstNode::StringLiteral(_) => {
+                vec![]
+            },
+            AstNode::UnaryOperation { expression, .. } => vec![expression],
+            AstNode::BinaryOperation { left, right, .. } => vec![left, right],
+            AstNode::FunctionCall { arguments, .. } => arguments.iter().collect(),
+            AstNode::ArrayAccess { indices, .. } => indices.iter().collect(),
+            AstNode::Ternary {
+                condition,
+                then_branch,
+                else_branch,
+            } => vec![condition, then_branch, else_branch],
+        }
+    }
+
+    pub fn children_mut(&mut self) -> Vec<&mut AstNode> {
+        match self {
+            AstNode::Number(_) | AstNode::Identifier(_) | AstNode::StringLiteral(_) => {
+                vec![]
+            },
+            AstNode::UnaryOperation { expression, .. } => vec![expression],
+            AstNode::BinaryOperation { left, right, .. } => vec![left, right],
+            AstNode::FunctionCall { arguments, .. } => arguments.iter_mut().collect(),
+            AstNode::ArrayAccess { indices, .. } => indices.iter_mut().collect(),
+            AstNode::Ternary {
+                condition,
+                then_branch,
+                else_branch,
+            } => vec![condition, then_branch, else_branch],
+        }
+    }
 }
 
 #[derive(Debug, Clone, PartialEq)]
@@ -296,48 +798,269 @@ pub enum UnaryOperationKind {
     Not,
 }
 
+#[derive(Debug, Clone, PartialEq)]
+pub struct PrettyLine {
+    pub prefix: String,
+    pub text: String,
+    pub kind: PrettyNodeKind,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PrettyNodeKind {
+    Operator,
+    Number,
+    Identifier,
+    StringLiteral,
+    FunctionCall,
+    ArrayAccess,
+}
+
 #[derive(Debug, Clone, PartialEq)]
 pub enum BinaryOperationKind {
     Plus,
     Minus,
     Multiply,
     Divide,
+    Modulo,
     Or,
     And,
+    // Comparisons are `1` if true and `0` otherwise
+    Less,
+    LessOrEqual,
+    Greater,
+    GreaterOrEqual,
+    Equal,
+    NotEqual,
 }
 
+// Deeper nesting would overflow the stack in the recursive descent.
+// Every level of nesting is about ten parser calls, and 200 levels overflow
+// the 2 MiB stack of a test thread in debug builds.
+pub const DEFAULT_MAX_DEPTH: usize = 150;
+
+// Highest limit that can be set. The parser and the recursive tree passes
+// are tested with it on a stack of 8 MiB, the one of the main thread
+pub const MAX_PARSE_DEPTH: usize = 500;
+
 pub struct AstParser {
     lexemes: Vec<Lexeme>,
+    // Source ranges of the lexemes, empty if unknown
+    positions: Vec<Range<usize>>,
     current_index: usize,
+
+    // Spans of the nodes built so far, children are popped by their parents
+    spans: Vec<NodeSpan>,
+
+    // Nesting of the parser calls and the limit for it and for the tree height
+    depth: usize,
+    max_depth: usize,
+
+    // Functions with a fixed number of arguments
+    known_functions: BTreeMap<String, usize>,
+    is_case_insensitive: bool,
+
+    // Used by `parse_all`: errors in arguments and indices are collected
+    is_recovering: bool,
+    errors: Vec<AstParseError>,
 }
 
 impl AstParser {
     pub fn new(lexemes: Vec<Lexeme>) -> Self {
         Self {
             lexemes,
+            positions: Vec::new(),
             current_index: 0,
+
+            spans: Vec::new(),
+
+            depth: 0,
+            max_depth: DEFAULT_MAX_DEPTH,
+
+            known_functions: validation::known_arities(),
+            is_case_insensitive: false,
+
+            is_recovering: false,
+            errors: Vec::new(),
+        }
+    }
+
+    /// The parsed tree has spans of its nodes.
+    pub fn with_positions(lexemes: Vec<PositionedLexeme>) -> Self {
+        let (lexemes, positions) = lexemes.into_iter().unzip();
+        Self {
+            positions,
+            ..Self::new(lexemes)
         }
     }
 
+    /// Limits are clamped to `MAX_PARSE_DEPTH`.
+    pub fn with_max_depth(mut self, max_depth: usize) -> Self {
+        self.max_depth = max_depth.min(MAX_PARSE_DEPTH);
+        self
+    }
+
+    /// Empty table turns the argument count check off.
+    pub fn with_known_functions(
+        mut self, known_functions: BTreeMap<String, usize>,
+    ) -> Self {
+        self.known_functions = known_functions;
+        self
+    }
+
+    /// Known functions are matched in any case: `SIN(a, b)` is checked as `sin`.
+    pub fn with_case_insensitive_names(mut self, is_case_insensitive: bool) -> Self {
+        self.is_case_insensitive = is_case_insensitive;
+        self
+    }
+
     pub fn parse(&mut self) -> Result<AbstractSyntaxTree, AstError> {
-        let node = self.parse_logical_or()?;
+        self.is_recovering = false;
+        self.parse_tree()
+    }
+
+    /// Like `parse`, but does not stop on the first bad function argument
+    /// or array index: it skips to the next `,` (or closing bracket) and goes on.
+    pub fn parse_all(&mut self) -> Result<AbstractSyntaxTree, Vec<AstParseError>> {
+        self.is_recovering = true;
+        self.errors.clear();
+
+        let tree = match self.parse_tree() {
+            Ok(tree) => Some(tree),
+            Err(error) => {
+                self.record(error);
+                None
+            },
+        };
+
+        match tree {
+            Some(tree) if self.errors.is_empty() => Ok(tree),
+            _ => Err(std::mem::take(&mut self.errors)),
+        }
+    }
+
+    /// Parses `;`-separated expressions (`a + b; c * d`) one by one,
+    /// so an error in one of them does not affect the others.
+    pub fn parse_program(&self) -> Vec<Result<AbstractSyntaxTree, AstError>> {
+        let mut results = Vec::new();
+        let mut start = 0;
+
+        let separators = self
+            .lexemes
+            .iter()
+            .enumerate()
+            .filter(|(_, lexeme)| **lexeme == Lexeme::Semicolon)
+            .map(|(index, _)| index)
+            .chain(std::iter::once(self.lexemes.len()));
+        for end in separators {
+            let mut parser = Self {
+                positions: self.positions.get(start..end).unwrap_or_default().to_vec(),
+                ..Self::new(self.lexemes[start..end].to_vec())
+            }
+            .with_max_depth(self.max_depth)
+            .with_known_functions(self.known_functions.clone())
+            .with_case_insensitive_names(self.is_case_insensitive);
+            results.push(parser.parse());
+            start = end + 1;
+        }
+
+        results
+    }
+
+    fn parse_tree(&mut self) -> Result<AbstractSyntaxTree, AstError> {
+        self.spans.clear();
+        self.depth = 0;
+        let node = self.parse_ternary()?;
+
+        // Long chains like `a + a + ... + a` are parsed in a loop,
+        // but still make a deep tree for the recursive passes
+        if Self::is_deeper_than(&node, self.max_depth) {
+            return Err(AstError::ExpressionTooDeep(self.max_depth));
+        }
 
         if self.peek().is_some()
             && let Some(peek) = self.consume()
         {
             Err(AstError::NotExpectedLexeme(peek.clone()))
         } else {
-            Ok(AbstractSyntaxTree { peek: node })
+            let spans = match self.positions.is_empty() {
+                true => None,
+                false => self.spans.pop(),
+            };
+            let tree = AbstractSyntaxTree { peek: node, spans };
+            tree.validate()?;
+            tree.validate_arguments(&self.known_functions, self.is_case_insensitive)?;
+            Ok(tree)
+        }
+    }
+
+    // Every nested expression (parentheses, arguments, indices, branches) starts here
+    fn parse_ternary(&mut self) -> Result<AstNode, AstError> {
+        self.nested(Self::parse_conditional)
+    }
+
+    fn nested(
+        &mut self, parse: fn(&mut Self) -> Result<AstNode, AstError>,
+    ) -> Result<AstNode, AstError> {
+        if self.depth >= self.max_depth {
+            return Err(AstError::ExpressionTooDeep(self.max_depth));
+        }
+
+        self.depth += 1;
+        let result = parse(self);
+        self.depth -= 1;
+
+        result
+    }
+
+    // Iterative, so it is safe for any tree
+    fn is_deeper_than(node: &AstNode, max_depth: usize) -> bool {
+        let mut stack = vec![(node, 1)];
+        while let Some((node, depth)) = stack.pop() {
+            if depth > max_depth {
+                return true;
+            }
+            stack.extend(node.children().into_iter().map(|child| (child, depth + 1)));
         }
+        false
+    }
+
+    // Lowest precedence, right-associative: `a ? b : c ? d : e` is `a ? b : (c ? d : e)`
+    fn parse_conditional(&mut self) -> Result<AstNode, AstError> {
+        let first = self.current_index;
+        let condition = self.parse_logical_or()?;
+
+        if let Some(Lexeme::Question) = self.peek()
+            && let Some(_) = self.consume()
+        {
+            let then_branch = self.parse_ternary()?;
+
+            if self.peek() != Some(&Lexeme::Colon) {
+                return Err(AstError::ExpectedColon);
+            }
+            let _ = self.consume();
+
+            let else_branch = self.parse_ternary()?;
+
+            self.attach_span(first, 3);
+            return Ok(AstNode::Ternary {
+                condition: Box::new(condition),
+                then_branch: Box::new(then_branch),
+                else_branch: Box::new(else_branch),
+            });
+        }
+
+        Ok(condition)
     }
 
     fn parse_logical_or(&mut self) -> Result<AstNode, AstError> {
+        let first = self.current_index;
         let mut left_node = self.parse_logical_and()?;
 
         while let Some(Lexeme::Or) = self.peek()
             && let Some(_) = self.consume()
         {
             let right_node = self.parse_logical_and()?;
+            self.attach_span(first, 2);
             left_node = AstNode::BinaryOperation {
                 operation: BinaryOperationKind::Or,
                 left: Box::new(left_node),
@@ -348,12 +1071,14 @@ impl AstParser {
     }
 
     fn parse_logical_and(&mut self) -> Result<AstNode, AstError> {
-        let mut left_node = self.parse_expression()?;
+        let first = self.current_index;
+        let mut left_node = self.parse_comparison()?;
 
         while let Some(Lexeme::And) = self.peek()
             && let Some(_) = self.consume()
         {
-            let right_node = self.parse_expression()?;
+            let right_node = self.parse_comparison()?;
+            self.attach_span(first, 2);
             left_node = AstNode::BinaryOperation {
                 operation: BinaryOperationKind::And,
                 left: Box::new(left_node),
@@ -363,7 +1088,46 @@ impl AstParser {
         Ok(left_node)
     }
 
+    // Left-associative like the others: `a < b < c` is `(a < b) < c`
+    fn parse_comparison(&mut self) -> Result<AstNode, AstError> {
+        let first = self.current_index;
+        let mut left_node = self.parse_expression()?;
+
+        while let Some(
+            Lexeme::Less
+            | Lexeme::LessOrEqual
+            | Lexeme::Greater
+            | Lexeme::GreaterOrEqual
+            | Lexeme::Equal
+            | Lexeme::NotEqual,
+        ) = self.peek()
+            && let Some(lexeme) = self.consume()
+        {
+            let operation = match lexeme {
+                Lexeme::Less => BinaryOperationKind::Less,
+                Lexeme::LessOrEqual => BinaryOperationKind::LessOrEqual,
+                Lexeme::Greater => BinaryOperationKind::Greater,
+                Lexeme::GreaterOrEqual => BinaryOperationKind::GreaterOrEqual,
+                Lexeme::Equal => BinaryOperationKind::Equal,
+                Lexeme::NotEqual => BinaryOperationKind::NotEqual,
+                _ => return Err(AstError::UnreachableLexeme(lexeme.clone())),
+            };
+
+            let right_node = self.parse_expression()?;
+            self.attach_span(first, 2);
+
+            left_node = AstNode::BinaryOperation {
+                operation,
+                left: Box::new(left_node),
+                right: Box::new(right_node),
+            };
+        }
+
+        Ok(left_node)
+    }
+
     fn parse_expression(&mut self) -> Result<AstNode, AstError> {
+        let first = self.current_index;
         let mut left_node = self.parse_term()?;
 
         while let Some(Lexeme::Plus) | Some(Lexeme::Minus) = self.peek()
@@ -376,6 +1140,7 @@ impl AstParser {
             };
 
             let right_node = self.parse_term()?;
+            self.attach_span(first, 2);
 
             left_node = AstNode::BinaryOperation {
                 operation,
@@ -388,18 +1153,22 @@ impl AstParser {
     }
 
     fn parse_term(&mut self) -> Result<AstNode, AstError> {
+        let first = self.current_index;
         let mut left_node = self.parse_unary()?;
 
-        while let Some(Lexeme::Multiply) | Some(Lexeme::Divide) = self.peek()
+        while let Some(Lexeme::Multiply) | Some(Lexeme::Divide) | Some(Lexeme::Modulus) =
+            self.peek()
             && let Some(lexeme) = self.consume()
         {
             let operation = match lexeme {
                 Lexeme::Multiply => BinaryOperationKind::Multiply,
                 Lexeme::Divide => BinaryOperationKind::Divide,
+                Lexeme::Modulus => BinaryOperationKind::Modulo,
                 _ => return Err(AstError::UnreachableLexeme(lexeme.clone())),
             };
 
             let right_node = self.parse_unary()?;
+            self.attach_span(first, 2);
 
             left_node = AstNode::BinaryOperation {
                 operation,
@@ -412,6 +1181,7 @@ impl AstParser {
     }
 
     fn parse_unary(&mut self) -> Result<AstNode, AstError> {
+        let first = self.current_index;
         if let Some(Lexeme::Not) | Some(Lexeme::Minus) = self.peek()
             && let Some(lexeme) = self.consume()
         {
@@ -421,7 +1191,8 @@ impl AstParser {
                 _ => return Err(AstError::UnreachableLexeme(lexeme.clone())),
             };
 
-            let child_node = self.parse_unary()?;
+            let child_node = self.nested(Self::parse_unary)?;
+            self.attach_span(first, 1);
 
             Ok(AstNode::UnaryOperation {
                 operation: operation_kind,
@@ -433,20 +1204,30 @@ impl AstParser {
     }
 
     fn parse_primary(&mut self) -> Result<AstNode, AstError> {
+        let first = self.current_index;
         if let Some(lexeme) = self.consume() {
             match lexeme {
-                Lexeme::Number(value) => Ok(AstNode::Number(value)),
+                Lexeme::Number(value) => {
+                    self.attach_span(first, 0);
+                    Ok(AstNode::Number(value))
+                },
                 Lexeme::String(value) => {
+                    // Strings are validated as array indices after parsing
                     match (matches!(self.peek(), Some(Lexeme::Comma)))
-                        || (matches!(self.peek_previous_by(2), Some(Lexeme::Comma)))
-                    {
-                        true => Ok(AstNode::StringLiteral(value.clone())),
+                        || (matches!(
+                            self.peek_previous_by(2),
+                            Some(Lexeme::Comma | Lexeme::LeftBracket)
+                        )) {
+                        true => {
+                            self.attach_span(first, 0);
+                            Ok(AstNode::StringLiteral(value.clone()))
+                        },
                         false => Err(AstError::StringOutsideFunction(value.clone())),
                     }
                 },
 
                 Lexeme::LeftParenthesis => {
-                    let inner_node = self.parse_logical_or()?;
+                    let inner_node = self.parse_ternary()?;
 
                     if self.peek() == Some(&Lexeme::RightParenthesis) {
                         self.consume();
@@ -465,7 +1246,11 @@ impl AstParser {
 
                         if self.peek() != Some(&Lexeme::RightParenthesis) {
                             loop {
-                                args.push(self.parse_logical_or()?);
+                                if let Some(arg) =
+                                    self.parse_recoverable(&Lexeme::RightParenthesis)?
+                                {
+                                    args.push(arg);
+                                }
 
                                 let peek = self.peek();
 
@@ -487,6 +1272,7 @@ impl AstParser {
                         }
 
                         let _ = self.consume();
+                        self.attach_span(first, args.len());
 
                         Ok(AstNode::FunctionCall {
                             name: function_name,
@@ -498,10 +1284,15 @@ impl AstParser {
 
                         loop {
                             let _ = self.consume();
-                            let index = self.parse_logical_or()?;
+                            let index = self.parse_recoverable(&Lexeme::RightBracket)?;
+                            // Comma-separated dimensions: A[i, j]
+                            if self.peek() == Some(&Lexeme::Comma) {
+                                indices.extend(index);
+                                continue;
+                            }
                             if self.peek() == Some(&Lexeme::RightBracket) {
                                 let _ = self.consume();
-                                indices.push(index);
+                                indices.extend(index);
                                 if self.peek() == Some(&Lexeme::LeftBracket) {
                                     continue;
                                 } else {
@@ -511,11 +1302,13 @@ impl AstParser {
                                 return Err(AstError::ExpectedRightBracket);
                             }
                         }
+                        self.attach_span(first, indices.len());
                         Ok(AstNode::ArrayAccess {
                             identifier,
                             indices,
                         })
                     } else {
+                        self.attach_span(first, 0);
                         Ok(AstNode::Identifier(name.clone()))
                     }
                 },
@@ -527,6 +1320,76 @@ impl AstParser {
         }
     }
 
+    // In recovery mode an error is recorded, and lexemes are skipped
+    // up to the next `,` or `closing` on the same nesting level
+    fn parse_recoverable(
+        &mut self, closing: &Lexeme,
+    ) -> Result<Option<AstNode>, AstError> {
+        let spans_count = self.spans.len();
+        match self.parse_ternary() {
+            Ok(node) => Ok(Some(node)),
+            Err(error) if self.is_recovering => {
+                // Spans of the partially parsed argument
+                self.spans.truncate(spans_count);
+                self.record(error);
+                self.synchronize(closing);
+                Ok(None)
+            },
+            Err(error) => Err(error),
+        }
+    }
+
+    fn synchronize(&mut self, closing: &Lexeme) {
+        let mut depth = 0;
+        while let Some(lexeme) = self.peek() {
+            match lexeme {
+                Lexeme::LeftParenthesis | Lexeme::LeftBracket => depth += 1,
+                Lexeme::Comma if depth == 0 => return,
+                lexeme if depth == 0 && lexeme == closing => return,
+                Lexeme::RightParenthesis | Lexeme::RightBracket => {
+                    match depth {
+                        // Closing bracket of the outer call
+                        0 => return,
+                        _ => depth -= 1,
+                    }
+                },
+                _ => {},
+            }
+            let _ = self.consume();
+        }
+    }
+
+    // Span of the node built from the lexemes `first..current_index`,
+    // which takes the last `children` spans as its children
+    fn attach_span(&mut self, first: usize, children: usize) {
+        let children = self
+            .spans
+            .split_off(self.spans.len().saturating_sub(children));
+        let start = self
+            .positions
+            .get(first)
+            .map_or(0, |position| position.start);
+        let end = self
+            .positions
+            .get(self.current_index.saturating_sub(1))
+            .map_or(0, |position| position.end);
+
+        self.spans.push(NodeSpan {
+            position: start..end,
+            children,
+        });
+    }
+
+    fn record(&mut self, error: AstError) {
+        self.errors.push(AstParseError {
+            position: self
+                .positions
+                .get(self.current_index.saturating_sub(1))
+                .cloned(),
+            error,
+        });
+    }
+
     fn consume(&mut self) -> Option<Lexeme> {
         if let Some(lexeme) = self.peek() {
             let lexeme = lexeme.clone();
@@ -552,17 +1415,64 @@ impl Reporter {
         match result {
             Ok(tree) => {
                 buffer.add_line("Abstract-Syntax Tree generation success!\n".to_string());
-                buffer.add_line(tree.pretty_print());
+                buffer.add_line(tree.pretty_print_with(&self.number_format));
             },
             Err(error) => buffer.add_line(format!("AST error: {}", error)),
         }
 
         buffer.get()
     }
+
+    pub fn program_build(
+        &self, results: &[Result<AbstractSyntaxTree, AstError>],
+    ) -> String {
+        let mut buffer = StringBuffer::default();
+
+        for (index, result) in results.iter().enumerate() {
+            buffer.add_line(format!("Expression #{}:\n", index + 1));
+            buffer.add_line(self.tree_build(result));
+        }
+
+        buffer.get()
+    }
+}
+
+/// Error collected by `AstParser::parse_all`.
+#[derive(Debug, PartialEq)]
+pub struct AstParseError {
+    /// Char range of the last consumed lexeme, as the positions of tokens.
+    /// `None` if the lexemes are given without positions.
+    pub position: Option<Range<usize>>,
+    pub error: AstError,
+}
+
+impl std::fmt::Display for AstParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match &self.position {
+            Some(position) if position.start + 1 == position.end => {
+                write!(f, "[Position: {}] {}", position.start + 1, self.error)
+            },
+            Some(position) => write!(
+                f,
+                "[Position: {}..{}] {}",
+                position.start + 1,
+                position.end,
+                self.error
+            ),
+            None => write!(f, "{}", self.error),
+        }
+    }
+}
+
+impl std::error::Error for AstParseError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        Some(&self.error)
+    }
 }
 
 #[derive(Debug, PartialEq)]
 pub enum AstError {
+    ExpectedColon,
     ExpectedRightBracket,
     ExpectedRightParenthesis,
     ExpectedCommaOrRightParenthesis(Lexeme),
@@ -570,10 +1480,32 @@ pub enum AstError {
     NotExpectedLexeme(Lexeme),
     StringOutsideFunction(String),
     UnreachableLexeme(Lexeme),
+    ExpressionTooDeep(usize),
 
     CannotBuildEmptyTree,
     FailedPopFromQueue,
     DivisionByZero(AstNode),
+    NonFiniteResult(AstNode),
+
+    UndefinedVariable(String),
+    UnknownFunction(String, usize),
+    WrongArgumentCount {
+        name: String,
+        expected: usize,
+        got: usize,
+    },
+    NotEvaluableArrayAccess(String),
+    NotEvaluableString(String),
+    StringInArithmetic(String),
+    IndexOutOfBounds {
+        identifier: String,
+        index: f64,
+        length: usize,
+    },
+    NonIntegerIndex(String, f64),
+
+    InvalidArrayIndex(String),
+    IdentifierUsedAsBothFunctionAndArray(String),
 }
 
 impl std::fmt::Display for AstError {
@@ -583,6 +1515,7 @@ impl std::fmt::Display for AstError {
                 "Expected ',' or ')', but found \"{}\".",
                 lexeme.display_type()
             ),
+            Self::ExpectedColon => "Expected ':' in ternary expression.",
             Self::ExpectedRightBracket => "Expected right bracket.",
             Self::ExpectedRightParenthesis => "Expected right parenthesis.",
             Self::NotExpectedEndOfExpression => "Not expected end of expression.",
@@ -595,6 +1528,10 @@ impl std::fmt::Display for AstError {
             Self::UnreachableLexeme(lexeme) => {
                 &format!("Unreachable lexeme \"{}\".", lexeme.display_type())
             },
+            Self::ExpressionTooDeep(max_depth) => &format!(
+                "Expression is too deep: more than {} nested levels.",
+                max_depth
+            ),
 
             Self::CannotBuildEmptyTree => {
                 "Cannot build a balanced tree from zero operands"
@@ -603,12 +1540,61 @@ impl std::fmt::Display for AstError {
                 "Failed to pop node from the queue during tree construction"
             },
             Self::DivisionByZero(node) => &format!("Division by zero. Node: {:#?}", node),
+            Self::NonFiniteResult(node) => {
+                &format!("Result is not a finite number. Node: {:#?}", node)
+            },
+
+            Self::UndefinedVariable(name) => &format!("Undefined variable \"{}\".", name),
+            Self::UnknownFunction(name, arguments) => &format!(
+                "Unknown function \"{}\" with {} argument(s).",
+                name, arguments
+            ),
+            Self::WrongArgumentCount {
+                name,
+                expected,
+                got,
+            } => &format!(
+                "Function \"{}\" takes {} argument(s), but {} given.",
+                name, expected, got
+            ),
+            Self::NotEvaluableArrayAccess(identifier) => {
+                &format!("Array access \"{}[...]\" cannot be evaluated.", identifier)
+            },
+            Self::NotEvaluableString(string) => {
+                &format!("String literal \"{}\" cannot be evaluated.", string)
+            },
+            Self::StringInArithmetic(string) => &format!(
+                "String literal \"{}\" cannot be an operand of an operation.",
+                string
+            ),
+            Self::IndexOutOfBounds {
+                identifier,
+                index,
+                length,
+            } => &format!(
+                "Index {} of \"{}\" is out of bounds, the length is {}.",
+                index, identifier, length
+            ),
+            Self::NonIntegerIndex(identifier, index) => {
+                &format!("Index {} of \"{}\" is not an integer.", index, identifier)
+            },
+
+            Self::InvalidArrayIndex(identifier) => &format!(
+                "Array access \"{}[...]\" has a string or logical index.",
+                identifier
+            ),
+            Self::IdentifierUsedAsBothFunctionAndArray(name) => &format!(
+                "Identifier \"{}\" is used both as a function and an array.",
+                name
+            ),
         };
 
         write!(f, "{}", text)
     }
 }
 
+impl std::error::Error for AstError {}
+
 impl std::fmt::Display for UnaryOperationKind {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
@@ -625,8 +1611,15 @@ impl std::fmt::Display for BinaryOperationKind {
             Self::Minus => write!(f, "-"),
             Self::Multiply => write!(f, "*"),
             Self::Divide => write!(f, "/"),
+            Self::Modulo => write!(f, "%"),
             Self::Or => write!(f, "|"),
             Self::And => write!(f, "&"),
+            Self::Less => write!(f, "<"),
+            Self::LessOrEqual => write!(f, "<="),
+            Self::Greater => write!(f, ">"),
+            Self::GreaterOrEqual => write!(f, ">="),
+            Self::Equal => write!(f, "=="),
+            Self::NotEqual => write!(f, "!="),
         }
     }
 }
@@ -634,6 +1627,7 @@ impl std::fmt::Display for BinaryOperationKind {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::compiler::ast::test_support::{lexemes, positioned_lexemes};
     use crate::compiler::lexer;
     use crate::compiler::tokenizer::Tokenizer;
 
@@ -872,4 +1866,563 @@ mod tests {
         };
         assert_eq!(AbstractSyntaxTree::from_node(expected_ast), actual_ast);
     }
+
+    #[test]
+    fn test_8() {
+        let code = "A[i,j] + f(a,b)";
+        let ast = process(code);
+
+        let expected_node = AstNode::ArrayAccess {
+            identifier: "A".to_string(),
+            indices: vec![
+                AstNode::Identifier("i".to_string()),
+                AstNode::Identifier("j".to_string()),
+            ],
+        };
+        let AstNode::BinaryOperation { left, .. } = &ast.peek else {
+            panic!("Expected binary operation, found {:?}", ast.peek);
+        };
+        assert_eq!(**left, expected_node);
+
+        assert_eq!(ast.to_pretty_string(), "A[i, j] + f(a, b)");
+        assert_eq!(
+            ast.to_pretty_string_with(&PrettyOptions::spaced()),
+            "A[i, j] + f(a, b)"
+        );
+        assert_eq!(
+            ast.to_pretty_string_with(&PrettyOptions::compact()),
+            "A[i,j] + f(a,b)"
+        );
+    }
+
+    #[test]
+    fn test_9() {
+        let code = "A[i][j + k]";
+        let ast = process(code);
+
+        assert_eq!(
+            ast.to_pretty_string_with(&PrettyOptions::spaced()),
+            "A[i, j + k]"
+        );
+        assert_eq!(
+            ast.to_pretty_string_with(&PrettyOptions::compact()),
+            "A[i,j + k]"
+        );
+        // Both notations describe the same array access
+        assert_eq!(ast, process("A[i, j + k]"));
+    }
+
+    #[test]
+    fn test_10() {
+        let code = "a % b * c";
+        let actual_ast = process(code);
+        let expected_ast = AstNode::BinaryOperation {
+            operation: BinaryOperationKind::Multiply,
+            left: Box::new(AstNode::BinaryOperation {
+                operation: BinaryOperationKind::Modulo,
+                left: Box::new(AstNode::Identifier("a".to_string())),
+                right: Box::new(AstNode::Identifier("b".to_string())),
+            }),
+            right: Box::new(AstNode::Identifier("c".to_string())),
+        };
+
+        assert_eq!(actual_ast.peek, expected_ast);
+        assert_eq!(actual_ast.to_pretty_string(), "a % b * c");
+    }
+
+    #[test]
+    fn test_11() {
+        let Ok(computed) = process("10 % 3 + -7 % 3").compute() else {
+            panic!()
+        };
+        assert_eq!(computed.peek, AstNode::Number(0.0));
+
+        assert!(matches!(
+            process("x % (2 - 2)").compute(),
+            Err(AstError::DivisionByZero(_))
+        ));
+    }
+
+    #[test]
+    fn test_12() {
+        let cases = [
+            ("-(a - b)", "b - a"),
+            ("-(a + b)", "-a - b"),
+            ("-(-(a - b))", "a - b"),
+            ("-(-a)", "a"),
+        ];
+        for (code, expected) in cases {
+            let Ok(computed) = process(code).compute() else {
+                panic!("{}", code)
+            };
+            assert_eq!(computed.to_pretty_string(), expected, "{}", code);
+        }
+    }
+
+    #[test]
+    fn test_13() {
+        let cases = [
+            ("f(a) + 0", "f(a)"),
+            ("0 + f(a)", "f(a)"),
+            ("f(a) - 0", "f(a)"),
+            ("f(a) * 1", "f(a)"),
+            ("1 * f(a)", "f(a)"),
+            ("f(a) * 0", "0.000"),
+            ("0 * f(a)", "0.000"),
+            ("f(a) / 1", "f(a)"),
+            ("(x + y) * 1", "x + y"),
+            ("x * 0 + y", "y"),
+        ];
+        for (code, expected) in cases {
+            let Ok(computed) = process(code).compute() else {
+                panic!("{}", code)
+            };
+            assert_eq!(computed.to_pretty_string(), expected, "{}", code);
+        }
+    }
+
+    #[test]
+    fn test_14() {
+        let ast = process("f(a) * 2");
+        let lines = ast.pretty_print_lines();
+
+        let kinds: Vec<PrettyNodeKind> = lines.iter().map(|line| line.kind).collect();
+        assert_eq!(
+            kinds,
+            vec![
+                PrettyNodeKind::Operator,
+                PrettyNodeKind::FunctionCall,
+                PrettyNodeKind::Identifier,
+                PrettyNodeKind::Number,
+            ]
+        );
+        assert_eq!(
+            ast.pretty_print(),
+            "└── *\n    ├── f(...)\n    │   └── a\n    └── 2.000\n"
+        );
+    }
+
+    #[test]
+    fn test_15() {
+        let ast = process("a ? b : c");
+        let expected = AstNode::Ternary {
+            condition: Box::new(AstNode::Identifier("a".to_string())),
+            then_branch: Box::new(AstNode::Identifier("b".to_string())),
+            else_branch: Box::new(AstNode::Identifier("c".to_string())),
+        };
+        assert_eq!(ast.peek, expected);
+        assert_eq!(ast.to_pretty_string(), "a ? b : c");
+        assert_eq!(
+            ast.pretty_print(),
+            "└── ?:\n    ├── a\n    ├── b\n    └── c\n"
+        );
+
+        // Lower precedence than logical or
+        assert_eq!(
+            process("a | b ? c + 1 : d").to_pretty_string(),
+            "a | b ? c + 1.000 : d"
+        );
+        assert_eq!(
+            process("(a ? b : c) + d").to_pretty_string(),
+            "(a ? b : c) + d"
+        );
+
+        let tokens = Tokenizer::process("a ? b");
+        let Ok(lexemes) = lexer::Lexer::new(tokens).run() else {
+            panic!("Failed to create lexemes")
+        };
+        assert_eq!(
+            AstParser::new(lexemes).parse(),
+            Err(AstError::ExpectedColon)
+        );
+    }
+
+    #[test]
+    fn test_16() {
+        // Right-associative: a ? b : (c ? d : e)
+        let ast = process("a ? b : c ? d : e");
+        let expected = AstNode::Ternary {
+            condition: Box::new(AstNode::Identifier("a".to_string())),
+            then_branch: Box::new(AstNode::Identifier("b".to_string())),
+            else_branch: Box::new(AstNode::Ternary {
+                condition: Box::new(AstNode::Identifier("c".to_string())),
+                then_branch: Box::new(AstNode::Identifier("d".to_string())),
+                else_branch: Box::new(AstNode::Identifier("e".to_string())),
+            }),
+        };
+        assert_eq!(ast.peek, expected);
+        assert_eq!(ast.to_pretty_string(), "a ? b : c ? d : e");
+        assert_eq!(
+            process("(a ? b : c) ? d : e").to_pretty_string(),
+            "(a ? b : c) ? d : e"
+        );
+
+        // Constant conditions are folded by compute
+        let cases = [
+            ("1 ? a : b", "a"),
+            ("2 - 2 ? a : b", "b"),
+            ("0 ? a : 1 ? c * 1 : d", "c"),
+            ("x ? 1 + 1 : b", "x ? 2.000 : b"),
+        ];
+        for (code, expected) in cases {
+            let Ok(computed) = process(code).compute() else {
+                panic!("{}", code)
+            };
+            assert_eq!(computed.to_pretty_string(), expected, "{}", code);
+        }
+    }
+
+    #[test]
+    fn test_17() {
+        let ast = AbstractSyntaxTree::from_node(AstNode::Number(0.125));
+        assert_eq!(
+            ast.pretty_print_with(&NumberFormat::Fixed(3)),
+            "└── 0.125\n"
+        );
+        assert_eq!(ast.pretty_print_with(&NumberFormat::Fixed(2)), "└── 0.13\n");
+        assert_eq!(
+            ast.to_canonical_string_with(&NumberFormat::Fixed(3)),
+            "0.125"
+        );
+        assert_eq!(ast.to_canonical_string(), "0.125");
+
+        let options = PrettyOptions {
+            number_format: NumberFormat::Fixed(2),
+            ..PrettyOptions::default()
+        };
+        assert_eq!(ast.to_pretty_string_with(&options), "0.13");
+        assert_eq!(ast.to_pretty_string(), "0.125");
+
+        let ast = AbstractSyntaxTree::from_node(AstNode::Number(0.3999999999999999));
+        let options = PrettyOptions {
+            number_format: NumberFormat::Full,
+            ..PrettyOptions::default()
+        };
+        assert_eq!(ast.to_pretty_string_with(&options), "0.3999999999999999");
+        assert_eq!(
+            ast.pretty_print_with(&NumberFormat::Full),
+            "└── 0.3999999999999999\n"
+        );
+
+        assert_eq!("full".parse(), Ok(NumberFormat::Full));
+        assert_eq!(" 4 ".parse(), Ok(NumberFormat::Fixed(4)));
+        assert_eq!("x".parse::<NumberFormat>(), Err("x".to_string()));
+        assert_eq!("15".parse(), Ok(NumberFormat::Fixed(15)));
+        assert_eq!("16".parse::<NumberFormat>(), Err("16".to_string()));
+    }
+
+    #[test]
+    fn test_18() {
+        let expected = vec![
+            AstParseError {
+                position: Some(2..3),
+                error: AstError::NotExpectedLexeme(Lexeme::Plus),
+            },
+            AstParseError {
+                position: Some(5..6),
+                error: AstError::NotExpectedLexeme(Lexeme::Multiply),
+            },
+        ];
+        assert_eq!(
+            AstParser::with_positions(positioned_lexemes("f(+, *)")).parse_all(),
+            Err(expected)
+        );
+        // Single-error parse stops on the first one
+        assert_eq!(
+            AstParser::new(lexemes("f(+, *)")).parse(),
+            Err(AstError::NotExpectedLexeme(Lexeme::Plus))
+        );
+
+        // Nested calls and array indices are skipped as a whole
+        // Positions are of the chars, as the ones of the syntax errors
+        let code = "f(g(a, +), A[*, i], b) + B[i, /]";
+        let Err(errors) = AstParser::with_positions(positioned_lexemes(code)).parse_all()
+        else {
+            panic!("Errors are expected")
+        };
+        assert_eq!(errors.len(), 3);
+        assert_eq!(
+            errors[2].to_string(),
+            "[Position: 31] Not expected lexeme \"Divide\"."
+        );
+        let Err(errors) = AstParser::new(lexemes(code)).parse_all() else {
+            panic!("Errors are expected")
+        };
+        assert_eq!(errors[2].position, None);
+
+        let code = "f(a, b) + A[i, j]";
+        assert_eq!(AstParser::new(lexemes(code)).parse_all(), Ok(process(code)));
+    }
+
+    #[test]
+    fn test_19() {
+        let ast = process("a - (b - c)");
+        assert_eq!(ast.to_pretty_string(), "a - (b - c)");
+        assert_eq!(ast.to_fully_parenthesized_string(), "(a - (b - c))");
+
+        let ast = process("(-a) + b");
+        assert_eq!(ast.to_pretty_string(), "b - a");
+        assert_eq!(ast.to_fully_parenthesized_string(), "(b - a)");
+
+        let ast = process("a + b * c - f(x / 2, A[i + 1]) ? -(d & e) : 1");
+        assert_eq!(
+            ast.to_fully_parenthesized_string(),
+            "(((a + (b * c)) - f((x / 2.000), A[(i + 1.000)])) ? -(d & e) : 1.000)"
+        );
+    }
+
+    #[test]
+    fn test_flat_string() {
+        let Ok(balanced) = process("a + b + c + d + e + f + g + h").balance() else {
+            panic!("Failed to balance AST")
+        };
+        assert_eq!(
+            balanced.to_fully_parenthesized_string(),
+            "(((a + b) + (c + d)) + ((e + f) + (g + h)))"
+        );
+        assert_eq!(balanced.to_flat_string(), "a + b + c + d + e + f + g + h");
+
+        // Other operations end a chain and keep their parentheses
+        let ast = process("(a + (b + c)) * d * (e - (f - g)) + -(x * y) + (p ? q : r)");
+        assert_eq!(
+            ast.to_flat_string(),
+            "((a + b + c) * d * (e - (f - g))) + -(x * y) + (p ? q : r)"
+        );
+        assert_eq!(
+            process("f(a + (b + c), A[i * (j * k)])").to_flat_string(),
+            "f(a + b + c, A[i * j * k])"
+        );
+    }
+
+    #[test]
+    fn test_20() {
+        let tokens = Tokenizer::process("a + b");
+        let Ok(lexemes) = lexer::Lexer::new(tokens).run_with_positions() else {
+            panic!("Failed to create lexemes")
+        };
+        let Ok(ast) = AstParser::with_positions(lexemes).parse() else {
+            panic!("Failed to build AST")
+        };
+
+        let leaf = |position: Range<usize>| NodeSpan {
+            position,
+            children: vec![],
+        };
+        let expected = NodeSpan {
+            position: 0..5,
+            children: vec![leaf(0..1), leaf(4..5)],
+        };
+        assert_eq!(ast.spans, Some(expected));
+        // Spans don't take part in the comparison
+        assert_eq!(ast, process("a + b"));
+        assert_eq!(process("a + b").spans, None);
+
+        let tokens = Tokenizer::process("-f(x, 2.5) * (b)");
+        let Ok(lexemes) = lexer::Lexer::new(tokens).run_with_positions() else {
+            panic!("Failed to create lexemes")
+        };
+        let Ok(ast) = AstParser::with_positions(lexemes).parse() else {
+            panic!("Failed to build AST")
+        };
+        let expected = NodeSpan {
+            position: 0..16,
+            children: vec![
+                NodeSpan {
+                    position: 0..10,
+                    children: vec![NodeSpan {
+                        position: 1..10,
+                        children: vec![leaf(3..4), leaf(6..9)],
+                    }],
+                },
+                leaf(14..15),
+            ],
+        };
+        assert_eq!(ast.spans, Some(expected));
+    }
+
+    #[test]
+    fn test_comparisons() {
+        // Between `&` and `+`
+        let ast = process("a + 1 < b & c");
+        let expected = AstNode::BinaryOperation {
+            operation: BinaryOperationKind::And,
+            left: Box::new(AstNode::BinaryOperation {
+                operation: BinaryOperationKind::Less,
+                left: Box::new(AstNode::BinaryOperation {
+                    operation: BinaryOperationKind::Plus,
+                    left: Box::new(AstNode::Identifier("a".to_string())),
+                    right: Box::new(AstNode::Number(1.0)),
+                }),
+                right: Box::new(AstNode::Identifier("b".to_string())),
+            }),
+            right: Box::new(AstNode::Identifier("c".to_string())),
+        };
+        assert_eq!(ast.peek, expected);
+
+        assert_eq!(process("a <= b").to_pretty_string(), "a <= b");
+        assert_eq!(process("a != b == c").to_pretty_string(), "a != b == c");
+        assert_eq!(process("a >= (b > c)").to_pretty_string(), "a >= (b > c)");
+        assert_eq!(process("(a == b) + c").to_pretty_string(), "(a == b) + c");
+    }
+
+    #[test]
+    fn test_21() {
+        let code = format!("{}a{}", "(".repeat(5000), ")".repeat(5000));
+        assert_eq!(
+            AstParser::new(lexemes(&code)).parse(),
+            Err(AstError::ExpressionTooDeep(DEFAULT_MAX_DEPTH))
+        );
+
+        let code = format!("{}a", "-".repeat(5000));
+        assert_eq!(
+            AstParser::new(lexemes(&code)).parse(),
+            Err(AstError::ExpressionTooDeep(DEFAULT_MAX_DEPTH))
+        );
+
+        // Not nested in the parser, but the tree is deep
+        let code = vec!["a"; 5000].join(" + ");
+        assert_eq!(
+            AstParser::new(lexemes(&code)).parse(),
+            Err(AstError::ExpressionTooDeep(DEFAULT_MAX_DEPTH))
+        );
+        let code = vec!["a"; DEFAULT_MAX_DEPTH].join(" + ");
+        assert!(AstParser::new(lexemes(&code)).parse().is_ok());
+
+        let code = "((a + b) * c)";
+        assert!(
+            AstParser::new(lexemes(code))
+                .with_max_depth(3)
+                .parse()
+                .is_ok()
+        );
+        assert_eq!(
+            AstParser::new(lexemes(code)).with_max_depth(2).parse(),
+            Err(AstError::ExpressionTooDeep(2))
+        );
+    }
+
+    #[test]
+    fn test_21_max_limit() {
+        // The stack of the main thread, the one of the tests is smaller
+        let thread = std::thread::Builder::new()
+            .stack_size(8 * 1024 * 1024)
+            .spawn(|| {
+                let parse = |code: &str| {
+                    AstParser::new(lexemes(code))
+                        .with_max_depth(usize::MAX)
+                        .parse()
+                };
+
+                let code = format!("{}a{}", "(".repeat(5000), ")".repeat(5000));
+                assert_eq!(
+                    parse(&code),
+                    Err(AstError::ExpressionTooDeep(MAX_PARSE_DEPTH))
+                );
+
+                let chain = vec!["a"; MAX_PARSE_DEPTH].join(" + ");
+                let unary = format!("{}a", "-".repeat(MAX_PARSE_DEPTH - 1));
+                for code in [chain, unary] {
+                    let Ok(ast) = parse(&code) else {
+                        panic!("Failed to build AST of height {}", MAX_PARSE_DEPTH)
+                    };
+                    assert_eq!(ast.height(), MAX_PARSE_DEPTH);
+
+                    // Every recursive pass survives the deepest tree
+                    let _ = ast.to_latex();
+                    let _ = ast.to_pretty_string();
+                    let _ = ast.pretty_print();
+                    let _ = ast.to_json();
+                    let _ = ast.to_dot();
+                    let _ = ast.canonicalize();
+                    let _ = ast.find_common_subexpressions();
+                    let _ = ast.evaluate(&std::collections::HashMap::from([(
+                        "a".to_string(),
+                        1.0,
+                    )]));
+                    let _ = ast.clone().compute();
+                    let _ = ast.clone().transform();
+                    let _ = ast.clone().fold();
+                    let _ = ast.clone().factor_common_terms();
+                    let _ = ast.balance();
+                }
+            });
+        let Ok(thread) = thread else {
+            panic!("Failed to spawn the test thread")
+        };
+        assert!(thread.join().is_ok());
+    }
+
+    #[test]
+    fn test_22() {
+        let program = |code: &str| {
+            AstParser::with_positions(positioned_lexemes(code)).parse_program()
+        };
+
+        let results = program("a+b; c*d");
+        assert_eq!(results.len(), 2);
+        assert_eq!(Ok(process("a+b")), results[0]);
+        assert_eq!(Ok(process("c*d")), results[1]);
+
+        // Positions of the second tree are in the whole code
+        let Ok(second) = &results[1] else {
+            panic!("Second expression is not parsed")
+        };
+        let Some(span) = &second.spans else {
+            panic!("Second expression has no spans")
+        };
+        assert_eq!(span.position, 5..8);
+
+        // Error only in the first expression
+        let results = program("a+; b");
+        assert_eq!(results.len(), 2);
+        assert!(results[0].is_err());
+        assert_eq!(Ok(process("b")), results[1]);
+
+        // Without semicolons it is the same as `parse`
+        assert_eq!(program("a*b + c"), vec![Ok(process("a*b + c"))]);
+    }
+
+    #[test]
+    fn test_iter() {
+        let ast = process("a + b*c");
+        let identifier = |name: &str| AstNode::Identifier(name.to_string());
+        let product = AstNode::BinaryOperation {
+            operation: BinaryOperationKind::Multiply,
+            left: Box::new(identifier("b")),
+            right: Box::new(identifier("c")),
+        };
+        let nodes: Vec<&AstNode> = ast.iter().collect();
+        assert_eq!(
+            nodes,
+            vec![
+                &ast.peek,
+                &identifier("a"),
+                &product,
+                &identifier("b"),
+                &identifier("c")
+            ]
+        );
+
+        let ast = process("f(a, -b) + A[i + 1, j] * (c ? d : 2)");
+        assert_eq!(ast.iter().count(), ast.node_count());
+        let identifiers: Vec<&str> = ast
+            .iter()
+            .filter_map(|node| match node {
+                AstNode::Identifier(name) => Some(name.as_str()),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(identifiers, vec!["a", "b", "i", "j", "c", "d"]);
+    }
+
+    #[test]
+    fn test_for_each_mut() {
+        let mut ast = process("f(a, b) + A[a] * a");
+        ast.for_each_mut(|node| {
+            if *node == AstNode::Identifier("a".to_string()) {
+                *node = AstNode::Number(1.0);
+            }
+        });
+        assert_eq!(ast, process("f(1, b) + A[1] * 1"));
+    }
 }
@@ -0,0 +1,315 @@
+use crate::compiler::ast::tree::UnaryOperationKind;
+use crate::compiler::ast::tree::{
+    AbstractSyntaxTree, AstError, AstNode, BinaryOperationKind,
+};
+use std::collections::{BTreeMap, HashSet};
+
+/// Function which `compute` and `evaluate` can apply to numbers.
+pub struct KnownFunction {
+    pub name: &'static str,
+    pub operation: FunctionOperation,
+}
+
+#[derive(Clone, Copy)]
+pub enum FunctionOperation {
+    Unary(fn(f64) -> f64),
+    Binary(fn(f64, f64) -> f64),
+}
+
+impl KnownFunction {
+    pub fn arity(&self) -> usize {
+        match self.operation {
+            FunctionOperation::Unary(_) => 1,
+            FunctionOperation::Binary(_) => 2,
+        }
+    }
+
+    /// `None` for another number of values.
+    pub fn apply(&self, values: &[f64]) -> Option<f64> {
+        match (self.operation, values) {
+            (FunctionOperation::Unary(operation), [x]) => Some(operation(*x)),
+            (FunctionOperation::Binary(operation), [x, y]) => Some(operation(*x, *y)),
+            _ => None,
+        }
+    }
+}
+
+/// The only table of functions: `compute` applies them,
+/// and `known_arities` is the default table of the argument count check.
+pub const KNOWN_FUNCTIONS: [KnownFunction; 11] = [
+    unary("sin", f64::sin),
+    unary("cos", f64::cos),
+    unary("tan", f64::tan),
+    unary("sqrt", f64::sqrt),
+    unary("abs", f64::abs),
+    unary("exp", f64::exp),
+    unary("ln", f64::ln),
+    binary("pow", f64::powf),
+    binary("min", f64::min),
+    binary("max", f64::max),
+    // `log(x, base)`, as `f64::log`
+    binary("log", f64::log),
+];
+
+const fn unary(name: &'static str, operation: fn(f64) -> f64) -> KnownFunction {
+    KnownFunction {
+        name,
+        operation: FunctionOperation::Unary(operation),
+    }
+}
+
+const fn binary(name: &'static str, operation: fn(f64, f64) -> f64) -> KnownFunction {
+    KnownFunction {
+        name,
+        operation: FunctionOperation::Binary(operation),
+    }
+}
+
+/// Numbers of arguments of `KNOWN_FUNCTIONS` by name.
+pub fn known_arities() -> BTreeMap<String, usize> {
+    KNOWN_FUNCTIONS
+        .iter()
+        .map(|function| (function.name.to_string(), function.arity()))
+        .collect()
+}
+
+/// Name for the lookup of known functions and constants,
+/// the tree keeps the original spelling.
+pub fn lookup_name(name: &str, is_case_insensitive: bool) -> String {
+    match is_case_insensitive {
+        true => name.to_ascii_lowercase(),
+        false => name.to_string(),
+    }
+}
+
+impl AbstractSyntaxTree {
+    /// Semantic checks which the parser cannot do by itself:
+    /// array indices must be arithmetic, and an identifier cannot be both
+    /// a function and an array in one expression.
+    /// Returns the first error in pre-order.
+    pub fn validate(&self) -> Result<(), AstError> {
+        let mut functions: HashSet<String> = HashSet::new();
+        let mut arrays: HashSet<String> = HashSet::new();
+
+        Self::validate_node(&self.peek, &mut functions, &mut arrays)
+    }
+
+    fn validate_node(
+        node: &AstNode, functions: &mut HashSet<String>, arrays: &mut HashSet<String>,
+    ) -> Result<(), AstError> {
+        match node {
+            AstNode::FunctionCall { name, .. } => {
+                if arrays.contains(name) {
+                    return Err(AstError::IdentifierUsedAsBothFunctionAndArray(
+                        name.clone(),
+                    ));
+                }
+                functions.insert(name.clone());
+            },
+            AstNode::ArrayAccess {
+                identifier,
+                indices,
+            } => {
+                if functions.contains(identifier) {
+                    return Err(AstError::IdentifierUsedAsBothFunctionAndArray(
+                        identifier.clone(),
+                    ));
+                }
+                if indices.iter().any(Self::is_invalid_index) {
+                    return Err(AstError::InvalidArrayIndex(identifier.clone()));
+                }
+                arrays.insert(identifier.clone());
+            },
+            _ => {},
+        }
+
+        for child in node.children() {
+            Self::validate_node(child, functions, arrays)?;
+        }
+
+        Ok(())
+    }
+
+    /// Calls of the listed functions must have the listed number of arguments,
+    /// whatever the arguments are. Other functions are not checked.
+    /// With `is_case_insensitive`, `SIN(a, b)` is checked as `sin`.
+    pub fn validate_arguments(
+        &self, functions: &BTreeMap<String, usize>, is_case_insensitive: bool,
+    ) -> Result<(), AstError> {
+        Self::validate_arguments_node(&self.peek, functions, is_case_insensitive)
+    }
+
+    fn validate_arguments_node(
+        node: &AstNode, functions: &BTreeMap<String, usize>, is_case_insensitive: bool,
+    ) -> Result<(), AstError> {
+        let lookup = |name: &str| lookup_name(name, is_case_insensitive);
+        if let AstNode::FunctionCall { name, arguments } = node
+            && let Some((_, expected)) = functions
+                .iter()
+                .find(|(known, _)| lookup(known) == lookup(name))
+            && arguments.len() != *expected
+        {
+            return Err(AstError::WrongArgumentCount {
+                name: name.clone(),
+                expected: *expected,
+                got: arguments.len(),
+            });
+        }
+
+        node.children().into_iter().try_for_each(|child| {
+            Self::validate_arguments_node(child, functions, is_case_insensitive)
+        })
+    }
+
+    fn is_invalid_index(node: &AstNode) -> bool {
+        match node {
+            AstNode::StringLiteral(_) => true,
+            AstNode::UnaryOperation {
+                operation: UnaryOperationKind::Not,
+                ..
+            } => true,
+            AstNode::BinaryOperation {
+                operation: BinaryOperationKind::Or | BinaryOperationKind::And,
+                ..
+            } => true,
+            // Arguments are checked by the function itself
+            AstNode::FunctionCall { .. } => false,
+            // Nested accesses are validated separately
+            AstNode::ArrayAccess { .. } => false,
+            _ => node.children().into_iter().any(Self::is_invalid_index),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::compiler::ast::tree::{AstError, AstParser};
+    use crate::compiler::lexer::Lexer;
+    use crate::compiler::tokenizer::Tokenizer;
+    use std::collections::BTreeMap;
+
+    fn parse(code: &str) -> Result<(), AstError> {
+        let tokens = Tokenizer::process(code);
+        let Ok(lexemes) = Lexer::new(tokens).run() else {
+            panic!("Failed to create lexemes for: {}", code)
+        };
+        AstParser::new(lexemes).parse().map(|_| ())
+    }
+
+    #[test]
+    fn test_01() {
+        assert_eq!(
+            parse("A[\"x\"]"),
+            Err(AstError::InvalidArrayIndex("A".to_string()))
+        );
+        assert_eq!(
+            parse("b + A[i, j | k]"),
+            Err(AstError::InvalidArrayIndex("A".to_string()))
+        );
+        assert_eq!(
+            parse("A[!i]"),
+            Err(AstError::InvalidArrayIndex("A".to_string()))
+        );
+        assert_eq!(parse("A[i + 1, B[j] * 2] + A[f(\"x\", 1)]"), Ok(()));
+    }
+
+    #[test]
+    fn test_02() {
+        assert_eq!(
+            parse("f(1)+f[1]"),
+            Err(AstError::IdentifierUsedAsBothFunctionAndArray(
+                "f".to_string()
+            ))
+        );
+        assert_eq!(
+            parse("g[f(x)] * f[0]"),
+            Err(AstError::IdentifierUsedAsBothFunctionAndArray(
+                "f".to_string()
+            ))
+        );
+        assert_eq!(parse("f(1) + g[1] + f(2) + g[2]"), Ok(()));
+    }
+
+    #[test]
+    fn test_03() {
+        assert_eq!(
+            parse("1 + sin(a, b)"),
+            Err(AstError::WrongArgumentCount {
+                name: "sin".to_string(),
+                expected: 1,
+                got: 2,
+            })
+        );
+        assert_eq!(
+            parse("pow(a) * 2"),
+            Err(AstError::WrongArgumentCount {
+                name: "pow".to_string(),
+                expected: 2,
+                got: 1,
+            })
+        );
+        assert_eq!(
+            parse("log(a)"),
+            Err(AstError::WrongArgumentCount {
+                name: "log".to_string(),
+                expected: 2,
+                got: 1,
+            })
+        );
+        // Nested calls are checked too
+        assert_eq!(
+            parse("f(max(x))"),
+            Err(AstError::WrongArgumentCount {
+                name: "max".to_string(),
+                expected: 2,
+                got: 1,
+            })
+        );
+        // Unknown functions are skipped
+        assert_eq!(parse("f(a, b) + g() + pow(sin(a), b)"), Ok(()));
+
+        // Table of the parser
+        let tokens = Tokenizer::process("sin(a, b) + f(a)");
+        let Ok(lexemes) = Lexer::new(tokens).run() else {
+            panic!("Failed to create lexemes")
+        };
+        assert!(
+            AstParser::new(lexemes.clone())
+                .with_known_functions(BTreeMap::new())
+                .parse()
+                .is_ok()
+        );
+        assert_eq!(
+            AstParser::new(lexemes)
+                .with_known_functions(BTreeMap::from([("f".to_string(), 2)]))
+                .parse()
+                .map(|_| ()),
+            Err(AstError::WrongArgumentCount {
+                name: "f".to_string(),
+                expected: 2,
+                got: 1,
+            })
+        );
+    }
+
+    #[test]
+    fn test_case_insensitive_names() {
+        let tokens = Tokenizer::process("1 + SIN(a, b)");
+        let Ok(lexemes) = Lexer::new(tokens).run() else {
+            panic!("Failed to create lexemes")
+        };
+
+        assert!(AstParser::new(lexemes.clone()).parse().is_ok());
+        assert_eq!(
+            AstParser::new(lexemes)
+                .with_case_insensitive_names(true)
+                .parse()
+                .map(|_| ()),
+            Err(AstError::WrongArgumentCount {
+                name: "SIN".to_string(),
+                expected: 1,
+                got: 2,
+            })
+        );
+    }
+}
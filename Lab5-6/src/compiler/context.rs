@@ -1,94 +1,360 @@
-use crate::compiler::ast::tree::{AbstractSyntaxTree, AstError, AstParser};
-use crate::compiler::lexer::{Lexeme, Lexer, LexerError};
+use crate::compiler::ast::tree::{
+    AbstractSyntaxTree, AstError, AstNode, AstParser, NumberFormat, PrettyOptions,
+};
+use crate::compiler::lexer::{Lexeme, Lexer, LexerError, PositionedLexeme};
 use crate::compiler::pcs::SystemConfiguration;
 use crate::compiler::pcs::research::{OptimizationReport, Researcher};
 use crate::compiler::pcs::vector::{SimulationResult, VectorSystemSimulator};
-use crate::compiler::reports::Reporter;
+use crate::compiler::reports::{ComplexityLimits, Reporter};
+use crate::compiler::run_log::{RunRecorder, RunSummary};
 use crate::compiler::syntax::{SyntaxAnalyzer, SyntaxError};
-use crate::compiler::tokenizer::{Token, Tokenizer};
+use crate::compiler::timings::Timings;
+use crate::compiler::tokenizer::{Token, TokenType, Tokenizer};
 use crate::config::Config;
+use crate::utils::StringBuffer;
+use std::cell::RefCell;
+use std::collections::BTreeMap;
+use std::ops::Range;
+use std::rc::Rc;
+
+// Computed tree and the span of the divisor, if computing fails on a zero one
+type ComputeWithDivisor = (Result<AbstractSyntaxTree, AstError>, Option<Range<usize>>);
 
 pub struct CompilerContext {
     pub code: String,
+    // Second expression for the equivalence check
+    pub compared_code: String,
     pub pretty_output: bool,
+    pub number_format: NumberFormat,
+    pub implicit_multiplication: bool,
+    pub normalize_floats: bool,
+    // Known functions and constants are matched in any case
+    pub case_insensitive_names: bool,
+    pub stage_timings: bool,
+    // Zero means unlimited
+    pub max_syntax_errors: usize,
+    // Nesting limit of the parser
+    pub max_parse_depth: usize,
+    pub tab_width: usize,
+    // Limits of the parsed tree for the metrics warning, zero means unlimited
+    pub max_tree_height: usize,
+    pub max_tree_nodes: usize,
+    // Substituted into the tree before the first computing
+    pub constants: BTreeMap<String, f64>,
+    // Numbers of arguments checked by the parser
+    pub known_functions: BTreeMap<String, usize>,
 
     pub system_configuration: SystemConfiguration,
+
+    // Shared with the contexts of the `;`-separated expressions
+    run_recorder: Rc<RefCell<RunRecorder>>,
 }
 
 impl CompilerContext {
     pub fn new(config: &Config) -> Self {
         Self {
             code: String::new(),
+            compared_code: String::new(),
             pretty_output: config.pretty_output,
+            number_format: config.number_format,
+            implicit_multiplication: config.implicit_multiplication,
+            normalize_floats: config.normalize_floats,
+            case_insensitive_names: config.case_insensitive_names,
+            stage_timings: config.stage_timings,
+            max_syntax_errors: config.max_syntax_errors,
+            max_parse_depth: config.max_parse_depth,
+            tab_width: config.tab_width,
+            max_tree_height: config.max_tree_height,
+            max_tree_nodes: config.max_tree_nodes,
+            constants: config.constants.clone(),
+            known_functions: config.known_functions.clone(),
 
             system_configuration: SystemConfiguration::default(),
+            run_recorder: Rc::default(),
+        }
+    }
+
+    fn reporter(&self) -> Reporter {
+        Reporter::new(self.number_format)
+            .with_tab_width(self.tab_width)
+            .with_complexity_limits(ComplexityLimits {
+                max_height: self.max_tree_height,
+                max_nodes: self.max_tree_nodes,
+            })
+    }
+
+    /// Same settings, other code.
+    fn with_code(&self, code: &str) -> Self {
+        Self {
+            code: code.to_string(),
+            compared_code: String::new(),
+            pretty_output: self.pretty_output,
+            number_format: self.number_format,
+            implicit_multiplication: self.implicit_multiplication,
+            normalize_floats: self.normalize_floats,
+            case_insensitive_names: self.case_insensitive_names,
+            stage_timings: self.stage_timings,
+            max_syntax_errors: self.max_syntax_errors,
+            max_parse_depth: self.max_parse_depth,
+            tab_width: self.tab_width,
+            max_tree_height: self.max_tree_height,
+            max_tree_nodes: self.max_tree_nodes,
+            constants: self.constants.clone(),
+            known_functions: self.known_functions.clone(),
+            system_configuration: self.system_configuration.clone(),
+            run_recorder: Rc::clone(&self.run_recorder),
+        }
+    }
+
+    /// Code of every `;`-separated expression, the whole code when there is no `;`.
+    fn expressions(&self) -> Vec<String> {
+        let chars = self.code.chars().collect::<Vec<char>>();
+        let mut expressions = Vec::new();
+        let mut start = 0;
+        for token in Tokenizer::process(&self.code) {
+            if token.kind == TokenType::Semicolon {
+                expressions.push(chars[start..token.position.start].iter().collect());
+                start = token.position.end;
+            }
         }
+        expressions.push(chars[start..].iter().collect());
+
+        expressions
+    }
+
+    /// The report of every expression of the program, one after another.
+    fn report_each(&self, report: fn(&CompilerContext) -> String) -> String {
+        let expressions = self.expressions();
+        // Misplaced `;` is reported by the syntax analysis of the whole code
+        if expressions.len() == 1 || SyntaxError::has_errors(&self.check_syntax()) {
+            return report(self);
+        }
+
+        let mut buffer = StringBuffer::default();
+        for (index, code) in expressions.iter().enumerate() {
+            buffer.add_line(format!("Expression #{}:\n", index + 1));
+            buffer.add_line(report(&self.with_code(code)));
+        }
+
+        buffer.get()
     }
 
     fn tokenize(&self) -> Vec<Token> {
-        Tokenizer::process(&self.code)
+        let mut tokens = Tokenizer::process(&self.code);
+        if self.normalize_floats {
+            tokens = Tokenizer::normalize_floats(tokens);
+        }
+        match self.implicit_multiplication {
+            true => Tokenizer::insert_implicit_multiplication(tokens),
+            false => tokens,
+        }
     }
 
     pub fn tokenize_report(&self) -> String {
         Tokenizer::report(&self.tokenize())
     }
 
-    fn check_syntax(&self) -> Vec<SyntaxError> {
-        let tokens = self.tokenize();
-        SyntaxAnalyzer::new(&tokens).analyze()
+    fn analyze_syntax(&self, tokens: &[Token]) -> Vec<SyntaxError> {
+        let analyzer = SyntaxAnalyzer::new(tokens);
+        let syntax_errors = match self.max_syntax_errors {
+            0 => analyzer.analyze(),
+            max => analyzer.with_max_errors(max).analyze(),
+        };
+        self.run_recorder.borrow_mut().record_syntax(
+            self.code.chars().count(),
+            tokens.len(),
+            &syntax_errors,
+        );
+
+        syntax_errors
+    }
+
+    fn record_tree(&self, is_built: bool) {
+        self.run_recorder.borrow_mut().record_tree(is_built);
+    }
+
+    pub fn check_syntax(&self) -> Vec<SyntaxError> {
+        self.analyze_syntax(&self.tokenize())
+    }
+
+    /// Input, syntax and tree building of the code, which every run starts with.
+    pub fn run_summary(&self) -> RunSummary {
+        self.run_recorder.take();
+        self.recorded_run_summary()
+    }
+
+    /// Runs `run` and logs its `RunSummary`.
+    pub fn logged<T>(&self, run: impl FnOnce(&Self) -> T) -> T {
+        self.logged_to(log::logger(), run)
+    }
+
+    pub fn logged_to<T>(&self, logger: &dyn log::Log, run: impl FnOnce(&Self) -> T) -> T {
+        self.run_recorder.take();
+        let output = run(self);
+
+        // Stages the run has not come to are run for the summary, not with logging off
+        let metadata = log::Metadata::builder().level(log::Level::Error).build();
+        if logger.enabled(&metadata) {
+            self.recorded_run_summary().log_to(logger);
+        }
+
+        output
+    }
+
+    // Stages recorded since the start of the run are not run again
+    fn recorded_run_summary(&self) -> RunSummary {
+        if !self.run_recorder.borrow().is_syntax_recorded() {
+            let _ = self.check_syntax();
+        }
+        if self.run_recorder.borrow().is_tree_missing() {
+            let is_built = self
+                .expressions()
+                .iter()
+                .all(|code| self.with_code(code).parsed_ast().is_ok());
+            self.record_tree(is_built);
+        }
+
+        self.run_recorder.borrow().summary()
     }
 
     pub fn syntax_report(&self) -> String {
-        Reporter.syntax(&self.code, self.pretty_output, &self.check_syntax())
+        self.reporter()
+            .syntax(&self.code, self.pretty_output, &self.check_syntax())
     }
 
     fn create_lexemes(&self) -> Result<Result<Vec<Lexeme>, LexerError>, String> {
+        let lexemes = self.create_positioned_lexemes()?;
+        Ok(
+            lexemes
+                .map(|lexemes| lexemes.into_iter().map(|(lexeme, _)| lexeme).collect()),
+        )
+    }
+
+    fn create_positioned_lexemes(
+        &self,
+    ) -> Result<Result<Vec<PositionedLexeme>, LexerError>, String> {
         let tokens = self.tokenize();
-        let syntax_errors = self.check_syntax();
-        if !syntax_errors.is_empty() {
+        let syntax_errors = self.analyze_syntax(&tokens);
+        if SyntaxError::has_errors(&syntax_errors) {
             return Err(self.syntax_report());
         }
-        let lexemes = Lexer::new(tokens).run();
+        let lexemes = Lexer::new(tokens).run_with_positions();
         Ok(lexemes)
     }
 
     pub fn lexer_report(&self) -> String {
         match self.create_lexemes() {
-            Ok(lexer_result) => Reporter.lexemes_creation(&lexer_result),
+            Ok(lexer_result) => self.reporter().lexemes_creation(&lexer_result),
+            Err(syntax_error) => syntax_error,
+        }
+    }
+
+    pub fn lexeme_stream_report(&self) -> String {
+        match self.create_positioned_lexemes() {
+            Ok(Ok(lexemes)) => self.reporter().lexemes(&lexemes),
+            Ok(Err(error)) => self.reporter().lexemes_creation(&Err(error)),
             Err(syntax_error) => syntax_error,
         }
     }
 
     fn create_ast(&self) -> Result<Result<AbstractSyntaxTree, AstError>, String> {
-        let lexer_result = self.create_lexemes()?;
-        let lexemes = match lexer_result {
+        let lexemes = match self.create_positioned_lexemes()? {
             Ok(value) => value,
-            Err(_) => return Err(Reporter.lexemes_creation(&lexer_result)),
+            Err(error) => {
+                self.record_tree(false);
+                return Err(self.reporter().lexemes_creation(&Err(error)));
+            },
         };
 
-        Ok(AstParser::new(lexemes).parse())
+        let ast = self.parser(lexemes).parse();
+        self.record_tree(ast.is_ok());
+        Ok(ast)
+    }
+
+    fn parser(&self, lexemes: Vec<PositionedLexeme>) -> AstParser {
+        AstParser::with_positions(lexemes)
+            .with_max_depth(self.max_parse_depth)
+            .with_known_functions(self.known_functions.clone())
+            .with_case_insensitive_names(self.case_insensitive_names)
+    }
+
+    fn substitute_constants(&self, ast: AbstractSyntaxTree) -> AbstractSyntaxTree {
+        ast.substitute_constants_with_case(&self.constants, self.case_insensitive_names)
     }
 
     pub fn ast_report(&self) -> String {
-        match self.create_ast() {
-            Ok(ast_result) => Reporter.tree_build(&ast_result),
-            Err(error) => error,
+        let lexemes = match self.create_positioned_lexemes() {
+            Ok(Ok(value)) => value,
+            Ok(Err(error)) => return self.reporter().lexemes_creation(&Err(error)),
+            Err(error) => return error,
+        };
+
+        // Several `;`-separated expressions get a tree each
+        let results = self.parser(lexemes).parse_program();
+        self.record_tree(results.iter().all(Result::is_ok));
+        match results.as_slice() {
+            [result] => self.reporter().tree_build(result),
+            results => self.reporter().program_build(results),
+        }
+    }
+
+    pub fn parsed_ast(&self) -> Result<AbstractSyntaxTree, String> {
+        let ast_creation_result = self.create_ast()?;
+        match ast_creation_result {
+            Ok(value) => Ok(value),
+            Err(_) => Err(self.reporter().tree_build(&ast_creation_result)),
+        }
+    }
+
+    pub fn equivalence_report(&self) -> String {
+        let compared = self.with_code(&self.compared_code);
+        match (self.parsed_ast(), compared.parsed_ast()) {
+            (Ok(left), Ok(right)) => self.reporter().equivalence(&left, &right),
+            (Err(error), _) => error,
+            (_, Err(error)) => format!("Second expression:\n\n{}", error),
         }
     }
 
     fn compute_ast_1(&self) -> Result<Result<AbstractSyntaxTree, AstError>, String> {
+        self.compute_ast_1_with_divisor()
+            .map(|(compute_result, _)| compute_result)
+    }
+
+    // Parsed tree keeps the spans through the substitution of constants
+    fn compute_ast_1_with_divisor(&self) -> Result<ComputeWithDivisor, String> {
         let ast_creation_result = self.create_ast()?;
         let ast = match ast_creation_result {
             Ok(value) => value,
-            Err(_) => return Err(Reporter.tree_build(&ast_creation_result)),
+            Err(_) => return Err(self.reporter().tree_build(&ast_creation_result)),
         };
 
-        Ok(ast.compute())
+        let ast = self.substitute_constants(ast);
+        let compute_result = ast.clone().compute();
+        let divisor = match &compute_result {
+            Err(error) => ast.zero_divisor_span(error),
+            Ok(_) => None,
+        };
+
+        Ok((compute_result, divisor))
     }
 
     pub fn compute_1_report(&self) -> String {
-        match self.compute_ast_1() {
-            Ok(compute_result) => Reporter.computing(&compute_result, 1),
+        self.report_each(Self::compute_1_expression_report)
+    }
+
+    fn compute_1_expression_report(&self) -> String {
+        match self.compute_ast_1_with_divisor() {
+            Ok((compute_result, divisor)) => {
+                let report = self.reporter().computing(&compute_result, 1);
+                match divisor {
+                    Some(divisor) => format!(
+                        "{}\n{}",
+                        report,
+                        self.reporter().division_by_zero(&self.code, divisor)
+                    ),
+                    None => report,
+                }
+            },
             Err(error) => error,
         }
     }
@@ -97,19 +363,23 @@ impl CompilerContext {
         let ast_compute_result = self.compute_ast_1()?;
         let ast = match ast_compute_result {
             Ok(value) => value,
-            Err(_) => return Err(Reporter.computing(&ast_compute_result, 1)),
+            Err(_) => return Err(self.reporter().computing(&ast_compute_result, 1)),
         };
 
         if ast.is_finalized() {
-            return Err(Reporter.computing_finalization());
+            return Err(self.reporter().computing_finalization());
         }
 
         Ok(ast.transform())
     }
 
     pub fn transform_report(&self) -> String {
+        self.report_each(Self::transform_expression_report)
+    }
+
+    fn transform_expression_report(&self) -> String {
         match self.transform_ast() {
-            Ok(transform_result) => Reporter.transforming(&transform_result),
+            Ok(transform_result) => self.reporter().transforming(&transform_result),
             Err(error) => error,
         }
     }
@@ -119,7 +389,7 @@ impl CompilerContext {
         let ast = match ast_transformation_result {
             Ok(value) => value,
             Err(_) => {
-                return Err(Reporter.transforming(&ast_transformation_result));
+                return Err(self.reporter().transforming(&ast_transformation_result));
             },
         };
 
@@ -127,8 +397,12 @@ impl CompilerContext {
     }
 
     pub fn compute_2_report(&self) -> String {
+        self.report_each(Self::compute_2_expression_report)
+    }
+
+    fn compute_2_expression_report(&self) -> String {
         match self.compute_ast_2() {
-            Ok(compute_result) => Reporter.computing(&compute_result, 2),
+            Ok(compute_result) => self.reporter().computing(&compute_result, 2),
             Err(error) => error,
         }
     }
@@ -137,36 +411,206 @@ impl CompilerContext {
         let ast_compute_result = self.compute_ast_2()?;
         let ast = match ast_compute_result {
             Ok(value) => value,
-            Err(_) => return Err(Reporter.computing(&ast_compute_result, 2)),
+            Err(_) => return Err(self.reporter().computing(&ast_compute_result, 2)),
         };
 
         if ast.is_finalized() {
-            return Err(Reporter.computing_finalization());
+            return Err(self.reporter().computing_finalization());
         }
 
         Ok(ast.balance())
     }
 
     pub fn balance_report(&self) -> String {
+        self.report_each(Self::balance_expression_report)
+    }
+
+    fn balance_expression_report(&self) -> String {
         match self.balance_ast() {
-            Ok(balance_result) => Reporter.balancing(&balance_result),
+            Ok(balance_result) => self.reporter().balancing(&balance_result),
+            Err(error) => error,
+        }
+    }
+
+    fn balance_with_metrics(
+        &self,
+    ) -> Result<(AbstractSyntaxTree, AbstractSyntaxTree), String> {
+        let ast_compute_result = self.compute_ast_2()?;
+        let before = match ast_compute_result {
+            Ok(value) => value,
+            Err(_) => return Err(self.reporter().computing(&ast_compute_result, 2)),
+        };
+
+        if before.is_finalized() {
+            return Err(self.reporter().computing_finalization());
+        }
+
+        let ast_balance_result = before.clone().balance();
+        match ast_balance_result {
+            Ok(after) => Ok((before, after)),
+            Err(_) => Err(self.reporter().balancing(&ast_balance_result)),
+        }
+    }
+
+    pub fn metrics_report(&self) -> String {
+        self.report_each(Self::metrics_expression_report)
+    }
+
+    fn metrics_expression_report(&self) -> String {
+        match self.balance_with_metrics() {
+            Ok((before, after)) => {
+                let mut report = self.reporter().metrics(&before, &after);
+                // Limits are checked before the tree is optimized
+                if let Ok(parsed) = self.parsed_ast() {
+                    report.push_str(&self.reporter().complexity(&parsed));
+                }
+                report
+            },
             Err(error) => error,
         }
     }
 
+    /// Every tree of the pipeline, up to the last successful stage.
+    fn pipeline_stages(&self) -> Result<Vec<(&'static str, AbstractSyntaxTree)>, String> {
+        type Stage =
+            fn(&CompilerContext) -> Result<Result<AbstractSyntaxTree, AstError>, String>;
+        let pipeline: [(&'static str, Stage); 8] = [
+            ("Parsed", Self::create_ast),
+            ("Computed #1", Self::compute_ast_1),
+            ("Transformed", Self::transform_ast),
+            ("Computed #2", Self::compute_ast_2),
+            ("Balanced", Self::balance_ast),
+            ("Computed #3", Self::compute_ast_3),
+            ("Folded", Self::folding_ast),
+            ("Computed #4", Self::compute_ast_4),
+        ];
+
+        let mut stages = Vec::new();
+        for (label, stage) in pipeline {
+            match stage(self) {
+                Ok(Ok(tree)) => stages.push((label, tree)),
+                Ok(Err(error)) if stages.is_empty() => {
+                    return Err(self.reporter().tree_build(&Err(error)));
+                },
+                Err(error) if stages.is_empty() => return Err(error),
+                // Finalized or failed: the rest of the stages is not available
+                _ => break,
+            }
+        }
+
+        Ok(stages)
+    }
+
+    pub fn pipeline_diff_report(&self) -> String {
+        self.report_each(Self::pipeline_diff_expression_report)
+    }
+
+    fn pipeline_diff_expression_report(&self) -> String {
+        match self.pipeline_stages() {
+            Ok(stages) => self.reporter().pipeline_diff(&stages),
+            Err(error) => error,
+        }
+    }
+
+    /// Runs the pipeline once, measuring every stage of it.
+    /// Stops when the tree is finalized, a failed stage gives its report.
+    pub fn measure_pipeline(&self) -> Result<Timings, String> {
+        let mut timings = Timings::default();
+
+        let tokens = timings.measure("Tokenize", || self.tokenize());
+        let errors = timings.measure("Syntax", || self.analyze_syntax(&tokens));
+        if SyntaxError::has_errors(&errors) {
+            return Err(self.syntax_report());
+        }
+        let lexemes =
+            match timings.measure("Lexer", || Lexer::new(tokens).run_with_positions()) {
+                Ok(lexemes) => lexemes,
+                Err(error) => return Err(self.reporter().lexemes_creation(&Err(error))),
+            };
+        let parse_result = timings.measure("Parse", || self.parser(lexemes).parse());
+        self.record_tree(parse_result.is_ok());
+        let mut ast = match parse_result {
+            Ok(ast) => self.substitute_constants(ast),
+            Err(_) => return Err(self.reporter().tree_build(&parse_result)),
+        };
+
+        let compute = AbstractSyntaxTree::compute;
+        type Stage<'a> = (
+            &'static str,
+            &'a dyn Fn(AbstractSyntaxTree) -> Result<AbstractSyntaxTree, AstError>,
+            fn(&Reporter, &Result<AbstractSyntaxTree, AstError>) -> String,
+        );
+        let pipeline: [Stage; 7] = [
+            ("Compute", &compute, |reporter, result| {
+                reporter.computing(result, 1)
+            }),
+            (
+                "Transform",
+                &AbstractSyntaxTree::transform,
+                Reporter::transforming,
+            ),
+            ("Compute", &compute, |reporter, result| {
+                reporter.computing(result, 2)
+            }),
+            ("Balance", &AbstractSyntaxTree::balance, Reporter::balancing),
+            ("Compute", &compute, |reporter, result| {
+                reporter.computing(result, 3)
+            }),
+            ("Fold", &AbstractSyntaxTree::fold, Reporter::folding),
+            ("Compute", &compute, |reporter, result| {
+                reporter.computing(result, 4)
+            }),
+        ];
+        for (label, stage, report) in pipeline {
+            // Same as in the stage reports, a finalized tree is not optimized
+            if label != "Compute" && ast.is_finalized() {
+                break;
+            }
+            let result = timings.measure(label, || stage(ast));
+            match result {
+                Ok(tree) => ast = tree,
+                Err(_) => return Err(report(&self.reporter(), &result)),
+            }
+        }
+
+        Ok(timings)
+    }
+
+    pub fn timings_report(&self) -> String {
+        self.report_each(Self::timings_expression_report)
+    }
+
+    fn timings_expression_report(&self) -> String {
+        match self.measure_pipeline() {
+            Ok(timings) => self.reporter().timings(&timings),
+            Err(error) => error,
+        }
+    }
+
+    pub fn balanced_ast(&self) -> Result<AbstractSyntaxTree, String> {
+        match self.balance_ast()? {
+            Ok(ast) => Ok(ast),
+            Err(error) => Err(error.to_string()),
+        }
+    }
+
     fn compute_ast_3(&self) -> Result<Result<AbstractSyntaxTree, AstError>, String> {
         let ast_balance_result = self.balance_ast()?;
         let ast = match ast_balance_result {
             Ok(value) => value,
-            Err(_) => return Err(Reporter.balancing(&ast_balance_result)),
+            Err(_) => return Err(self.reporter().balancing(&ast_balance_result)),
         };
 
         Ok(ast.compute())
     }
 
     pub fn compute_3_report(&self) -> String {
+        self.report_each(Self::compute_3_expression_report)
+    }
+
+    fn compute_3_expression_report(&self) -> String {
         match self.compute_ast_3() {
-            Ok(compute_result) => Reporter.computing(&compute_result, 3),
+            Ok(compute_result) => self.reporter().computing(&compute_result, 3),
             Err(error) => error,
         }
     }
@@ -175,19 +619,23 @@ impl CompilerContext {
         let ast_compute_result = self.compute_ast_3()?;
         let ast = match ast_compute_result {
             Ok(value) => value,
-            Err(_) => return Err(Reporter.computing(&ast_compute_result, 3)),
+            Err(_) => return Err(self.reporter().computing(&ast_compute_result, 3)),
         };
 
         if ast.is_finalized() {
-            return Err(Reporter.computing_finalization());
+            return Err(self.reporter().computing_finalization());
         }
 
         Ok(ast.fold())
     }
 
     pub fn folding_report(&self) -> String {
+        self.report_each(Self::folding_expression_report)
+    }
+
+    fn folding_expression_report(&self) -> String {
         match self.folding_ast() {
-            Ok(folding_result) => Reporter.folding(&folding_result),
+            Ok(folding_result) => self.reporter().folding(&folding_result),
             Err(error) => error,
         }
     }
@@ -196,15 +644,65 @@ impl CompilerContext {
         let ast_folding_result = self.folding_ast()?;
         let ast = match ast_folding_result {
             Ok(value) => value,
-            Err(_) => return Err(Reporter.folding(&ast_folding_result)),
+            Err(_) => return Err(self.reporter().folding(&ast_folding_result)),
         };
 
         Ok(ast.compute())
     }
 
     pub fn compute_4_report(&self) -> String {
+        self.report_each(Self::compute_4_expression_report)
+    }
+
+    fn compute_4_expression_report(&self) -> String {
         match self.compute_ast_4() {
-            Ok(compute_result) => Reporter.computing(&compute_result, 4),
+            Ok(compute_result) => self.reporter().computing(&compute_result, 4),
+            Err(error) => error,
+        }
+    }
+
+    fn factor_ast(&self) -> Result<Result<AbstractSyntaxTree, AstError>, String> {
+        let ast_computing_result = self.compute_ast_4()?;
+        let ast = match ast_computing_result {
+            Ok(value) => value,
+            Err(_) => return Err(self.reporter().computing(&ast_computing_result, 4)),
+        };
+
+        if ast.is_finalized() {
+            return Err(self.reporter().computing_finalization());
+        }
+
+        Ok(ast.factor_common_terms())
+    }
+
+    pub fn factoring_report(&self) -> String {
+        self.report_each(Self::factoring_expression_report)
+    }
+
+    fn factoring_expression_report(&self) -> String {
+        match self.factor_ast() {
+            Ok(factoring_result) => self.reporter().factoring(&factoring_result),
+            Err(error) => error,
+        }
+    }
+
+    fn find_common_subexpressions(&self) -> Result<Vec<(String, usize)>, String> {
+        let ast_computing_result = self.compute_ast_4()?;
+        let ast = match ast_computing_result {
+            Ok(value) => value,
+            Err(_) => return Err(self.reporter().computing(&ast_computing_result, 4)),
+        };
+
+        Ok(ast.find_common_subexpressions())
+    }
+
+    pub fn cse_report(&self) -> String {
+        self.report_each(Self::cse_expression_report)
+    }
+
+    fn cse_expression_report(&self) -> String {
+        match self.find_common_subexpressions() {
+            Ok(subexpressions) => self.reporter().cse(&subexpressions),
             Err(error) => error,
         }
     }
@@ -213,17 +711,67 @@ impl CompilerContext {
         let ast_computing_result = self.compute_ast_4()?;
         let ast = match ast_computing_result {
             Ok(value) => value,
-            Err(_) => return Err(Reporter.computing(&ast_computing_result, 4)),
+            Err(_) => return Err(self.reporter().computing(&ast_computing_result, 4)),
         };
 
         let forms = ast.find_equivalent_forms();
 
-        Ok(forms.iter().map(|form| form.to_pretty_string()).collect())
+        let options = PrettyOptions {
+            number_format: self.number_format,
+            ..PrettyOptions::default()
+        };
+        Ok(forms
+            .iter()
+            .map(|form| form.to_pretty_string_with(&options))
+            .collect())
     }
 
     pub fn equivalent_forms_report(&self) -> String {
+        self.report_each(Self::equivalent_forms_expression_report)
+    }
+
+    fn equivalent_forms_expression_report(&self) -> String {
+        let source = match self.parsed_ast() {
+            Ok(value) => value,
+            Err(error) => return error,
+        };
+
+        // Constants are solved by the first computing, there are no other forms
+        if let Ok(AbstractSyntaxTree {
+            peek: AstNode::Number(value),
+            ..
+        }) = self.substitute_constants(source.clone()).compute()
+        {
+            return self.reporter().constant_value(value);
+        }
+
         match self.find_equivalent_forms() {
-            Ok(forms) => Reporter.finding_equivalent_form(&forms),
+            Ok(forms) => self.reporter().equivalent_forms(&source, &forms),
+            Err(error) => error,
+        }
+    }
+
+    /// LaTeX form of the parsed expressions, before any optimization.
+    pub fn latex(&self) -> Result<String, String> {
+        let expressions = self.expressions();
+        if expressions.len() == 1 || SyntaxError::has_errors(&self.check_syntax()) {
+            return Ok(self.parsed_ast()?.to_latex_with(&self.number_format));
+        }
+
+        let latex = expressions
+            .iter()
+            .map(|code| {
+                let tree = self.with_code(code).parsed_ast()?;
+                Ok(tree.to_latex_with(&self.number_format))
+            })
+            .collect::<Result<Vec<String>, String>>()?;
+
+        Ok(latex.join(";\\quad "))
+    }
+
+    pub fn latex_report(&self) -> String {
+        match self.latex() {
+            Ok(latex) => self.reporter().latex(&latex),
             Err(error) => error,
         }
     }
@@ -232,7 +780,7 @@ impl CompilerContext {
         let ast_computing_result = self.compute_ast_4()?;
         let ast = match ast_computing_result {
             Ok(value) => value,
-            Err(_) => return Err(Reporter.computing(&ast_computing_result, 4)),
+            Err(_) => return Err(self.reporter().computing(&ast_computing_result, 4)),
         };
         let simulation_result =
             VectorSystemSimulator::new(&ast, &self.system_configuration).simulate();
@@ -241,9 +789,13 @@ impl CompilerContext {
     }
 
     pub fn pcs_simulation_report(&self) -> String {
+        self.report_each(Self::pcs_simulation_expression_report)
+    }
+
+    fn pcs_simulation_expression_report(&self) -> String {
         let computation_report = self.compute_4_report();
         let simulation_report = match self.run_pcs_simulation() {
-            Ok(simulation_result) => Reporter.pcs_simulation(&simulation_result),
+            Ok(simulation_result) => self.reporter().pcs_simulation(&simulation_result),
             Err(error) => return error,
         };
 
@@ -255,23 +807,27 @@ impl CompilerContext {
 
         let mut trees = Vec::new();
         for form in &equivalent_forms {
-            let context = CompilerContext {
-                code: form.clone(),
-                pretty_output: self.pretty_output,
-                system_configuration: self.system_configuration.clone(),
-            };
+            let context = self.with_code(form);
             let ast_computing_result = context.compute_ast_4()?;
             let ast = match ast_computing_result {
                 Ok(value) => value,
-                Err(_) => return Err(Reporter.computing(&ast_computing_result, 4)),
+                Err(_) => {
+                    return Err(self.reporter().computing(&ast_computing_result, 4));
+                },
             };
             trees.push(ast);
         }
 
-        Researcher::new(&trees, &self.system_configuration).run()
+        Researcher::new(&trees, &self.system_configuration)
+            .with_number_format(self.number_format)
+            .run()
     }
 
     pub fn optimization_research_report(&self) -> String {
+        self.report_each(Self::optimization_research_expression_report)
+    }
+
+    fn optimization_research_expression_report(&self) -> String {
         let optimization_reports = self.run_optimization_research();
 
         let optimization_reports = match optimization_reports {
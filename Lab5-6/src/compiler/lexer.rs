@@ -1,13 +1,16 @@
 use crate::compiler::reports::Reporter;
-use crate::compiler::tokenizer::{Token, TokenType};
-use crate::utils::StringBuffer;
-use std::num::ParseFloatError;
+use crate::compiler::tokenizer::{Token, TokenType, Tokenizer};
+use crate::utils::{StringBuffer, escape_string};
+use std::num::{IntErrorKind, ParseFloatError};
+use std::ops::Range;
+
+/// Lexeme with the source range of its tokens.
+pub type PositionedLexeme = (Lexeme, Range<usize>);
 
 #[derive(Debug)]
 pub struct Lexer {
     tokens: Vec<Token>,
     current_index: usize,
-    in_string: bool,
 }
 
 #[derive(Clone, Debug, PartialEq)]
@@ -26,7 +29,16 @@ pub enum Lexeme {
     Not,
     And,
     Or,
+    Less,
+    LessOrEqual,
+    Greater,
+    GreaterOrEqual,
+    Equal,
+    NotEqual,
     Comma,
+    Question,
+    Colon,
+    Semicolon,
     String(String),
 }
 
@@ -47,35 +59,125 @@ impl Lexeme {
             Lexeme::Not => "Not",
             Lexeme::And => "And",
             Lexeme::Or => "Or",
+            Lexeme::Less => "Less",
+            Lexeme::LessOrEqual => "Less Or Equal",
+            Lexeme::Greater => "Greater",
+            Lexeme::GreaterOrEqual => "Greater Or Equal",
+            Lexeme::Equal => "Equal",
+            Lexeme::NotEqual => "Not Equal",
             Lexeme::Comma => "Comma",
+            Lexeme::Question => "Question",
+            Lexeme::Colon => "Colon",
+            Lexeme::Semicolon => "Semicolon",
             Lexeme::String(_) => "String",
         }
     }
 }
 
+// A lexeme of a single token. Numbers with a fractional part
+// are three tokens (`12`, `.`, `34`), so they are joined by the lexer,
+// as well as comparisons with `=` (`<=`, `==`, `!=`).
+impl TryFrom<&Token> for Lexeme {
+    type Error = LexerError;
+
+    fn try_from(token: &Token) -> Result<Self, Self::Error> {
+        let value = || {
+            token
+                .value
+                .clone()
+                .ok_or(LexerError::TokenMissingValue(token.clone()))
+        };
+
+        let lexeme = match token.kind {
+            TokenType::Number => Lexeme::Number(Lexer::parse_number(token, value()?)?),
+            TokenType::Identifier => Lexeme::Identifier(value()?),
+            TokenType::Plus => Lexeme::Plus,
+            TokenType::Minus => Lexeme::Minus,
+            TokenType::Asterisk => Lexeme::Multiply,
+            TokenType::Slash => Lexeme::Divide,
+            TokenType::Percent => Lexeme::Modulus,
+            TokenType::LeftParenthesis => Lexeme::LeftParenthesis,
+            TokenType::RightParenthesis => Lexeme::RightParenthesis,
+            TokenType::LeftBracket => Lexeme::LeftBracket,
+            TokenType::RightBracket => Lexeme::RightBracket,
+            TokenType::ExclamationMark => Lexeme::Not,
+            TokenType::Ampersand => Lexeme::And,
+            TokenType::Pipe => Lexeme::Or,
+            TokenType::Less => Lexeme::Less,
+            TokenType::Greater => Lexeme::Greater,
+            TokenType::Comma => Lexeme::Comma,
+            TokenType::Question => Lexeme::Question,
+            TokenType::Colon => Lexeme::Colon,
+            TokenType::Semicolon => Lexeme::Semicolon,
+            TokenType::StringLiteral => Lexeme::String(Lexer::unescape(token)?),
+            TokenType::Dot
+            | TokenType::QuotationMark
+            | TokenType::Equal
+            | TokenType::Space
+            | TokenType::Tab
+            | TokenType::NewLine
+            | TokenType::Backslash
+            | TokenType::Unknown => {
+                return Err(LexerError::UnconvertibleToken(token.clone()));
+            },
+        };
+
+        Ok(lexeme)
+    }
+}
+
+// Kind of the token the lexeme is made of (of the first one for fractional numbers)
+impl From<&Lexeme> for TokenType {
+    fn from(lexeme: &Lexeme) -> Self {
+        match lexeme {
+            Lexeme::Identifier(_) => TokenType::Identifier,
+            Lexeme::Number(_) => TokenType::Number,
+            Lexeme::Plus => TokenType::Plus,
+            Lexeme::Minus => TokenType::Minus,
+            Lexeme::Multiply => TokenType::Asterisk,
+            Lexeme::Divide => TokenType::Slash,
+            Lexeme::Modulus => TokenType::Percent,
+            Lexeme::LeftParenthesis => TokenType::LeftParenthesis,
+            Lexeme::RightParenthesis => TokenType::RightParenthesis,
+            Lexeme::LeftBracket => TokenType::LeftBracket,
+            Lexeme::RightBracket => TokenType::RightBracket,
+            Lexeme::Not => TokenType::ExclamationMark,
+            Lexeme::And => TokenType::Ampersand,
+            Lexeme::Or => TokenType::Pipe,
+            Lexeme::Less | Lexeme::LessOrEqual => TokenType::Less,
+            Lexeme::Greater | Lexeme::GreaterOrEqual => TokenType::Greater,
+            Lexeme::Equal => TokenType::Equal,
+            Lexeme::NotEqual => TokenType::ExclamationMark,
+            Lexeme::Comma => TokenType::Comma,
+            Lexeme::Question => TokenType::Question,
+            Lexeme::Colon => TokenType::Colon,
+            Lexeme::Semicolon => TokenType::Semicolon,
+            Lexeme::String(_) => TokenType::StringLiteral,
+        }
+    }
+}
+
 impl Lexer {
     pub fn new(tokens: Vec<Token>) -> Self {
         Self {
             tokens,
             current_index: 0,
-            in_string: false,
         }
     }
 
     pub fn run(&mut self) -> Result<Vec<Lexeme>, LexerError> {
+        let lexemes = self.run_with_positions()?;
+        Ok(lexemes.into_iter().map(|(lexeme, _)| lexeme).collect())
+    }
+
+    /// Like `run`, but every lexeme comes with the source range of its tokens.
+    pub fn run_with_positions(&mut self) -> Result<Vec<PositionedLexeme>, LexerError> {
         type Error = LexerError;
-        let mut lexemes: Vec<Lexeme> = Vec::new();
-        let mut string_buffer = String::new();
+        let mut lexemes: Vec<PositionedLexeme> = Vec::new();
 
         while self.current_index < self.tokens.len() {
             let token = &self.tokens[self.current_index];
 
-            if self.in_string && token.kind != TokenType::QuotationMark {
-                string_buffer.push_str(token.display_value().as_str());
-                self.current_index += 1;
-                continue;
-            }
-
             let mut push_current_index_for = 1;
 
             let lexeme = match &token.kind {
@@ -86,6 +188,19 @@ impl Lexer {
                         .ok_or(Error::TokenMissingValue(token.clone()))?
                         .to_string();
 
+                    // Invalid digits are left to the syntax analyzer: `0` and an identifier
+                    if let Some(next) = self.peek_next()
+                        && let Some((radix, digits)) =
+                            Tokenizer::radix_literal(token, next)
+                        && let Some(value) = Self::parse_radix_number(next, radix, digits)
+                    {
+                        let last = next.position.end;
+                        lexemes
+                            .push((Lexeme::Number(value?), token.position.start..last));
+                        self.current_index += 2;
+                        continue;
+                    }
+
                     if let Some(possible_dot) = self.peek_next()
                         && possible_dot.kind == TokenType::Dot
                         && let Some(fractional_part_token) = self.peek_next_by(2)
@@ -96,59 +211,92 @@ impl Lexer {
                         push_current_index_for += 2;
                     }
 
-                    let number: f64 = number
-                        .parse()
-                        .map_err(|e| Error::ParseFloatError(token.clone(), e))?;
-                    Lexeme::Number(number)
-                },
-                TokenType::Identifier => {
-                    let identifier = token
-                        .value
-                        .as_ref()
-                        .ok_or(Error::TokenMissingValue(token.clone()))?
-                        .to_string();
-                    Lexeme::Identifier(identifier)
+                    Lexeme::Number(Self::parse_number(token, number)?)
                 },
-                TokenType::Plus => Lexeme::Plus,
-                TokenType::Minus => Lexeme::Minus,
-                TokenType::Asterisk => Lexeme::Multiply,
-                TokenType::Slash => Lexeme::Divide,
-                TokenType::Percent => Lexeme::Modulus,
-                TokenType::LeftParenthesis => Lexeme::LeftParenthesis,
-                TokenType::RightParenthesis => Lexeme::RightParenthesis,
-                TokenType::LeftBracket => Lexeme::LeftBracket,
-                TokenType::RightBracket => Lexeme::RightBracket,
-                TokenType::ExclamationMark => Lexeme::Not,
-                TokenType::Ampersand => Lexeme::And,
-                TokenType::Pipe => Lexeme::Or,
-                TokenType::Comma => Lexeme::Comma,
-                TokenType::QuotationMark => {
-                    self.in_string = !self.in_string;
-                    if !self.in_string {
-                        let lexeme = Lexeme::String(string_buffer.clone());
-                        string_buffer.clear();
-                        lexeme
-                    } else {
-                        self.current_index += 1;
-                        continue;
+                TokenType::Less
+                | TokenType::Greater
+                | TokenType::Equal
+                | TokenType::ExclamationMark
+                    if self.peek_next().is_some_and(|next| {
+                        next.kind == TokenType::Equal
+                            && next.position.start == token.position.end
+                    }) =>
+                {
+                    push_current_index_for += 1;
+                    match token.kind {
+                        TokenType::Less => Lexeme::LessOrEqual,
+                        TokenType::Greater => Lexeme::GreaterOrEqual,
+                        TokenType::Equal => Lexeme::Equal,
+                        _ => Lexeme::NotEqual,
                     }
                 },
-                TokenType::Dot
-                | TokenType::Space
-                | TokenType::Tab
-                | TokenType::NewLine
-                | TokenType::Unknown => {
-                    return Err(Error::NotExpectedToken(token.clone()));
-                },
+                _ => Lexeme::try_from(token)?,
             };
 
-            lexemes.push(lexeme);
+            let last = &self.tokens[self.current_index + push_current_index_for - 1];
+            lexemes.push((lexeme, token.position.start..last.position.end));
             self.current_index += push_current_index_for;
         }
 
         Ok(lexemes)
     }
 
+    fn parse_number(token: &Token, literal: String) -> Result<f64, LexerError> {
+        let number: f64 = literal
+            .parse()
+            .map_err(|e| LexerError::ParseFloatError(token.clone(), e))?;
+        // Too long literals are parsed as `inf` instead of failing
+        if !number.is_finite() {
+            return Err(LexerError::NumericOverflow(token.clone(), literal));
+        }
+        Ok(number)
+    }
+
+    // `None` for invalid digits, too long literals are errors
+    fn parse_radix_number(
+        token: &Token, radix: u32, digits: &str,
+    ) -> Option<Result<f64, LexerError>> {
+        match u64::from_str_radix(digits, radix) {
+            Ok(number) => Some(Ok(number as f64)),
+            Err(error) if *error.kind() == IntErrorKind::PosOverflow => {
+                let literal = format!("0{}", token.value.as_deref().unwrap_or_default());
+                Some(Err(LexerError::NumericOverflow(token.clone(), literal)))
+            },
+            Err(_) => None,
+        }
+    }
+
+    fn unescape(token: &Token) -> Result<String, LexerError> {
+        let value = token
+            .value
+            .as_ref()
+            .ok_or(LexerError::TokenMissingValue(token.clone()))?;
+
+        let mut result = String::with_capacity(value.len());
+        let mut chars = value.chars();
+        while let Some(symbol) = chars.next() {
+            if symbol != '\\' {
+                result.push(symbol);
+                continue;
+            }
+
+            let unescaped = match chars.next() {
+                Some('n') => '\n',
+                Some('t') => '\t',
+                Some('\\') => '\\',
+                Some('"') => '"',
+                other => {
+                    let sequence =
+                        format!("\\{}", other.map(String::from).unwrap_or_default());
+                    return Err(LexerError::InvalidEscape(token.clone(), sequence));
+                },
+            };
+            result.push(unescaped);
+        }
+
+        Ok(result)
+    }
+
     fn peek_next(&self) -> Option<&Token> {
         self.tokens.get(self.current_index + 1)
     }
@@ -182,46 +330,91 @@ impl Reporter {
 
         buffer.get()
     }
+
+    /// Lexemes with their source ranges, to see which tokens were merged,
+    /// e.g. `4.5` from three tokens.
+    pub fn lexemes(&self, lexemes: &[PositionedLexeme]) -> String {
+        let mut buffer = StringBuffer::default();
+
+        buffer.add_line(format!("Lexeme stream: {} lexemes.\n", lexemes.len()));
+        for (index, (lexeme, position)) in lexemes.iter().enumerate() {
+            let value = match lexeme {
+                Lexeme::Identifier(name) => name.clone(),
+                Lexeme::Number(number) => self.number_format.apply(*number),
+                Lexeme::String(string) => escape_string(string),
+                _ => String::new(),
+            };
+            buffer.add_line(format!(
+                "{:3}) {:20}: {:20} [Position: {}..{}]",
+                index + 1,
+                lexeme.display_type(),
+                value,
+                position.start + 1,
+                position.end
+            ));
+        }
+
+        buffer.get()
+    }
 }
 
 #[derive(Debug)]
 pub enum LexerError {
-    NotExpectedToken(Token),
+    InvalidEscape(Token, String),
+    NumericOverflow(Token, String),
     ParseFloatError(Token, ParseFloatError),
     TokenMissingValue(Token),
+    UnconvertibleToken(Token),
 }
 
 impl std::fmt::Display for LexerError {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         let text = match self {
-            Self::NotExpectedToken(token) => format!(
-                "Not expected token with kind \"{}\" [{}..{}]",
-                token.kind,
-                token.position.start,
-                token.position.end - 1
+            Self::InvalidEscape(token, sequence) => format!(
+                "Invalid escape sequence \"{}\" in string {}. Expected \\n, \\t, \\\\ or \\\"",
+                sequence,
+                token.display_position()
+            ),
+            Self::NumericOverflow(token, literal) => format!(
+                "Number \"{}\" does not fit into a float {}",
+                literal,
+                token.display_position()
             ),
             Self::ParseFloatError(token, error) => format!(
-                "Failed to parse float [{}..{}]: {}",
-                token.position.start,
-                token.position.end - 1,
+                "Failed to parse float {}: {}",
+                token.display_position(),
                 error
             ),
             Self::TokenMissingValue(token) => format!(
-                "Token with kind \"{}\" [{}..{}] is missing a value",
+                "Token with kind \"{}\" {} is missing a value",
                 token.kind,
-                token.position.start,
-                token.position.end - 1
+                token.display_position()
+            ),
+            Self::UnconvertibleToken(token) => format!(
+                "Not expected token with kind \"{}\" {}",
+                token.kind,
+                token.display_position()
             ),
         };
 
-        write!(f, "Lexer error. {:?}", text)
+        write!(f, "{}", text)
+    }
+}
+
+impl std::error::Error for LexerError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::ParseFloatError(_, error) => Some(error),
+            _ => None,
+        }
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::compiler::tokenizer::Tokenizer;
+    use crate::compiler::ast::test_support::parse;
+    use crate::compiler::ast::tree::AstNode;
 
     #[test]
     fn test_1() {
@@ -273,4 +466,256 @@ mod tests {
         ];
         assert_eq!(actual_lexemes, expected_lexemes);
     }
+
+    #[test]
+    fn test_3() {
+        let literal = "9".repeat(400);
+        let code = format!("a + {}", literal);
+
+        let tokens = Tokenizer::process(&code);
+        let lexer_result = Lexer::new(tokens).run();
+
+        let Err(LexerError::NumericOverflow(_, actual_literal)) = &lexer_result else {
+            panic!("Expected numeric overflow, got {:?}", lexer_result)
+        };
+        assert_eq!(actual_literal, &literal);
+        assert_eq!(
+            Reporter::default().lexemes_creation(&lexer_result),
+            format!(
+                "Lexer error: Number \"{}\" does not fit into a float [Line 1, Col 5]\n",
+                literal
+            )
+        );
+    }
+
+    #[test]
+    fn test_4() {
+        let tokens = Tokenizer::process("a +\n b # c");
+        let lexer_result = Lexer::new(tokens).run();
+
+        assert!(matches!(
+            lexer_result,
+            Err(LexerError::UnconvertibleToken(_))
+        ));
+        assert_eq!(
+            Reporter::default().lexemes_creation(&lexer_result),
+            "Lexer error: Not expected token with kind \"NewLine\" [Line 1, Col 4]\n"
+        );
+
+        let tokens = Tokenizer::process("a + b # c");
+        let lexer_result = Lexer::new(tokens).run();
+        assert_eq!(
+            Reporter::default().lexemes_creation(&lexer_result),
+            "Lexer error: Not expected token with kind \"Unknown\" [Line 1, Col 7]\n"
+        );
+    }
+
+    #[test]
+    fn test_5() {
+        let code = r#"f("a\tb", "say \"hi\"\n", "back\\slash")"#;
+
+        let tokens = Tokenizer::process(code);
+        let Ok(lexemes) = Lexer::new(tokens).run() else {
+            panic!("Failed to create lexemes for: {}", code)
+        };
+        let strings: Vec<&Lexeme> = lexemes
+            .iter()
+            .filter(|lexeme| matches!(lexeme, Lexeme::String(_)))
+            .collect();
+        assert_eq!(
+            strings,
+            vec![
+                &Lexeme::String("a\tb".to_string()),
+                &Lexeme::String("say \"hi\"\n".to_string()),
+                &Lexeme::String("back\\slash".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_6() {
+        let code = r#"a + "bad\q""#;
+
+        let tokens = Tokenizer::process(code);
+        let lexer_result = Lexer::new(tokens).run();
+        let Err(LexerError::InvalidEscape(token, sequence)) = &lexer_result else {
+            panic!("Expected invalid escape, got: {:?}", lexer_result)
+        };
+        assert_eq!(token.position, 4..11);
+        assert_eq!(sequence, "\\q");
+        assert_eq!(
+            lexer_result.map_err(|error| error.to_string()),
+            Err(
+                r#"Invalid escape sequence "\q" in string [Line 1, Col 5]. Expected \n, \t, \\ or \""#
+                    .to_string()
+            )
+        );
+    }
+
+    #[test]
+    fn test_7() {
+        let tokens = Tokenizer::process("sin(x) + 12.5");
+        let Ok(lexemes) = Lexer::new(tokens).run_with_positions() else {
+            panic!("Failed to create lexemes")
+        };
+        let positions: Vec<Range<usize>> =
+            lexemes.into_iter().map(|(_, position)| position).collect();
+        // The number is made of three tokens
+        assert_eq!(positions, vec![0..3, 3..4, 4..5, 5..6, 7..8, 9..13]);
+    }
+
+    #[test]
+    fn test_8() {
+        let operators = [
+            ("+", Lexeme::Plus),
+            ("-", Lexeme::Minus),
+            ("*", Lexeme::Multiply),
+            ("/", Lexeme::Divide),
+            ("%", Lexeme::Modulus),
+            ("(", Lexeme::LeftParenthesis),
+            (")", Lexeme::RightParenthesis),
+            ("[", Lexeme::LeftBracket),
+            ("]", Lexeme::RightBracket),
+            ("!", Lexeme::Not),
+            ("&", Lexeme::And),
+            ("|", Lexeme::Or),
+            (",", Lexeme::Comma),
+            ("?", Lexeme::Question),
+            (":", Lexeme::Colon),
+            (";", Lexeme::Semicolon),
+            ("<", Lexeme::Less),
+            (">", Lexeme::Greater),
+        ];
+
+        for (code, expected) in operators {
+            let tokens = Tokenizer::process(code);
+            let Some(token) = tokens.first() else {
+                panic!("No tokens for: {}", code)
+            };
+            let Ok(lexeme) = Lexeme::try_from(token) else {
+                panic!("Failed to convert token: {:?}", token)
+            };
+            assert_eq!(lexeme, expected);
+            assert_eq!(TokenType::from(&lexeme), token.kind);
+        }
+
+        let tokens = Tokenizer::process("#");
+        let Some(token) = tokens.first() else {
+            panic!("No tokens for: #")
+        };
+        let Err(LexerError::UnconvertibleToken(unconvertible)) = Lexeme::try_from(token)
+        else {
+            panic!("Unknown token is converted: {:?}", token)
+        };
+        assert_eq!(&unconvertible, token);
+    }
+
+    #[test]
+    fn test_lexeme_stream() {
+        let tokens = Tokenizer::process("a + 2");
+        let Ok(lexemes) = Lexer::new(tokens).run_with_positions() else {
+            panic!("Failed to create lexemes")
+        };
+
+        let report = Reporter::default().lexemes(&lexemes);
+        let lines: Vec<&str> = report.lines().map(str::trim_end).collect();
+        assert_eq!(
+            lines,
+            vec![
+                "Lexeme stream: 3 lexemes.",
+                "",
+                "  1) Identifier          : a                    [Position: 1..1]",
+                "  2) Plus                :                      [Position: 3..3]",
+                "  3) Number              : 2.000                [Position: 5..5]",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_comparisons() {
+        let Ok(actual) =
+            Lexer::new(Tokenizer::process("a <= b == c != d >= e")).run_with_positions()
+        else {
+            panic!("Failed to create lexemes")
+        };
+        let operators: Vec<PositionedLexeme> = actual
+            .into_iter()
+            .filter(|(lexeme, _)| !matches!(lexeme, Lexeme::Identifier(_)))
+            .collect();
+        assert_eq!(
+            operators,
+            vec![
+                (Lexeme::LessOrEqual, 2..4),
+                (Lexeme::Equal, 7..9),
+                (Lexeme::NotEqual, 12..14),
+                (Lexeme::GreaterOrEqual, 17..19),
+            ]
+        );
+
+        // `=` that is not glued is not a part of the comparison
+        assert!(matches!(
+            Lexer::new(Tokenizer::process("a < = b")).run(),
+            Err(LexerError::UnconvertibleToken(token)) if token.kind == TokenType::Equal
+        ));
+    }
+
+    #[test]
+    fn test_radix_literals() {
+        let lexemes = |code: &str| {
+            let tokens =
+                Tokenizer::insert_implicit_multiplication(Tokenizer::process(code));
+            Lexer::new(tokens).run_with_positions()
+        };
+
+        let Ok(actual) = lexemes("0xFF + 0b101 * 0XaB") else {
+            panic!("Failed to create lexemes")
+        };
+        assert_eq!(
+            actual,
+            vec![
+                (Lexeme::Number(255.0), 0..4),
+                (Lexeme::Plus, 5..6),
+                (Lexeme::Number(5.0), 7..12),
+                (Lexeme::Multiply, 13..14),
+                (Lexeme::Number(171.0), 15..19),
+            ]
+        );
+
+        // Invalid digits: `0` and an identifier, as before
+        let Ok(actual) = lexemes("0x0R") else {
+            panic!("Failed to create lexemes")
+        };
+        assert_eq!(
+            actual,
+            vec![
+                (Lexeme::Number(0.0), 0..1),
+                (Lexeme::Identifier("x0R".to_string()), 1..4),
+            ]
+        );
+
+        // The whole 64-bit range, not only the signed one
+        let Ok(actual) = lexemes("0x8000000000000000 + 0xFFFFFFFFFFFFFFFF") else {
+            panic!("Failed to create lexemes")
+        };
+        assert_eq!(
+            actual,
+            vec![
+                (Lexeme::Number(9_223_372_036_854_775_808.0), 0..18),
+                (Lexeme::Plus, 19..20),
+                (Lexeme::Number(u64::MAX as f64), 21..39),
+            ]
+        );
+
+        let code = format!("0x{}", "F".repeat(17));
+        assert!(
+            matches!(lexemes(&code), Err(LexerError::NumericOverflow(_, literal)) if literal == code)
+        );
+
+        for (code, expected) in [("0xFF + 1", 256.0), ("0b101", 5.0)] {
+            let Ok(computed) = parse(code).compute() else {
+                panic!("Failed to compute AST for: {}", code)
+            };
+            assert_eq!(computed.peek, AstNode::Number(expected), "Code: {}", code);
+        }
+    }
 }
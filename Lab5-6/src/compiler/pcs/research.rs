@@ -1,4 +1,4 @@
-use crate::compiler::ast::tree::AbstractSyntaxTree;
+use crate::compiler::ast::tree::{AbstractSyntaxTree, NumberFormat};
 use crate::compiler::pcs::SystemConfiguration;
 use crate::compiler::pcs::vector::{SimulationResult, VectorSystemSimulator};
 use crate::compiler::reports::Reporter;
@@ -7,6 +7,7 @@ use crate::utils::StringBuffer;
 pub struct Researcher<'a> {
     forms: &'a Vec<AbstractSyntaxTree>,
     configuration: &'a SystemConfiguration,
+    number_format: NumberFormat,
 }
 
 pub struct OptimizationReport {
@@ -23,9 +24,15 @@ impl<'a> Researcher<'a> {
         Self {
             forms: equivalent_forms,
             configuration: system_configuration,
+            number_format: NumberFormat::default(),
         }
     }
 
+    pub fn with_number_format(mut self, number_format: NumberFormat) -> Self {
+        self.number_format = number_format;
+        self
+    }
+
     pub fn run(&self) -> Result<Vec<OptimizationReport>, String> {
         let mut results = Vec::new();
 
@@ -35,7 +42,7 @@ impl<'a> Researcher<'a> {
 
             results.push(OptimizationReport {
                 index,
-                canonical_string: form.to_canonical_string(),
+                canonical_string: form.to_canonical_string_with(&self.number_format),
                 result,
             });
         }
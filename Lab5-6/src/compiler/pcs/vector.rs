@@ -32,7 +32,7 @@ impl OperationType {
             BinaryOperationKind::Plus => Self::Add,
             BinaryOperationKind::Minus => Self::Sub,
             BinaryOperationKind::Multiply => Self::Mul,
-            BinaryOperationKind::Divide => Self::Div,
+            BinaryOperationKind::Divide | BinaryOperationKind::Modulo => Self::Div,
             _ => OperationType::Load,
         }
     }
@@ -177,6 +177,7 @@ impl<'a> VectorSystemSimulator<'a> {
                     BinaryOperationKind::Minus => "-",
                     BinaryOperationKind::Multiply => "*",
                     BinaryOperationKind::Divide => "/",
+                    BinaryOperationKind::Modulo => "%",
                     _ => "?",
                 };
                 let display_name =
@@ -1 +1,268 @@
-pub struct Reporter;
+use crate::compiler::ast::tree::{AbstractSyntaxTree, NumberFormat, PrettyOptions};
+use crate::utils::StringBuffer;
+
+pub const DEFAULT_TAB_WIDTH: usize = 4;
+
+/// Size of the parsed tree, above which the metrics warn that
+/// the expression may be unreasonably nested. Zero means no limit.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ComplexityLimits {
+    pub max_height: usize,
+    pub max_nodes: usize,
+}
+
+impl Default for ComplexityLimits {
+    fn default() -> Self {
+        Self {
+            max_height: 32,
+            max_nodes: 256,
+        }
+    }
+}
+
+/// When the syntax report repeats the code above the errors.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum SourceEcho {
+    /// Only in the pretty output, where the errors are underlined.
+    #[default]
+    Pretty,
+    /// Also in the plain output, so the report is self-contained, e.g. in logs.
+    Always,
+    Never,
+}
+
+impl SourceEcho {
+    pub fn is_shown(&self, pretty_output: bool) -> bool {
+        match self {
+            SourceEcho::Pretty => pretty_output,
+            SourceEcho::Always => true,
+            SourceEcho::Never => false,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct Reporter {
+    pub number_format: NumberFormat,
+    // Columns of a tab stop, used to line up the error carets
+    pub tab_width: usize,
+    pub complexity_limits: ComplexityLimits,
+    pub source_echo: SourceEcho,
+}
+
+impl Default for Reporter {
+    fn default() -> Self {
+        Self::new(NumberFormat::default())
+    }
+}
+
+impl Reporter {
+    pub fn new(number_format: NumberFormat) -> Self {
+        Self {
+            number_format,
+            tab_width: DEFAULT_TAB_WIDTH,
+            complexity_limits: ComplexityLimits::default(),
+            source_echo: SourceEcho::default(),
+        }
+    }
+
+    pub fn with_tab_width(mut self, tab_width: usize) -> Self {
+        self.tab_width = tab_width;
+        self
+    }
+
+    pub fn with_complexity_limits(mut self, complexity_limits: ComplexityLimits) -> Self {
+        self.complexity_limits = complexity_limits;
+        self
+    }
+
+    pub fn with_source_echo(mut self, source_echo: SourceEcho) -> Self {
+        self.source_echo = source_echo;
+        self
+    }
+}
+
+impl Reporter {
+    pub fn pipeline_diff(&self, stages: &[(&str, AbstractSyntaxTree)]) -> String {
+        let mut buffer = StringBuffer::default();
+
+        let options = PrettyOptions {
+            number_format: self.number_format,
+            ..PrettyOptions::default()
+        };
+
+        buffer.add_line("Optimization stages:\n".to_string());
+        for (label, tree) in stages {
+            buffer.add_line(format!(
+                "{:13} {}",
+                format!("{}:", label),
+                tree.to_pretty_string_with(&options)
+            ));
+        }
+
+        buffer.get()
+    }
+
+    pub fn latex(&self, latex: &str) -> String {
+        let mut buffer = StringBuffer::default();
+
+        buffer.add_line("LaTeX (copied to clipboard):\n".to_string());
+        buffer.add_line(latex.to_string());
+
+        buffer.get()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::compiler::ast::tree::NumberFormat;
+    use crate::compiler::context::CompilerContext;
+    use crate::config::Config;
+
+    #[test]
+    fn test_pipeline_diff() {
+        let mut context = CompilerContext::new(&Config::default());
+        context.code = "a+a+a".to_string();
+        let report = context.pipeline_diff_report();
+
+        let labels = [
+            "Parsed:",
+            "Computed #1:",
+            "Transformed:",
+            "Computed #2:",
+            "Balanced:",
+            "Computed #3:",
+            "Folded:",
+            "Computed #4:",
+        ];
+        let positions: Vec<usize> = labels
+            .iter()
+            .map(|label| {
+                let Some(position) = report.find(label) else {
+                    panic!("No \"{}\" in report:\n{}", label, report)
+                };
+                position
+            })
+            .collect();
+        assert!(positions.is_sorted(), "Report:\n{}", report);
+        assert!(report.starts_with("Optimization stages:\n\nParsed:       a + a + a\n"));
+    }
+
+    #[test]
+    fn test_pipeline_diff_finalized() {
+        let mut context = CompilerContext::new(&Config::default());
+
+        // Fully computed, nothing to transform
+        context.code = "2 * 3".to_string();
+        assert_eq!(
+            context.pipeline_diff_report(),
+            "Optimization stages:\n\nParsed:       2.000 * 3.000\nComputed #1:  6.000\n"
+        );
+
+        context.code = "a + (".to_string();
+        assert!(
+            !context
+                .pipeline_diff_report()
+                .starts_with("Optimization stages:")
+        );
+    }
+
+    #[test]
+    fn test_equivalent_forms_report() {
+        let mut context = CompilerContext::new(&Config::default());
+        context.code = "a+(b)".to_string();
+        let report = context.equivalent_forms_report();
+        assert!(report.starts_with(concat!(
+            "Canonical form:       (a + b)\n",
+            "Minimal parentheses:  a + b\n",
+            "Full parentheses:     (a + b)\n",
+            "Flat chains:          a + b\n",
+            "Value:                not a constant\n",
+            "\n",
+            "Found ",
+        )));
+
+        context.code = "2+3".to_string();
+        assert_eq!(
+            context.equivalent_forms_report(),
+            "Value:                5.000\n"
+        );
+
+        // Every form is printed in the configured number format
+        context.number_format = NumberFormat::Fixed(1);
+        context.code = "a*(b+0.25)".to_string();
+        let report = context.equivalent_forms_report();
+        assert!(
+            report.starts_with(concat!(
+                "Canonical form:       ((0.3 + b) * a)\n",
+                "Minimal parentheses:  a * (b + 0.3)\n",
+                "Full parentheses:     (a * (b + 0.3))\n",
+                "Flat chains:          a * (b + 0.3)\n",
+            )),
+            "Report:\n{}",
+            report
+        );
+    }
+
+    #[test]
+    fn test_equivalence() {
+        let mut context = CompilerContext::new(&Config::default());
+        context.code = "x*2 + 2*3".to_string();
+        context.compared_code = "6 + 2*x".to_string();
+        assert_eq!(
+            context.equivalence_report(),
+            "Expressions are equivalent!\n\n1) x * 2.000 + 2.000 * 3.000\n2) 6.000 + 2.000 * x\n"
+        );
+
+        context.compared_code = "a-b".to_string();
+        assert!(
+            context
+                .equivalence_report()
+                .starts_with("Expressions are not equivalent.")
+        );
+
+        context.compared_code = "a + (".to_string();
+        assert!(
+            context
+                .equivalence_report()
+                .starts_with("Second expression:")
+        );
+    }
+
+    #[test]
+    fn test_latex() {
+        let mut context = CompilerContext::new(&Config::default());
+        context.code = "-a / (b + 2)".to_string();
+        assert_eq!(context.latex(), Ok("\\frac{-a}{b + 2.000}".to_string()));
+        assert_eq!(
+            context.latex_report(),
+            "LaTeX (copied to clipboard):\n\n\\frac{-a}{b + 2.000}\n"
+        );
+
+        context.code = "a + (".to_string();
+        assert!(context.latex().is_err());
+    }
+
+    #[test]
+    fn test_program_reports() {
+        let mut context = CompilerContext::new(&Config::default());
+        context.code = "a+b; c*d".to_string();
+        let report = context.compute_1_report();
+        let Some((first, second)) = report.split_once("Expression #2:\n") else {
+            panic!("No second expression in:\n{}", report)
+        };
+        assert!(first.starts_with("Expression #1:\n"), "Report:\n{}", report);
+        assert!(first.contains("succeed!\n\n└── +\n"), "Report:\n{}", report);
+        assert!(
+            second.contains("succeed!\n\n└── *\n"),
+            "Report:\n{}",
+            report
+        );
+        assert_eq!(context.latex(), Ok("a + b;\\quad c \\cdot d".to_string()));
+        assert!(context.run_summary().is_ast_built);
+
+        // Misplaced `;` is a syntax error of the whole code
+        context.code = "(a; b)".to_string();
+        assert_eq!(context.compute_1_report(), context.syntax_report());
+    }
+}
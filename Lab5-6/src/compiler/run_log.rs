@@ -0,0 +1,213 @@
+use crate::compiler::syntax::SyntaxError;
+use log::Level;
+
+/// Outcome of an analysis run, written to the log after every run.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct RunSummary {
+    pub input_length: usize,
+    pub tokens: usize,
+    pub syntax_errors: usize,
+    pub is_ast_built: bool,
+}
+
+impl RunSummary {
+    /// Warning for syntax errors, error when the tree is not built anyway.
+    pub fn level(&self) -> Level {
+        match (self.syntax_errors, self.is_ast_built) {
+            (0, true) => Level::Info,
+            (0, false) => Level::Error,
+            _ => Level::Warn,
+        }
+    }
+
+    pub fn log(&self) {
+        self.log_to(log::logger());
+    }
+
+    /// The logger filters by the configured level itself.
+    pub fn log_to(&self, logger: &dyn log::Log) {
+        let metadata = log::Metadata::builder()
+            .level(self.level())
+            .target(module_path!())
+            .build();
+        if !logger.enabled(&metadata) {
+            return;
+        }
+
+        logger.log(
+            &log::Record::builder()
+                .metadata(metadata)
+                .args(format_args!(
+                    "Analysis run: input_length={}, tokens={}, syntax_errors={}, ast_built={}",
+                    self.input_length, self.tokens, self.syntax_errors, self.is_ast_built
+                ))
+                .module_path(Some(module_path!()))
+                .file(Some(file!()))
+                .line(Some(line!()))
+                .build(),
+        );
+    }
+}
+
+/// Stages of a run as they happen, so the summary does not run them again.
+/// The syntax is the one analyzed first: with several `;`-separated expressions,
+/// the whole code is checked before them. Trees of all of them have to be built.
+#[derive(Debug, Default)]
+pub struct RunRecorder {
+    summary: Option<RunSummary>,
+    // Errors, not warnings, stop the run before the tree is built
+    has_syntax_errors: bool,
+    is_tree_recorded: bool,
+}
+
+impl RunRecorder {
+    pub fn record_syntax(
+        &mut self, input_length: usize, tokens: usize, syntax_errors: &[SyntaxError],
+    ) {
+        if self.summary.is_some() {
+            return;
+        }
+        self.summary = Some(RunSummary {
+            input_length,
+            tokens,
+            syntax_errors: syntax_errors.len(),
+            is_ast_built: false,
+        });
+        self.has_syntax_errors = SyntaxError::has_errors(syntax_errors);
+    }
+
+    pub fn record_tree(&mut self, is_built: bool) {
+        let Some(summary) = &mut self.summary else {
+            return;
+        };
+        summary.is_ast_built =
+            is_built && (summary.is_ast_built || !self.is_tree_recorded);
+        self.is_tree_recorded = true;
+    }
+
+    pub fn is_syntax_recorded(&self) -> bool {
+        self.summary.is_some()
+    }
+
+    /// The run has not come to the tree, though the syntax lets it be built.
+    pub fn is_tree_missing(&self) -> bool {
+        !self.is_tree_recorded && !self.has_syntax_errors
+    }
+
+    pub fn summary(&self) -> RunSummary {
+        self.summary.clone().unwrap_or_default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::compiler::context::CompilerContext;
+    use crate::config::Config;
+    use log::LevelFilter;
+    use std::sync::Mutex;
+
+    struct TestLogger {
+        level: LevelFilter,
+        records: Mutex<Vec<(Level, String)>>,
+    }
+
+    impl log::Log for TestLogger {
+        fn enabled(&self, metadata: &log::Metadata) -> bool {
+            metadata.level() <= self.level
+        }
+
+        fn log(&self, record: &log::Record) {
+            if let Ok(mut records) = self.records.lock() {
+                records.push((record.level(), record.args().to_string()));
+            }
+        }
+
+        fn flush(&self) {}
+    }
+
+    #[test]
+    fn test_log_run() {
+        let mut context = CompilerContext::new(&Config::default());
+        context.code = "a + * b)".to_string();
+        let summary = context.run_summary();
+        assert_eq!(
+            summary,
+            RunSummary {
+                input_length: 8,
+                tokens: 5,
+                syntax_errors: 2,
+                is_ast_built: false,
+            }
+        );
+
+        let logger = TestLogger {
+            level: LevelFilter::Warn,
+            records: Mutex::new(Vec::new()),
+        };
+        summary.log_to(&logger);
+
+        context.code = "a + b".to_string();
+        // Info is below the level of the logger
+        context.run_summary().log_to(&logger);
+
+        let Ok(records) = logger.records.lock() else {
+            panic!("Failed to lock records")
+        };
+        assert_eq!(
+            *records,
+            vec![(
+                Level::Warn,
+                "Analysis run: input_length=8, tokens=5, syntax_errors=2, ast_built=false"
+                    .to_string()
+            )]
+        );
+    }
+
+    #[test]
+    fn test_log_recorded_run() {
+        let logger = TestLogger {
+            level: LevelFilter::Info,
+            records: Mutex::new(Vec::new()),
+        };
+        let mut context = CompilerContext::new(&Config::default());
+
+        // The whole program is checked, and the tree of every expression is built
+        context.code = "a + b; c".to_string();
+        let output = context.logged_to(&logger, CompilerContext::ast_report);
+        assert_eq!(output, context.ast_report());
+
+        // Syntax errors stop the run before the tree
+        context.code = "a + * b)".to_string();
+        let output = context.logged_to(&logger, CompilerContext::compute_1_report);
+        assert_eq!(output, context.syntax_report());
+
+        // Tokens only, the rest is run for the summary
+        context.code = "(a".to_string();
+        context.logged_to(&logger, CompilerContext::tokenize_report);
+
+        let Ok(records) = logger.records.lock() else {
+            panic!("Failed to lock records")
+        };
+        assert_eq!(
+            *records,
+            vec![
+                (
+                    Level::Info,
+                    "Analysis run: input_length=8, tokens=5, syntax_errors=0, ast_built=true"
+                        .to_string()
+                ),
+                (
+                    Level::Warn,
+                    "Analysis run: input_length=8, tokens=5, syntax_errors=2, ast_built=false"
+                        .to_string()
+                ),
+                (
+                    Level::Warn,
+                    "Analysis run: input_length=2, tokens=2, syntax_errors=1, ast_built=false"
+                        .to_string()
+                ),
+            ]
+        );
+    }
+}
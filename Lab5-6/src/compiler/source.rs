@@ -0,0 +1,276 @@
+use crate::utils::StringExtension;
+use std::ops::Range;
+
+/// Built once from the input, translates char offsets of tokens
+/// into lines and columns and back into text.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SourceMap {
+    chars: Vec<char>,
+    // Offsets of the first chars of the lines
+    line_starts: Vec<usize>,
+}
+
+impl SourceMap {
+    pub fn new(source: &str) -> Self {
+        let chars: Vec<char> = source.chars().collect();
+        let line_starts = std::iter::once(0)
+            .chain(
+                chars
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, ch)| **ch == '\n')
+                    .map(|(index, _)| index + 1),
+            )
+            .collect();
+
+        Self { chars, line_starts }
+    }
+
+    /// Length in chars.
+    pub fn len(&self) -> usize {
+        self.chars.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.chars.is_empty()
+    }
+
+    /// Offsets past the end point to the end, e.g. of the code shortened since.
+    pub fn clamp(&self, offset: usize) -> usize {
+        offset.min(self.len())
+    }
+
+    /// Line and column of the offset, both starting from 1 as in `Location`.
+    pub fn line_col(&self, offset: usize) -> (usize, usize) {
+        let offset = self.clamp(offset);
+        // The first line starts at 0, so there is always a line before the offset
+        let line = self.line_starts.partition_point(|start| *start <= offset);
+        let start = self.line_starts.get(line - 1).copied().unwrap_or_default();
+
+        (line, offset - start + 1)
+    }
+
+    /// A trailing line break starts an empty line.
+    pub fn line_count(&self) -> usize {
+        self.line_starts.len()
+    }
+
+    /// Char range of the line starting from 1, without the line break.
+    pub fn line_range(&self, line: usize) -> Option<Range<usize>> {
+        let start = *self.line_starts.get(line.checked_sub(1)?)?;
+        let end = self
+            .line_starts
+            .get(line)
+            .map(|next| next - 1)
+            .unwrap_or(self.len());
+
+        Some(start..end)
+    }
+
+    /// Text of the line starting from 1, without the line break.
+    pub fn line_text(&self, line: usize) -> Option<String> {
+        Some(self.chars[self.line_range(line)?].iter().collect())
+    }
+
+    /// Text of the char range, cut to the source.
+    pub fn snippet(&self, range: Range<usize>) -> String {
+        let end = self.clamp(range.end);
+        let start = range.start.min(end);
+
+        self.chars[start..end].iter().collect()
+    }
+
+    /// Display column of every char and of the end. A tab takes up to `tab_width`
+    /// columns, to the next tab stop.
+    pub fn display_columns(&self, tab_width: usize) -> Vec<usize> {
+        let tab_width = tab_width.max(1);
+
+        let mut columns = Vec::with_capacity(self.len() + 1);
+        let mut column = 0;
+        for ch in &self.chars {
+            columns.push(column);
+            column = match ch {
+                '\t' => column + tab_width - column % tab_width,
+                _ => column + 1,
+            };
+        }
+        columns.push(column);
+
+        columns
+    }
+
+    /// Line of carets under the ranges, to be printed below the source:
+    /// `^` under a single char, `^---^` under a longer range.
+    pub fn underline(&self, ranges: &[Range<usize>], tab_width: usize) -> String {
+        let columns = self.display_columns(tab_width);
+        let column = |index: usize| columns[self.clamp(index)];
+
+        let mut line = " ".repeat(columns[self.len()]);
+        for range in ranges {
+            let start = column(range.start);
+            let end = column(range.end);
+            if end - start <= 1 {
+                line.replace_char(start, '^');
+            } else {
+                for index in (start + 1)..(end - 1) {
+                    line.replace_char(index, '-');
+                }
+
+                line.replace_char(start, '^');
+                line.replace_char(end - 1, '^');
+            }
+        }
+
+        line
+    }
+
+    /// Every line the ranges are on, with the carets of `underline` under it,
+    /// so code of several lines is not underlined as one.
+    /// A range at the line break is on its line, as the end of the line.
+    pub fn underlined_lines(
+        &self, ranges: &[Range<usize>], tab_width: usize,
+    ) -> Vec<(String, String)> {
+        (1..=self.line_count())
+            .filter_map(|line| {
+                let line_range = self.line_range(line)?;
+                let on_line: Vec<Range<usize>> = ranges
+                    .iter()
+                    .filter(|range| {
+                        range.start <= line_range.end
+                            && range.end.max(range.start + 1) > line_range.start
+                    })
+                    .map(|range| {
+                        range.start.max(line_range.start) - line_range.start
+                            ..range.end.min(line_range.end + 1) - line_range.start
+                    })
+                    .collect();
+                if on_line.is_empty() {
+                    return None;
+                }
+
+                let text = self.line_text(line)?;
+                let underline = SourceMap::new(&text).underline(&on_line, tab_width);
+                Some((text, underline))
+            })
+            .collect()
+    }
+
+    /// Range widened over the parentheses around it: `(a - a)` for `a - a`.
+    /// Spans of the tree nodes do not include them.
+    pub fn parenthesized(&self, range: Range<usize>) -> Range<usize> {
+        let is_space = |index: &usize| self.chars[*index].is_whitespace();
+        let (mut start, mut end) = (self.clamp(range.start), self.clamp(range.end));
+
+        loop {
+            let before = (0..start).rev().find(|index| !is_space(index));
+            let after = (end..self.len()).find(|index| !is_space(index));
+            match (before, after) {
+                (Some(before), Some(after))
+                    if self.chars[before] == '(' && self.chars[after] == ')' =>
+                {
+                    start = before;
+                    end = after + 1;
+                },
+                _ => return start..end,
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_line_col() {
+        let source = SourceMap::new("a + b\nä * c\n\nd");
+        assert_eq!(source.len(), 14);
+
+        assert_eq!(source.line_col(0), (1, 1));
+        assert_eq!(source.line_col(4), (1, 5));
+        // Line break is the last column of its line
+        assert_eq!(source.line_col(5), (1, 6));
+        assert_eq!(source.line_col(6), (2, 1));
+        assert_eq!(source.line_col(8), (2, 3));
+        assert_eq!(source.line_col(12), (3, 1));
+        // Last char and the end
+        assert_eq!(source.line_col(13), (4, 1));
+        assert_eq!(source.line_col(14), (4, 2));
+        assert_eq!(source.line_col(100), (4, 2));
+
+        assert_eq!(SourceMap::new("").line_col(0), (1, 1));
+    }
+
+    #[test]
+    fn test_line_text() {
+        let source = SourceMap::new("a + b\nä * c\n\nd");
+
+        assert_eq!(source.line_text(1), Some("a + b".to_string()));
+        assert_eq!(source.line_text(2), Some("ä * c".to_string()));
+        assert_eq!(source.line_text(3), Some(String::new()));
+        assert_eq!(source.line_text(4), Some("d".to_string()));
+        assert_eq!(source.line_text(0), None);
+        assert_eq!(source.line_text(5), None);
+    }
+
+    #[test]
+    fn test_snippet() {
+        let source = SourceMap::new("a + b\nä * c\n\nd");
+
+        assert_eq!(source.snippet(6..11), "ä * c");
+        assert_eq!(source.snippet(4..7), "b\nä");
+        assert_eq!(source.snippet(13..14), "d");
+        assert_eq!(source.snippet(13..100), "d");
+        assert_eq!(source.snippet(50..100), "");
+    }
+
+    #[test]
+    fn test_underline() {
+        let source = SourceMap::new("a\t+ bcd");
+        assert_eq!(source.underline(&[0..1, 4..7], 4), "^     ^-^");
+        let plus = 2..3;
+        assert_eq!(source.underline(&[plus], 2), "  ^    ");
+        assert_eq!(source.underline(&[], 4), " ".repeat(9));
+    }
+
+    #[test]
+    fn test_underlined_lines() {
+        let source = SourceMap::new("a +\n\tb / c\n\nd");
+        assert_eq!(source.line_count(), 4);
+        assert_eq!(source.line_range(2), Some(4..10));
+
+        assert_eq!(
+            source.underlined_lines(&[2..3, 7..8], 4),
+            vec![
+                ("a +".to_string(), "  ^".to_string()),
+                ("\tb / c".to_string(), "      ^  ".to_string()),
+            ]
+        );
+        // Over the line break
+        assert_eq!(
+            source.underlined_lines(&[2..5, 9..10], 2),
+            vec![
+                ("a +".to_string(), "  ^".to_string()),
+                ("\tb / c".to_string(), "^^    ^".to_string()),
+            ]
+        );
+        assert_eq!(source.underlined_lines(&[], 4), Vec::new());
+    }
+
+    #[test]
+    fn test_parenthesized() {
+        let source = SourceMap::new("1/( (a - a) ) + (b)");
+        assert_eq!(source.parenthesized(5..10), 2..13);
+        assert_eq!(source.parenthesized(17..18), 16..19);
+        // Not parenthesized on both sides
+        assert_eq!(source.parenthesized(0..1), 0..1);
+        assert_eq!(source.parenthesized(2..13), 2..13);
+    }
+
+    #[test]
+    fn test_display_columns() {
+        let source = SourceMap::new("a\tb\t\tc");
+        assert_eq!(source.display_columns(4), vec![0, 1, 4, 5, 8, 12, 13]);
+        assert_eq!(source.display_columns(0), vec![0, 1, 2, 3, 4, 5, 6]);
+    }
+}
@@ -1,7 +1,10 @@
 use crate::compiler::reports::Reporter;
+use crate::compiler::source::SourceMap;
 use crate::compiler::tokenizer::{Token, TokenType};
 use crate::utils::{StringBuffer, StringExtension};
+use common::json::{json_array, json_object, json_string, syntax_error_fields};
 use std::collections::VecDeque;
+use std::ops::Range;
 
 #[derive(Debug)]
 pub struct SyntaxAnalyzer {
@@ -10,16 +13,107 @@ pub struct SyntaxAnalyzer {
 
     status: Status,
     errors: Vec<SyntaxError>,
-
-    brackets_stack: VecDeque<Token>,
-    parentheses_stack: VecDeque<Token>,
-    quotation_marks_stack: VecDeque<Token>,
+    // Errors after this count are replaced with a single `TooManyErrors`
+    max_errors: Option<usize>,
+
+    // Indexes of opening brackets and parentheses, to check the kind of closing ones
+    delimiters_stack: VecDeque<usize>,
+    // Indexes of `?` still waiting for their `:`
+    ternary_stack: VecDeque<usize>,
+    // `Status::last_operator` outside of every open delimiter
+    outer_operators: VecDeque<Option<OperatorCategory>>,
+    // Index of the opening parenthesis of the last closed group
+    last_closed: Option<usize>,
 }
 
+const BINARY_OPERATORS: [TokenType; 12] = [
+    TokenType::Plus,
+    TokenType::Minus,
+    TokenType::Asterisk,
+    TokenType::Slash,
+    TokenType::Percent,
+    TokenType::Ampersand,
+    TokenType::Pipe,
+    TokenType::Question,
+    TokenType::Colon,
+    TokenType::Less,
+    TokenType::Greater,
+    TokenType::Equal,
+];
+
 #[derive(Debug, PartialEq, Eq)]
 pub struct SyntaxError {
     pub token: Token,
     pub kind: SyntaxErrorKind,
+    pub severity: Severity,
+}
+
+impl SyntaxError {
+    pub fn is_warning(&self) -> bool {
+        self.severity == Severity::Warning
+    }
+
+    /// Text of the error, warnings are marked.
+    pub fn message(&self) -> String {
+        match self.severity {
+            Severity::Error => self.to_string(),
+            Severity::Warning => format!("{}: {}", self.severity.label(), self),
+        }
+    }
+
+    /// Warnings don't stop the compilation.
+    pub fn has_errors(errors: &[SyntaxError]) -> bool {
+        errors.iter().any(|error| !error.is_warning())
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Severity {
+    Error,
+    Warning,
+}
+
+impl Severity {
+    pub fn label(&self) -> &'static str {
+        match self {
+            Severity::Error => "Error",
+            Severity::Warning => "Warning",
+        }
+    }
+}
+
+impl std::fmt::Display for Severity {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            Severity::Error => "error",
+            Severity::Warning => "warning",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+/// Errors are ordered by the start of the token, then by its end, then by the kind,
+/// so the report does not depend on the order of the checks.
+/// The location of the token is left out, as it is in the `Token` equality:
+/// it is derived from the position.
+impl Ord for SyntaxError {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        self.token
+            .position
+            .start
+            .cmp(&other.token.position.start)
+            .then(self.token.position.end.cmp(&other.token.position.end))
+            .then(self.kind.ordinal().cmp(&other.kind.ordinal()))
+            .then_with(|| self.token.kind.cmp(&other.token.kind))
+            .then_with(|| self.token.value.cmp(&other.token.value))
+            .then_with(|| self.is_warning().cmp(&other.is_warning()))
+    }
+}
+
+impl PartialOrd for SyntaxError {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
 }
 
 macro_rules! syntax_error {
@@ -27,20 +121,30 @@ macro_rules! syntax_error {
         SyntaxError {
             token: $token.clone(),
             kind: SyntaxErrorKind::$kind,
+            severity: SyntaxErrorKind::$kind.severity(),
         }
     };
 }
 
 #[derive(Debug, PartialEq, Eq)]
 pub enum SyntaxErrorKind {
+    AssignmentNotSupported,
+    ChainedComparison,
     EmptyBrackets,
+    EmptyExpression,
     EmptyParentheses,
+    ImplicitMultiplication,
     InvalidBinaryLiteral,
     InvalidFloat,
     InvalidFunctionName,
     InvalidHexLiteral,
     InvalidVariableName,
+    MismatchedDelimiter,
     MissingArgument,
+    RedundantParentheses,
+    TooManyErrors,
+    ConsecutiveOperators,
+    UnexpectedBackslash,
     UnexpectedBrackets,
     UnexpectedComma,
     UnexpectedDot,
@@ -49,17 +153,84 @@ pub enum SyntaxErrorKind {
     UnexpectedOperand,
     UnexpectedOperator,
     UnexpectedParenthesis,
+    UnexpectedPercent,
+    UnexpectedSemicolon,
     UnknownToken,
     UnmatchedBrackets,
+    UnmatchedColon,
     UnmatchedParenthesis,
+    UnmatchedQuestionMark,
     UnmatchedQuotationMark,
 }
 
+impl SyntaxErrorKind {
+    /// Last tie-break of the `SyntaxError` ordering. The ranks are the same
+    /// in every lab, so the errors of one code are reported in the same order.
+    fn ordinal(&self) -> u8 {
+        match self {
+            SyntaxErrorKind::AssignmentNotSupported => 0,
+            SyntaxErrorKind::ChainedComparison => 1,
+            SyntaxErrorKind::ConsecutiveOperators => 2,
+            SyntaxErrorKind::EmptyBrackets => 3,
+            SyntaxErrorKind::EmptyExpression => 4,
+            SyntaxErrorKind::EmptyParentheses => 5,
+            SyntaxErrorKind::InvalidBinaryLiteral => 6,
+            SyntaxErrorKind::InvalidFloat => 7,
+            SyntaxErrorKind::InvalidFunctionName => 8,
+            SyntaxErrorKind::InvalidHexLiteral => 9,
+            SyntaxErrorKind::InvalidVariableName => 10,
+            SyntaxErrorKind::MismatchedDelimiter => 11,
+            SyntaxErrorKind::MissingArgument => 12,
+            SyntaxErrorKind::RedundantParentheses => 13,
+            SyntaxErrorKind::TooManyErrors => 14,
+            SyntaxErrorKind::UnexpectedBackslash => 15,
+            SyntaxErrorKind::UnexpectedBrackets => 16,
+            SyntaxErrorKind::UnexpectedComma => 17,
+            SyntaxErrorKind::UnexpectedDot => 18,
+            SyntaxErrorKind::UnexpectedEndOfExpression => 19,
+            SyntaxErrorKind::UnexpectedNewLine => 20,
+            SyntaxErrorKind::UnexpectedOperand => 21,
+            SyntaxErrorKind::UnexpectedOperator => 22,
+            SyntaxErrorKind::UnexpectedParenthesis => 23,
+            SyntaxErrorKind::UnexpectedPercent => 24,
+            SyntaxErrorKind::UnexpectedSemicolon => 25,
+            SyntaxErrorKind::UnknownToken => 26,
+            SyntaxErrorKind::UnmatchedBrackets => 27,
+            SyntaxErrorKind::UnmatchedColon => 28,
+            SyntaxErrorKind::UnmatchedParenthesis => 29,
+            SyntaxErrorKind::UnmatchedQuestionMark => 30,
+            SyntaxErrorKind::UnmatchedQuotationMark => 31,
+            SyntaxErrorKind::ImplicitMultiplication => 32,
+        }
+    }
+
+    /// Code with a warning is still compiled, its meaning is only doubtful.
+    /// Comments are not a part of the language, so none are ignored to warn about.
+    pub fn severity(&self) -> Severity {
+        match self {
+            SyntaxErrorKind::ChainedComparison
+            | SyntaxErrorKind::ImplicitMultiplication
+            | SyntaxErrorKind::RedundantParentheses => Severity::Warning,
+            _ => Severity::Error,
+        }
+    }
+}
+
 impl std::fmt::Display for SyntaxError {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         let text = match self.kind {
+            SyntaxErrorKind::AssignmentNotSupported => {
+                "Assignment is not supported. Did you mean '=='?"
+            },
+            SyntaxErrorKind::ChainedComparison => {
+                "Chained comparison: `a < b < c` compares the result of `a < b` with c."
+            },
             SyntaxErrorKind::EmptyBrackets => "Empty array access.",
+            SyntaxErrorKind::EmptyExpression => "Empty expression.",
             SyntaxErrorKind::EmptyParentheses => "Empty function or grouping.",
+            SyntaxErrorKind::ImplicitMultiplication => {
+                "Multiplication is assumed between the operands."
+            },
             SyntaxErrorKind::InvalidBinaryLiteral => match &self.token.value {
                 None => "Invalid binary literal.",
                 Some(value) => &format!("Invalid binary literal '0{}'.", value),
@@ -74,7 +245,16 @@ impl std::fmt::Display for SyntaxError {
                 Some(value) => &format!("Invalid hexadecimal literal '0{}'.", value),
             },
             SyntaxErrorKind::InvalidVariableName => "Invalid variable name.",
+            SyntaxErrorKind::MismatchedDelimiter => {
+                "Closing delimiter does not match the opening one."
+            },
             SyntaxErrorKind::MissingArgument => "Missing function argument.",
+            SyntaxErrorKind::RedundantParentheses => "Redundant parentheses.",
+            SyntaxErrorKind::TooManyErrors => "Too many errors, the rest are skipped.",
+            SyntaxErrorKind::ConsecutiveOperators => "Two operators in a row.",
+            SyntaxErrorKind::UnexpectedBackslash => {
+                "Unexpected backslash. It is only valid inside strings."
+            },
             SyntaxErrorKind::UnexpectedBrackets => "Unexpected brackets.",
             SyntaxErrorKind::UnexpectedComma => "Unexpected comma.",
             SyntaxErrorKind::UnexpectedDot => "Unexpected dot.",
@@ -86,9 +266,15 @@ impl std::fmt::Display for SyntaxError {
             },
             SyntaxErrorKind::UnexpectedOperator => "Unexpected operator.",
             SyntaxErrorKind::UnexpectedParenthesis => "Unexpected parenthesis.",
+            SyntaxErrorKind::UnexpectedPercent => {
+                "Unexpected '%'. It is the remainder of two operands, not a unary operator."
+            },
+            SyntaxErrorKind::UnexpectedSemicolon => "Unexpected semicolon.",
             SyntaxErrorKind::UnknownToken => "Unknown token.",
             SyntaxErrorKind::UnmatchedBrackets => "Unmatched brackets.",
+            SyntaxErrorKind::UnmatchedColon => "':' without '?' before it.",
             SyntaxErrorKind::UnmatchedParenthesis => "Unmatched parenthesis.",
+            SyntaxErrorKind::UnmatchedQuestionMark => "'?' without ':' after it.",
             SyntaxErrorKind::UnmatchedQuotationMark => "Unmatched quotation mark.",
         };
 
@@ -96,11 +282,21 @@ impl std::fmt::Display for SyntaxError {
     }
 }
 
+impl std::error::Error for SyntaxError {}
+
 #[derive(Debug, Default)]
 pub struct Status {
     pub expect_operand: bool,
     pub expect_operator: bool,
-    pub in_string: bool,
+    // Arithmetic operators bind tighter than comparisons, so they don't change it
+    pub last_operator: Option<OperatorCategory>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OperatorCategory {
+    Comparison,
+    // `&`, `|`, `?` and `:`, which break a chain of comparisons
+    Logical,
 }
 
 impl SyntaxAnalyzer {
@@ -111,59 +307,83 @@ impl SyntaxAnalyzer {
 
             errors: Vec::new(),
             status: Status::default(),
+            max_errors: None,
 
-            brackets_stack: VecDeque::new(),
-            parentheses_stack: VecDeque::new(),
-            quotation_marks_stack: VecDeque::new(),
+            delimiters_stack: VecDeque::new(),
+            ternary_stack: VecDeque::new(),
+            outer_operators: VecDeque::new(),
+            last_closed: None,
         }
     }
 
+    /// Unlimited by default.
+    pub fn with_max_errors(mut self, max_errors: usize) -> Self {
+        self.max_errors = Some(max_errors);
+        self
+    }
+
     pub fn analyze(mut self) -> Vec<SyntaxError> {
         self.status = Status {
             expect_operand: true,
             expect_operator: false,
-            in_string: false,
+            last_operator: None,
         };
 
+        // Nothing but whitespace, so there is nothing to analyze
+        if self.tokens.iter().all(|token| {
+            matches!(
+                token.kind,
+                TokenType::Space | TokenType::Tab | TokenType::NewLine
+            )
+        }) {
+            return vec![SyntaxError {
+                token: Token {
+                    kind: TokenType::Space,
+                    position: 0..1,
+                    value: None,
+                    location: None,
+                },
+                kind: SyntaxErrorKind::EmptyExpression,
+                severity: Severity::Error,
+            }];
+        }
+
+        let mut is_truncated = false;
         while self.current_index < self.tokens.len() {
+            // One error past the limit is enough to report the marker
+            if self.max_errors.is_some_and(|max| self.error_count() > max) {
+                is_truncated = true;
+                break;
+            }
+
             let token = &self.tokens[self.current_index];
 
             match &token.kind {
                 TokenType::QuotationMark => {
-                    // Toggle string state.
-                    if !self.status.in_string {
-                        // Start mark. We're expecting an operand here.
-                        if !self.status.expect_operand {
-                            // If we didn't expect an operand, it's an error.
-                            self.errors.push(syntax_error!(UnexpectedOperator, token));
-                        }
-                        self.status.in_string = true;
-                        // While inside string we're considering that operand is not finished
-                        self.status.expect_operator = false;
-                    } else {
-                        // Closing mark
-                        self.status.in_string = false;
-                        // String literal is operand
-                        self.status.expect_operator = true;
-                    }
-
-                    if self.quotation_marks_stack.is_empty() {
-                        self.quotation_marks_stack.push_back(token.clone());
-                    } else {
-                        self.quotation_marks_stack.pop_back();
-                    }
-
-                    self.status.expect_operand = false;
+                    // Nothing closes it, so it doesn't open a string
+                    self.errors
+                        .push(syntax_error!(UnmatchedQuotationMark, token));
                     self.current_index += 1;
                     continue;
                 },
 
-                _ if self.status.in_string => {
+                TokenType::StringLiteral => {
+                    // String literal is operand
+                    if !self.status.expect_operand {
+                        self.errors.push(syntax_error!(UnexpectedOperator, token));
+                    }
+                    self.status.expect_operand = false;
+                    self.status.expect_operator = true;
                     self.current_index += 1;
                     continue;
                 },
 
                 TokenType::ExclamationMark => {
+                    if self.is_before_equal() {
+                        self.read_comparison(2);
+                        continue;
+                    }
+
                     // Used only like identifier part
                     if self.status.expect_operand {
                         self.status.expect_operand = true;
@@ -294,7 +514,15 @@ impl SyntaxAnalyzer {
                 | TokenType::Slash
                 | TokenType::Percent
                 | TokenType::Ampersand
-                | TokenType::Pipe => {
+                | TokenType::Pipe
+                | TokenType::Question
+                | TokenType::Colon => {
+                    // Synthetic `*` of `2x`, see `Tokenizer::insert_implicit_multiplication`
+                    if token.kind == TokenType::Asterisk && token.position.is_empty() {
+                        self.errors
+                            .push(syntax_error!(ImplicitMultiplication, token));
+                    }
+
                     // Unary operations
                     let unary = if [TokenType::Minus].contains(&token.kind)
                         && let Some(next) = self.peek_next()
@@ -313,10 +541,45 @@ impl SyntaxAnalyzer {
                     if self.status.expect_operator || unary {
                         self.status.expect_operand = true;
                         self.status.expect_operator = false;
+                    } else if token.kind == TokenType::Percent {
+                        // `%5` or `a * %b`: not a percent sign, nor a unary operator
+                        self.errors.push(syntax_error!(UnexpectedPercent, token));
+                    } else if let Some(previous) = self.peek_previous()
+                        && BINARY_OPERATORS.contains(&previous.kind)
+                    {
+                        self.errors.push(syntax_error!(ConsecutiveOperators, token));
+                        // Waiting for operand still
                     } else {
                         self.errors.push(syntax_error!(UnexpectedOperator, token));
                         // Waiting for operand still
                     }
+
+                    if [
+                        TokenType::Ampersand,
+                        TokenType::Pipe,
+                        TokenType::Question,
+                        TokenType::Colon,
+                    ]
+                    .contains(&token.kind)
+                    {
+                        self.status.last_operator = Some(OperatorCategory::Logical);
+                    }
+
+                    // `:` closes the last `?` of the same group, `(a ? b) : c` is an error
+                    if token.kind == TokenType::Question {
+                        self.ternary_stack.push_back(self.current_index);
+                    } else if token.kind == TokenType::Colon {
+                        let group = self.delimiters_stack.back().copied();
+                        match self.ternary_stack.back() {
+                            Some(question)
+                                if group.is_none_or(|start| *question > start) =>
+                            {
+                                self.ternary_stack.pop_back();
+                            },
+                            _ => self.errors.push(syntax_error!(UnmatchedColon, token)),
+                        }
+                    }
+
                     self.current_index += 1;
                     continue;
                 },
@@ -332,7 +595,7 @@ impl SyntaxAnalyzer {
                         continue;
                     }
 
-                    self.brackets_stack.push_back(token.clone());
+                    self.open_group();
                     self.status.expect_operand = true;
                     self.status.expect_operator = false;
                     self.current_index += 1;
@@ -340,17 +603,6 @@ impl SyntaxAnalyzer {
                 },
 
                 TokenType::RightBracket => {
-                    match self.brackets_stack.pop_back().is_some() {
-                        true => {
-                            // Correct
-                            self.status.expect_operand = false;
-                            self.status.expect_operator = true;
-                        },
-                        false => {
-                            self.errors.push(syntax_error!(UnmatchedBrackets, token))
-                        },
-                    }
-
                     // Empty array access check
                     if let Some(previous) = self.peek_previous()
                         && matches!(previous.kind, TokenType::LeftBracket)
@@ -358,6 +610,20 @@ impl SyntaxAnalyzer {
                         self.errors.push(syntax_error!(EmptyBrackets, token));
                     }
 
+                    match self.delimiters_stack.pop_back() {
+                        Some(opening) => {
+                            // `(a]`. Closing it anyway, to not report it twice
+                            if self.tokens[opening].kind != TokenType::LeftBracket {
+                                self.errors
+                                    .push(syntax_error!(MismatchedDelimiter, token));
+                            }
+                            self.status.expect_operand = false;
+                            self.status.expect_operator = true;
+                            self.close_group(opening);
+                        },
+                        None => self.errors.push(syntax_error!(UnmatchedBrackets, token)),
+                    }
+
                     self.current_index += 1;
                     continue;
                 },
@@ -392,7 +658,7 @@ impl SyntaxAnalyzer {
                             .push(syntax_error!(UnexpectedParenthesis, token));
                     }
 
-                    self.parentheses_stack.push_back(token.clone());
+                    self.open_group();
                     self.status.expect_operand = true;
                     self.status.expect_operator = false;
                     self.current_index += 1;
@@ -424,13 +690,38 @@ impl SyntaxAnalyzer {
                             .push(syntax_error!(UnexpectedParenthesis, token));
                     }
 
-                    match self.parentheses_stack.pop_back().is_some() {
-                        true => {
-                            // Correct
+                    match self.delimiters_stack.pop_back() {
+                        Some(opening) => {
+                            // `A[i)`. Closing it anyway, to not report it twice
+                            if self.tokens[opening].kind != TokenType::LeftParenthesis {
+                                self.errors
+                                    .push(syntax_error!(MismatchedDelimiter, token));
+                            }
+
+                            // `((a + b))`: the group is the only content of another one,
+                            // which is not a function call
+                            let is_call = opening.checked_sub(1).is_some_and(|i| {
+                                self.tokens[i].kind == TokenType::Identifier
+                            });
+                            if self.last_closed == Some(opening + 1)
+                                && self.peek_previous().is_some_and(|previous| {
+                                    previous.kind == TokenType::RightParenthesis
+                                })
+                                && !is_call
+                            {
+                                self.errors.push(syntax_error!(
+                                    RedundantParentheses,
+                                    self.tokens[opening]
+                                ));
+                            }
+
+                            self.last_closed = Some(opening);
                             self.status.expect_operand = false;
                             self.status.expect_operator = true;
+                            self.close_group(opening);
                         },
-                        false => {
+                        None => {
+                            self.last_closed = None;
                             self.errors.push(syntax_error!(UnmatchedParenthesis, token))
                         },
                     }
@@ -440,8 +731,13 @@ impl SyntaxAnalyzer {
                 },
 
                 TokenType::Comma => {
-                    // Allowed only inside parentheses (function)
-                    if self.parentheses_stack.is_empty() {
+                    // The argument ends here, and so does every ternary and comparison in it
+                    self.close_ternaries(self.delimiters_stack.back().copied());
+                    self.status.last_operator = None;
+                    let token = &self.tokens[self.current_index];
+
+                    // Allowed only inside parentheses (function) or brackets (array access)
+                    if self.delimiters_stack.is_empty() {
                         // Surely an error
                         self.errors.push(syntax_error!(UnexpectedComma, token));
                         self.status.expect_operand = true;
@@ -452,15 +748,36 @@ impl SyntaxAnalyzer {
 
                     // Inside parentheses comma need to be after operand and before new operand
                     if self.status.expect_operand {
-                        // Empty argument
-                        self.errors.push(syntax_error!(UnexpectedComma, token));
+                        match self.peek_previous() {
+                            // Empty argument in the middle: `f(a, , b)`
+                            Some(previous) if previous.kind == TokenType::Comma => {
+                                self.errors.push(syntax_error!(MissingArgument, token));
+                            },
+                            // Empty first argument: `f(, b)`
+                            _ => self.errors.push(syntax_error!(UnexpectedComma, token)),
+                        }
+
+                        // Empty arguments are reported, the closing delimiter is fine: `f(,)`
+                        if let Some(next) = self.peek_next()
+                            && matches!(
+                                next.kind,
+                                TokenType::RightParenthesis | TokenType::RightBracket
+                            )
+                        {
+                            self.status.expect_operand = false;
+                            self.status.expect_operator = true;
+                        }
+
                         self.current_index += 1;
                         continue;
                     }
 
                     // Argument is not present
                     if let Some(next) = self.peek_next()
-                        && matches!(next.kind, TokenType::RightParenthesis)
+                        && matches!(
+                            next.kind,
+                            TokenType::RightParenthesis | TokenType::RightBracket
+                        )
                     {
                         // Empty argument
                         self.errors.push(syntax_error!(MissingArgument, token));
@@ -475,17 +792,73 @@ impl SyntaxAnalyzer {
                     continue;
                 },
 
+                TokenType::Semicolon => {
+                    if !self.delimiters_stack.is_empty() {
+                        // Expressions cannot be separated inside of parentheses or brackets
+                        self.errors.push(syntax_error!(UnexpectedSemicolon, token));
+                    } else if self.status.expect_operand {
+                        // Previous expression is not finished (or is empty)
+                        self.errors
+                            .push(syntax_error!(UnexpectedEndOfExpression, token));
+                    }
+                    self.close_ternaries(self.delimiters_stack.back().copied());
+                    self.status.last_operator = None;
+
+                    // New expression starts with an operand
+                    self.status.expect_operand = true;
+                    self.status.expect_operator = false;
+                    self.current_index += 1;
+                    continue;
+                },
+
+                TokenType::Equal => {
+                    if self.is_before_equal() {
+                        self.read_comparison(2);
+                        continue;
+                    }
+
+                    if !self.status.expect_operator {
+                        // Not between operands, e.g. `a < = b`, so `==` would not help
+                        self.read_comparison(1);
+                        continue;
+                    }
+
+                    // Lone `=` between operands, where `==` is most likely meant.
+                    // Continuing, but considering that operator was read
+                    self.errors
+                        .push(syntax_error!(AssignmentNotSupported, token));
+                    self.status.expect_operand = true;
+                    self.status.expect_operator = false;
+                    self.current_index += 1;
+                    continue;
+                },
+
+                TokenType::Less | TokenType::Greater => {
+                    match self.is_before_equal() {
+                        true => self.read_comparison(2),
+                        false => self.read_comparison(1),
+                    }
+                    continue;
+                },
+
                 TokenType::Unknown => {
                     // Unknown — always an error
                     self.errors.push(syntax_error!(UnknownToken, token));
                     self.current_index += 1;
                     continue;
                 },
+                TokenType::Backslash => {
+                    // Outside of strings, e.g. a Windows path.
+                    // Continuing, but considering that operator was read
+                    self.errors.push(syntax_error!(UnexpectedBackslash, token));
+                    self.status.expect_operand = true;
+                    self.status.expect_operator = false;
+                    self.current_index += 1;
+                    continue;
+                },
                 TokenType::NewLine => {
-                    // Unexpected newline is error, if we're not in string
-                    if !self.status.in_string {
-                        self.errors.push(syntax_error!(UnexpectedNewLine, token));
-                    }
+                    // Newlines inside strings are a part of the literal
+                    self.errors.push(syntax_error!(UnexpectedNewLine, token));
                     self.current_index += 1;
                     continue;
                 },
@@ -497,8 +870,51 @@ impl SyntaxAnalyzer {
             }
         }
 
+        // The rest of the stream is not analyzed, so its delimiters and end are unknown
+        if !is_truncated {
+            self.report_unfinished();
+        }
+
+        self.errors.sort();
+
+        // Warnings are kept, only the errors past the limit are skipped
+        if let Some(max) = self.max_errors
+            && let Some(skipped) = self
+                .errors
+                .iter()
+                .filter(|error| !error.is_warning())
+                .nth(max)
+        {
+            let marker = syntax_error!(TooManyErrors, skipped.token);
+            let mut count = 0;
+            self.errors.retain(|error| {
+                count += usize::from(!error.is_warning());
+                error.is_warning() || count <= max
+            });
+            self.errors.push(marker);
+        }
+
+        self.errors
+    }
+
+    // Warnings don't count towards `max_errors`
+    fn error_count(&self) -> usize {
+        self.errors
+            .iter()
+            .filter(|error| !error.is_warning())
+            .count()
+    }
+
+    fn report_unfinished(&mut self) {
+        self.close_ternaries(None);
+
         // Error for every unmatched left parenthesis
-        for unmatched in self.parentheses_stack.into_iter() {
+        let unmatched_parentheses = self
+            .delimiters_stack
+            .iter()
+            .map(|index| &self.tokens[*index])
+            .filter(|token| token.kind == TokenType::LeftParenthesis);
+        for unmatched in unmatched_parentheses {
             self.errors
                 .push(syntax_error!(UnmatchedParenthesis, unmatched));
         }
@@ -510,19 +926,61 @@ impl SyntaxAnalyzer {
             self.errors
                 .push(syntax_error!(UnexpectedEndOfExpression, last));
         }
+    }
 
-        // Unclosed string
-        if let Some(token) = self.quotation_marks_stack.pop_back()
-            && self.status.in_string
+    /// Comparison of `length` tokens at the current index: `<`, `<=`, `==`, `!=`...
+    /// Two of them with only operands and arithmetic between are a chain.
+    fn read_comparison(&mut self, length: usize) {
+        let token = &self.tokens[self.current_index];
+
+        if self.status.expect_operator {
+            if self.status.last_operator == Some(OperatorCategory::Comparison) {
+                self.errors.push(syntax_error!(ChainedComparison, token));
+            }
+            self.status.expect_operand = true;
+            self.status.expect_operator = false;
+        } else if let Some(previous) = self.peek_previous()
+            && BINARY_OPERATORS.contains(&previous.kind)
         {
-            self.errors
-                .push(syntax_error!(UnmatchedQuotationMark, token));
+            self.errors.push(syntax_error!(ConsecutiveOperators, token));
+        } else {
+            self.errors.push(syntax_error!(UnexpectedOperator, token));
         }
 
-        self.errors
-            .sort_by(|a, b| a.token.position.start.cmp(&b.token.position.start));
+        self.status.last_operator = Some(OperatorCategory::Comparison);
+        self.current_index += length;
+    }
 
-        self.errors
+    // Glued `=` makes one operator with the current token: `<=`, `>=`, `==`, `!=`
+    fn is_before_equal(&self) -> bool {
+        let token = &self.tokens[self.current_index];
+        self.peek_next().is_some_and(|next| {
+            next.kind == TokenType::Equal && next.position.start == token.position.end
+        })
+    }
+
+    // A group is an operand for the comparisons around it
+    fn open_group(&mut self) {
+        self.delimiters_stack.push_back(self.current_index);
+        self.outer_operators
+            .push_back(self.status.last_operator.take());
+    }
+
+    fn close_group(&mut self, opening: usize) {
+        self.close_ternaries(Some(opening));
+        self.status.last_operator = self.outer_operators.pop_back().flatten();
+    }
+
+    /// Reports every `?` after the opening delimiter `start` (all of them, if None),
+    /// because its group or expression ended without `:`.
+    fn close_ternaries(&mut self, start: Option<usize>) {
+        while let Some(&question) = self.ternary_stack.back()
+            && start.is_none_or(|start| question > start)
+        {
+            self.ternary_stack.pop_back();
+            self.errors
+                .push(syntax_error!(UnmatchedQuestionMark, self.tokens[question]));
+        }
     }
 
     fn peek_next(&self) -> Option<&Token> {
@@ -548,9 +1006,29 @@ impl Reporter {
     ) -> String {
         let mut buffer = StringBuffer::default();
 
-        let first_line = match syntax_errors.len() {
-            0 => "Tokenization & syntax analysis: OK!\n".to_string(),
-            n => format!("Syntax analysis: Found {} errors.\n", n),
+        let warnings = syntax_errors
+            .iter()
+            .filter(|error| error.is_warning())
+            .count();
+        let errors = syntax_errors.len() - warnings;
+        let count = |n: usize, noun: &str| match n {
+            1 => format!("1 {}", noun),
+            n => format!("{} {}s", n, noun),
+        };
+        let first_line = match (errors, warnings) {
+            (0, 0) => "Tokenization & syntax analysis: OK!\n".to_string(),
+            (0, warnings) => format!(
+                "Tokenization & syntax analysis: OK, {}.\n",
+                count(warnings, "warning")
+            ),
+            (errors, 0) => {
+                format!("Syntax analysis: Found {}.\n", count(errors, "error"))
+            },
+            (errors, warnings) => format!(
+                "Syntax analysis: Found {}, {}.\n",
+                count(errors, "error"),
+                count(warnings, "warning")
+            ),
         };
         buffer.add_line(first_line);
 
@@ -558,6 +1036,12 @@ impl Reporter {
             return buffer.get();
         }
 
+        // Pretty errors of several lines show each line above its carets
+        let multiline = code.trim_end().contains('\n');
+        if self.source_echo.is_shown(pretty_output) && !(pretty_output && multiline) {
+            buffer.add_line(format!("\n{}", code.trim_end()));
+        }
+
         match pretty_output {
             true => self.format_errors_pretty(&mut buffer, code, syntax_errors),
             false => self.format_errors(&mut buffer, syntax_errors),
@@ -569,59 +1053,138 @@ impl Reporter {
     fn format_errors_pretty(
         &self, buffer: &mut StringBuffer, code: &str, syntax_errors: &[SyntaxError],
     ) {
-        buffer.add_line(format!("\n{}", code));
+        if !code.trim_end().contains('\n') {
+            let errors: Vec<(Range<usize>, String)> = syntax_errors
+                .iter()
+                .map(|error| (error.token.position.clone(), error.message()))
+                .collect();
+            self.format_line_errors_pretty(buffer, code, &errors);
+            return;
+        }
 
-        // First line: Underlines
-        let length = code.len();
-        let mut first_line = " ".repeat(length);
-        for error in syntax_errors {
-            let underline_length = error.token.position.end - error.token.position.start;
-            if underline_length == 1 {
-                first_line.replace_char(error.token.position.start, '^');
-            } else {
-                for index in
-                    (error.token.position.start + 1)..(error.token.position.end - 1)
-                {
-                    first_line.replace_char(index, '-');
-                }
-
-                first_line.replace_char(error.token.position.start, '^');
-                first_line.replace_char(error.token.position.end - 1, '^');
+        // Several lines: every line with errors is shown with its own carets.
+        // An error is on the line of its start
+        let source = SourceMap::new(code);
+        for line in 1..=source.line_count() {
+            let (Some(range), Some(text)) =
+                (source.line_range(line), source.line_text(line))
+            else {
+                continue;
+            };
+            let errors: Vec<(Range<usize>, String)> = syntax_errors
+                .iter()
+                .filter(|error| source.line_col(error.token.position.start).0 == line)
+                .map(|error| {
+                    let position = &error.token.position;
+                    let end = position.end.min(range.end + 1);
+                    (
+                        position.start - range.start..end - range.start,
+                        error.message(),
+                    )
+                })
+                .collect();
+            if errors.is_empty() {
+                continue;
             }
+
+            // The line break stays as a space, so errors on it have a column
+            buffer.add_line(text.clone());
+            self.format_line_errors_pretty(buffer, &format!("{text} "), &errors);
         }
-        buffer.add_line(first_line);
+    }
+
+    // Carets under the code of one line, then a message for each of them
+    fn format_line_errors_pretty(
+        &self, buffer: &mut StringBuffer, code: &str, errors: &[(Range<usize>, String)],
+    ) {
+        // Token positions are char-based, but tabs take more than one column
+        let source = SourceMap::new(code);
+        let columns = source.display_columns(self.tab_width);
+        let column = |index: usize| columns[source.clamp(index)];
+
+        // Trailing whitespace is not underlined, unless an error is there.
+        // Positions are kept, as they are counted from the start
+        let length = errors
+            .iter()
+            .map(|(position, _)| column(position.end))
+            .fold(columns[code.trim_end().chars().count()], usize::max);
+
+        // First line: Underlines
+        let ranges: Vec<Range<usize>> = errors
+            .iter()
+            .map(|(position, _)| position.clone())
+            .collect();
+        let mut underline = source.underline(&ranges, self.tab_width);
+        // Only spaces and carets, so bytes are chars
+        underline.truncate(length);
+        buffer.add_line(underline);
 
         // Other lines
-        for error in syntax_errors.iter().rev() {
+        for (position, message) in errors.iter().rev() {
             // One for -, another one for \n
             let mut line = " ".repeat(length + 2);
-            for error in syntax_errors.iter() {
-                line.replace_char(error.token.position.start, '|');
+            for (other, _) in errors {
+                line.replace_char(column(other.start), '|');
             }
-            for index in (error.token.position.start + 1)..(length + 1) {
+            for index in (column(position.start) + 1)..(length + 1) {
                 line.replace_char(index, '_');
             }
-            line.push_str(&error.to_string());
+            line.push_str(message);
             buffer.add_line(line);
         }
     }
 
+    /// Only the list of `syntax`, without the summary and the code.
+    pub fn syntax_errors_list(&self, syntax_errors: &[SyntaxError]) -> String {
+        let mut buffer = StringBuffer::default();
+        self.format_errors(&mut buffer, syntax_errors);
+        buffer.get()
+    }
+
+    // Errors go first, then warnings
     fn format_errors(&self, buffer: &mut StringBuffer, syntax_errors: &[SyntaxError]) {
-        for error in syntax_errors {
+        let (warnings, errors): (Vec<&SyntaxError>, Vec<&SyntaxError>) =
+            syntax_errors.iter().partition(|error| error.is_warning());
+        let width = Self::message_width(syntax_errors);
+        for error in errors.into_iter().chain(warnings) {
             let error = format!(
-                "{:50} {}",
-                error.to_string(),
+                "{:width$} {}",
+                error.message(),
                 error.token.display_position()
             );
             buffer.add_line(error);
         }
     }
+
+    /// Width of the message column, so the positions are lined up right after
+    /// the longest message. The result is shown in the UI, with no terminal to fit.
+    fn message_width(syntax_errors: &[SyntaxError]) -> usize {
+        syntax_errors
+            .iter()
+            .map(|error| error.message().chars().count())
+            .max()
+            .unwrap_or_default()
+    }
+
+    pub fn syntax_json(&self, syntax_errors: &[SyntaxError]) -> String {
+        json_array(syntax_errors.iter().map(|error| {
+            let mut fields = syntax_error_fields(
+                &format!("{:?}", error.kind),
+                &error.to_string(),
+                &error.token.position,
+                error.token.value.as_deref(),
+            );
+            fields.insert(1, ("severity", json_string(&error.severity.to_string())));
+            json_object(fields)
+        }))
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::compiler::tokenizer::Tokenizer;
+    use crate::compiler::reports::SourceEcho;
+    use crate::compiler::tokenizer::{Location, Tokenizer};
 
     macro_rules! test_error {
         ($error_kind:ident, $token_kind:expr, $position:literal) => {
@@ -630,8 +1193,10 @@ mod tests {
                     kind: $token_kind,
                     position: $position..$position + 1,
                     value: None,
+                    location: None,
                 },
                 kind: SyntaxErrorKind::$error_kind,
+                severity: SyntaxErrorKind::$error_kind.severity(),
             }
         };
         ($error_kind:ident, $token_kind:expr, $position:expr) => {
@@ -640,8 +1205,10 @@ mod tests {
                     kind: $token_kind,
                     position: $position,
                     value: None,
+                    location: None,
                 },
                 kind: SyntaxErrorKind::$error_kind,
+                severity: SyntaxErrorKind::$error_kind.severity(),
             }
         };
         ($error_kind:ident, $token_kind:expr, $position:literal, $value:expr) => {
@@ -650,8 +1217,10 @@ mod tests {
                     kind: $token_kind,
                     position: $position..$position + 1,
                     value: Some($value),
+                    location: None,
                 },
                 kind: SyntaxErrorKind::$error_kind,
+                severity: SyntaxErrorKind::$error_kind.severity(),
             }
         };
         ($error_kind:ident, $token_kind:expr, $position:expr, $value:expr) => {
@@ -660,8 +1229,10 @@ mod tests {
                     kind: $token_kind,
                     position: $position,
                     value: Some($value),
+                    location: None,
                 },
                 kind: SyntaxErrorKind::$error_kind,
+                severity: SyntaxErrorKind::$error_kind.severity(),
             }
         };
     }
@@ -673,18 +1244,18 @@ mod tests {
         let errors_actual: Vec<SyntaxError> =
             SyntaxAnalyzer::new(&Tokenizer::process(code)).analyze();
         let errors_expected: Vec<SyntaxError> = vec![
-            test_error!(UnexpectedOperator, TokenType::Plus, 4),
+            test_error!(ConsecutiveOperators, TokenType::Plus, 4),
             test_error!(InvalidVariableName, TokenType::Number, 10, "2".to_string()),
             test_error!(UnmatchedParenthesis, TokenType::LeftParenthesis, 17),
             test_error!(UnexpectedComma, TokenType::Comma, 24),
-            test_error!(UnexpectedOperator, TokenType::Asterisk, 32),
+            test_error!(ConsecutiveOperators, TokenType::Asterisk, 32),
             test_error!(UnexpectedDot, TokenType::Dot, 37),
             test_error!(UnexpectedOperand, TokenType::Number, 38, "2".to_string()),
             test_error!(MissingArgument, TokenType::Comma, 40),
             test_error!(InvalidFunctionName, TokenType::Number, 44, "8".to_string()),
             test_error!(UnexpectedOperator, TokenType::Minus, 46),
             test_error!(UnexpectedParenthesis, TokenType::RightParenthesis, 47),
-            test_error!(UnexpectedOperator, TokenType::Asterisk, 49),
+            test_error!(ConsecutiveOperators, TokenType::Asterisk, 49),
             test_error!(UnexpectedEndOfExpression, TokenType::Asterisk, 49),
         ];
         assert_eq!(errors_actual, errors_expected);
@@ -703,6 +1274,176 @@ mod tests {
         assert_eq!(errors_actual, errors_expected);
     }
 
+    #[test]
+    fn test_syntax_02_unicode() {
+        let code = "швидкість * * час";
+
+        let tokens = Tokenizer::process(code);
+        let positions: Vec<std::ops::Range<usize>> =
+            tokens.iter().map(|token| token.position.clone()).collect();
+        assert_eq!(positions, vec![0..9, 10..11, 12..13, 14..17]);
+
+        let errors: Vec<SyntaxError> = SyntaxAnalyzer::new(&tokens).analyze();
+        assert_eq!(
+            errors,
+            vec![test_error!(ConsecutiveOperators, TokenType::Asterisk, 12)]
+        );
+
+        let report = Reporter::default().syntax(code, true, &errors);
+        let lines: Vec<&str> = report.lines().collect();
+        let code_line = lines.iter().position(|line| *line == code);
+        let Some(code_line) = code_line else {
+            panic!("Code line is missing in report:\n{}", report)
+        };
+        assert_eq!(
+            lines[code_line + 1],
+            format!("{}^{}", " ".repeat(12), " ".repeat(4))
+        );
+        assert_eq!(
+            lines[code_line + 2],
+            format!(
+                "{}|{} Two operators in a row.",
+                " ".repeat(12),
+                "_".repeat(5)
+            )
+        );
+    }
+
+    #[test]
+    fn test_syntax_02_tab() {
+        let code = "\ta + * b";
+
+        let errors: Vec<SyntaxError> =
+            SyntaxAnalyzer::new(&Tokenizer::process(code)).analyze();
+        assert_eq!(
+            errors,
+            vec![test_error!(ConsecutiveOperators, TokenType::Asterisk, 5)]
+        );
+
+        // Tab of the code is rendered up to the tab stop
+        for (tab_width, caret) in [(4, 8), (8, 12)] {
+            let report = Reporter::default()
+                .with_tab_width(tab_width)
+                .syntax(code, true, &errors);
+            let lines: Vec<&str> = report.lines().collect();
+            let code_line = lines.iter().position(|line| *line == code);
+            let Some(code_line) = code_line else {
+                panic!("Code line is missing in report:\n{}", report)
+            };
+            assert_eq!(
+                lines[code_line + 1],
+                format!("{}^{}", " ".repeat(caret), " ".repeat(2))
+            );
+            assert!(
+                lines[code_line + 2].starts_with(&format!("{}|_", " ".repeat(caret)))
+            );
+        }
+    }
+
+    #[test]
+    fn test_syntax_02_pretty_trailing_spaces() {
+        let code = "a + * b   ";
+
+        let errors: Vec<SyntaxError> =
+            SyntaxAnalyzer::new(&Tokenizer::process(code)).analyze();
+        let report = Reporter::default().syntax(code, true, &errors);
+        let lines: Vec<&str> = report.lines().collect();
+        assert_eq!(
+            lines[3..],
+            ["a + * b", "    ^  ", "    |___ Two operators in a row."]
+        );
+
+        // Error in the whitespace itself is still underlined
+        let errors: Vec<SyntaxError> =
+            SyntaxAnalyzer::new(&Tokenizer::process("   ")).analyze();
+        let report = Reporter::default().syntax("   ", true, &errors);
+        let lines: Vec<&str> = report.lines().collect();
+        assert_eq!(lines[3..], ["", "^", "|_ Empty expression."]);
+    }
+
+    #[test]
+    fn test_message_width() {
+        let errors: Vec<SyntaxError> =
+            SyntaxAnalyzer::new(&Tokenizer::process("*a + (b")).analyze();
+        let lengths: Vec<usize> = errors
+            .iter()
+            .map(|error| error.message().chars().count())
+            .collect();
+        assert_eq!(lengths, vec![20, 22]);
+        assert_eq!(Reporter::message_width(&errors), 22);
+
+        // Positions start right after the longest message
+        let report = Reporter::default().syntax_errors_list(&errors);
+        assert_eq!(
+            report,
+            "Unexpected operator.   [Line 1, Col 1]\n\
+             Unmatched parenthesis. [Line 1, Col 6]\n"
+        );
+    }
+
+    #[test]
+    fn test_source_echo() {
+        let code = "a + * b";
+        let errors: Vec<SyntaxError> =
+            SyntaxAnalyzer::new(&Tokenizer::process(code)).analyze();
+
+        let plain = Reporter::default().syntax(code, false, &errors);
+        assert!(!plain.contains(code));
+
+        let plain = Reporter::default()
+            .with_source_echo(SourceEcho::Always)
+            .syntax(code, false, &errors);
+        let lines: Vec<&str> = plain.lines().collect();
+        assert_eq!(
+            lines[3..],
+            ["a + * b", "Two operators in a row. [Line 1, Col 5]"]
+        );
+
+        let pretty = Reporter::default()
+            .with_source_echo(SourceEcho::Never)
+            .syntax(code, true, &errors);
+        let lines: Vec<&str> = pretty.lines().collect();
+        assert_eq!(lines[2..], ["    ^  ", "    |___ Two operators in a row."]);
+    }
+
+    #[test]
+    fn test_syntax_pretty_multiline() {
+        let code = "a\n\tb * / c";
+        let errors: Vec<SyntaxError> =
+            SyntaxAnalyzer::new(&Tokenizer::process(code)).analyze();
+
+        let pretty = Reporter::default()
+            .with_tab_width(4)
+            .syntax(code, true, &errors);
+        let lines: Vec<&str> = pretty.lines().collect();
+        assert_eq!(
+            lines[1..],
+            [
+                "",
+                "a",
+                " ^",
+                " |_ Unexpected newline.",
+                "\tb * / c",
+                "    ^   ^  ",
+                "    |   |___ Two operators in a row.",
+                "    |_______ Unexpected operand 'b'.",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_syntax_02_json() {
+        let code = "*a + nb -";
+
+        let errors: Vec<SyntaxError> =
+            SyntaxAnalyzer::new(&Tokenizer::process(code)).analyze();
+        let expected = concat!(
+            r#"[{"kind":"UnexpectedOperator","severity":"error","message":"Unexpected operator.","start":0,"end":1},"#,
+            r#"{"kind":"UnexpectedEndOfExpression","severity":"error","message":"Unexpected end of expression.","start":8,"end":9}]"#,
+        );
+        assert_eq!(Reporter::default().syntax_json(&errors), expected);
+    }
+
     #[test]
     fn test_syntax_03() {
         let code = "a ++ nb /* k -+/ g";
@@ -710,10 +1451,10 @@ mod tests {
         let errors_actual: Vec<SyntaxError> =
             SyntaxAnalyzer::new(&Tokenizer::process(code)).analyze();
         let errors_expected: Vec<SyntaxError> = vec![
-            test_error!(UnexpectedOperator, TokenType::Plus, 3),
-            test_error!(UnexpectedOperator, TokenType::Asterisk, 9),
-            test_error!(UnexpectedOperator, TokenType::Plus, 14),
-            test_error!(UnexpectedOperator, TokenType::Slash, 15),
+            test_error!(ConsecutiveOperators, TokenType::Plus, 3),
+            test_error!(ConsecutiveOperators, TokenType::Asterisk, 9),
+            test_error!(ConsecutiveOperators, TokenType::Plus, 14),
+            test_error!(ConsecutiveOperators, TokenType::Slash, 15),
         ];
         assert_eq!(errors_actual, errors_expected);
     }
@@ -755,7 +1496,8 @@ mod tests {
             test_error!(UnexpectedNewLine, TokenType::NewLine, 41),
             test_error!(InvalidVariableName, TokenType::Number, 48, "6".to_string()),
             test_error!(UnknownToken, TokenType::Unknown, 56, "$".to_string()),
-            test_error!(UnknownToken, TokenType::Unknown, 61, "?".to_string()),
+            test_error!(ConsecutiveOperators, TokenType::Question, 61),
+            test_error!(UnmatchedQuestionMark, TokenType::Question, 61),
         ];
         assert_eq!(errors_actual, errors_expected);
     }
@@ -875,7 +1617,7 @@ mod tests {
             SyntaxAnalyzer::new(&Tokenizer::process(code)).analyze();
         let errors_expected: Vec<SyntaxError> = vec![
             test_error!(UnexpectedOperator, TokenType::Slash, 0),
-            test_error!(UnexpectedOperator, TokenType::Asterisk, 5),
+            test_error!(ConsecutiveOperators, TokenType::Asterisk, 5),
             test_error!(UnmatchedParenthesis, TokenType::RightParenthesis, 11),
             test_error!(UnmatchedParenthesis, TokenType::LeftParenthesis, 30),
         ];
@@ -891,7 +1633,7 @@ mod tests {
             SyntaxAnalyzer::new(&Tokenizer::process(code)).analyze();
         let errors_expected: Vec<SyntaxError> = vec![
             test_error!(UnexpectedOperator, TokenType::Minus, 5),
-            test_error!(UnexpectedOperator, TokenType::Ampersand, 6),
+            test_error!(ConsecutiveOperators, TokenType::Ampersand, 6),
             test_error!(UnmatchedParenthesis, TokenType::RightParenthesis, 9),
             test_error!(UnmatchedParenthesis, TokenType::LeftParenthesis, 11),
             test_error!(UnexpectedOperator, TokenType::Asterisk, 12),
@@ -901,24 +1643,7 @@ mod tests {
             test_error!(UnexpectedOperand, TokenType::Number, 24, "0".to_string()),
             test_error!(UnexpectedDot, TokenType::Dot, 25),
             test_error!(UnexpectedOperand, TokenType::Number, 26, "1".to_string()),
-            test_error!(UnknownToken, TokenType::Unknown, 44, "=".to_string()),
-            test_error!(UnknownToken, TokenType::Unknown, 45, "=".to_string()),
-            test_error!(UnexpectedOperand, TokenType::Number, 46, "0".to_string()),
-            test_error!(UnknownToken, TokenType::Unknown, 48, "?".to_string()),
-            test_error!(UnexpectedOperand, TokenType::Number, 49, "4".to_string()),
-            test_error!(
-                UnexpectedOperand,
-                TokenType::Identifier,
-                50..61,
-                "more_errors".to_string()
-            ),
-            test_error!(UnknownToken, TokenType::Unknown, 61, ":".to_string()),
-            test_error!(
-                UnexpectedOperand,
-                TokenType::Identifier,
-                62,
-                "b".to_string()
-            ),
+            test_error!(InvalidVariableName, TokenType::Number, 49, "4".to_string()),
             test_error!(UnknownToken, TokenType::Unknown, 63, "^".to_string()),
             test_error!(UnexpectedOperand, TokenType::Number, 64, "2".to_string()),
             test_error!(UnexpectedDot, TokenType::Dot, 69),
@@ -926,6 +1651,72 @@ mod tests {
         assert_eq!(errors_actual, errors_expected);
     }
 
+    #[test]
+    fn test_syntax_max_errors() {
+        let code =
+            "-cos(-&t))/(*(*f)(127.0.0.1, \"/dev/null\", (t==0)?4more_errors:b^2) - .5";
+        let tokens = Tokenizer::process(code);
+
+        let errors = SyntaxAnalyzer::new(&tokens).with_max_errors(5).analyze();
+        assert_eq!(errors.len(), 6);
+        // The parenthesis at 11 is closed later, past the point where the analysis stopped
+        assert_eq!(
+            errors[4],
+            test_error!(UnexpectedOperator, TokenType::Asterisk, 14)
+        );
+        assert_eq!(errors[5].kind, SyntaxErrorKind::TooManyErrors);
+
+        // Under the limit nothing changes
+        let errors = SyntaxAnalyzer::new(&tokens).with_max_errors(100).analyze();
+        assert_eq!(errors, SyntaxAnalyzer::new(&tokens).analyze());
+
+        // The end is not checked after the analysis stopped
+        let tokens = Tokenizer::process("(a + * b + * c)");
+        assert_eq!(
+            SyntaxAnalyzer::new(&tokens).analyze(),
+            vec![
+                test_error!(ConsecutiveOperators, TokenType::Asterisk, 5),
+                test_error!(ConsecutiveOperators, TokenType::Asterisk, 11),
+            ]
+        );
+        let errors = SyntaxAnalyzer::new(&tokens).with_max_errors(1).analyze();
+        assert_eq!(errors.len(), 2);
+        assert_eq!(
+            errors[0],
+            test_error!(ConsecutiveOperators, TokenType::Asterisk, 5)
+        );
+        assert_eq!(errors[1].kind, SyntaxErrorKind::TooManyErrors);
+        assert_eq!(errors[1].token.position, 11..12);
+    }
+
+    #[test]
+    fn test_syntax_semicolon() {
+        let errors =
+            |code: &str| SyntaxAnalyzer::new(&Tokenizer::process(code)).analyze();
+
+        assert!(errors("a+b; c*d").is_empty());
+        assert_eq!(
+            errors("a+; b"),
+            vec![test_error!(
+                UnexpectedEndOfExpression,
+                TokenType::Semicolon,
+                2
+            )]
+        );
+        assert_eq!(
+            errors("f(a; b)"),
+            vec![test_error!(UnexpectedSemicolon, TokenType::Semicolon, 3)]
+        );
+        assert_eq!(
+            errors("a;"),
+            vec![test_error!(
+                UnexpectedEndOfExpression,
+                TokenType::Semicolon,
+                1
+            )]
+        );
+    }
+
     #[test]
     fn test_syntax_12() {
         let code = "//(*0)- an*0p(a+b)-1.000.5//6(*f(-b, 1.8-0*(2-6) %1 + (++a)/(6x^2+4x-1) + d/dt*(smn(at+q)/(4cos(at)-ht^2)";
@@ -934,18 +1725,18 @@ mod tests {
             SyntaxAnalyzer::new(&Tokenizer::process(code)).analyze();
         let errors_expected: Vec<SyntaxError> = vec![
             test_error!(UnexpectedOperator, TokenType::Slash, 0),
-            test_error!(UnexpectedOperator, TokenType::Slash, 1),
+            test_error!(ConsecutiveOperators, TokenType::Slash, 1),
             test_error!(UnexpectedOperator, TokenType::Asterisk, 3),
             test_error!(InvalidFunctionName, TokenType::Number, 11, "0".to_string()),
             test_error!(UnexpectedDot, TokenType::Dot, 24),
             test_error!(UnexpectedOperand, TokenType::Number, 25, "5".to_string()),
-            test_error!(UnexpectedOperator, TokenType::Slash, 27),
+            test_error!(ConsecutiveOperators, TokenType::Slash, 27),
             test_error!(InvalidFunctionName, TokenType::Number, 28, "6".to_string()),
             test_error!(UnmatchedParenthesis, TokenType::LeftParenthesis, 29),
             test_error!(UnexpectedOperator, TokenType::Asterisk, 30),
             test_error!(UnmatchedParenthesis, TokenType::LeftParenthesis, 32),
             test_error!(UnexpectedOperator, TokenType::Plus, 55),
-            test_error!(UnexpectedOperator, TokenType::Plus, 56),
+            test_error!(ConsecutiveOperators, TokenType::Plus, 56),
             test_error!(InvalidVariableName, TokenType::Number, 61, "6".to_string()),
             test_error!(UnknownToken, TokenType::Unknown, 63, "^".to_string()),
             test_error!(UnexpectedOperand, TokenType::Number, 64, "2".to_string()),
@@ -991,7 +1782,7 @@ mod tests {
             test_error!(UnknownToken, TokenType::Unknown, 35, "^".to_string()),
             test_error!(UnexpectedOperand, TokenType::Number, 36, "2".to_string()),
             test_error!(InvalidVariableName, TokenType::Number, 38, "5".to_string()),
-            test_error!(UnexpectedOperator, TokenType::Plus, 55),
+            test_error!(ConsecutiveOperators, TokenType::Plus, 55),
             test_error!(UnexpectedParenthesis, TokenType::RightParenthesis, 56),
         ];
         assert_eq!(errors_actual, errors_expected);
@@ -1011,13 +1802,13 @@ mod tests {
             test_error!(UnexpectedParenthesis, TokenType::RightParenthesis, 28),
             test_error!(UnexpectedParenthesis, TokenType::LeftParenthesis, 29),
             test_error!(UnexpectedParenthesis, TokenType::RightParenthesis, 36),
-            test_error!(UnexpectedOperator, TokenType::Plus, 49),
+            test_error!(ConsecutiveOperators, TokenType::Plus, 49),
             test_error!(UnexpectedParenthesis, TokenType::RightParenthesis, 50),
             test_error!(UnexpectedOperator, TokenType::Plus, 55),
-            test_error!(UnexpectedOperator, TokenType::Plus, 56),
-            test_error!(UnexpectedOperator, TokenType::Minus, 62),
+            test_error!(ConsecutiveOperators, TokenType::Plus, 56),
+            test_error!(ConsecutiveOperators, TokenType::Minus, 62),
             test_error!(UnexpectedParenthesis, TokenType::RightParenthesis, 63),
-            test_error!(UnexpectedOperator, TokenType::Slash, 68),
+            test_error!(ConsecutiveOperators, TokenType::Slash, 68),
             test_error!(UnexpectedParenthesis, TokenType::RightParenthesis, 69),
             test_error!(UnexpectedDot, TokenType::Dot, 78),
             test_error!(
@@ -1040,7 +1831,7 @@ mod tests {
             SyntaxAnalyzer::new(&Tokenizer::process(code)).analyze();
         let errors_expected: Vec<SyntaxError> = vec![
             test_error!(UnexpectedOperator, TokenType::Asterisk, 0),
-            test_error!(UnexpectedOperator, TokenType::Asterisk, 1),
+            test_error!(ConsecutiveOperators, TokenType::Asterisk, 1),
             test_error!(UnexpectedOperator, TokenType::Asterisk, 4),
             test_error!(MissingArgument, TokenType::Comma, 12),
             test_error!(UnmatchedParenthesis, TokenType::RightParenthesis, 15),
@@ -1057,7 +1848,7 @@ mod tests {
             ),
             test_error!(UnmatchedParenthesis, TokenType::LeftParenthesis, 28),
             test_error!(InvalidVariableName, TokenType::Number, 31, "5".to_string()),
-            test_error!(UnexpectedOperator, TokenType::Plus, 35),
+            test_error!(ConsecutiveOperators, TokenType::Plus, 35),
         ];
         assert_eq!(errors_actual, errors_expected);
     }
@@ -1076,7 +1867,7 @@ mod tests {
             test_error!(UnknownToken, TokenType::Unknown, 6, "^".to_string()),
             test_error!(UnexpectedOperand, TokenType::Number, 7, "2".to_string()),
             test_error!(InvalidVariableName, TokenType::Number, 9, "5".to_string()),
-            test_error!(UnexpectedOperator, TokenType::Plus, 24),
+            test_error!(ConsecutiveOperators, TokenType::Plus, 24),
             test_error!(UnexpectedParenthesis, TokenType::RightParenthesis, 25),
             test_error!(UnexpectedParenthesis, TokenType::RightParenthesis, 31),
             test_error!(UnmatchedParenthesis, TokenType::RightParenthesis, 31),
@@ -1091,8 +1882,8 @@ mod tests {
             test_error!(UnexpectedOperand, TokenType::Number, 81, "1".to_string()),
             test_error!(UnexpectedParenthesis, TokenType::RightParenthesis, 87),
             test_error!(UnmatchedParenthesis, TokenType::RightParenthesis, 87),
-            test_error!(UnexpectedOperator, TokenType::Slash, 88),
             test_error!(UnexpectedEndOfExpression, TokenType::Slash, 88),
+            test_error!(UnexpectedOperator, TokenType::Slash, 88),
         ];
         assert_eq!(errors_actual, errors_expected);
     }
@@ -1109,7 +1900,7 @@ mod tests {
             test_error!(UnknownToken, TokenType::Unknown, 6, "#".to_string()),
             test_error!(UnexpectedParenthesis, TokenType::LeftParenthesis, 7),
             test_error!(UnexpectedParenthesis, TokenType::LeftParenthesis, 12),
-            test_error!(UnexpectedOperator, TokenType::Slash, 18),
+            test_error!(ConsecutiveOperators, TokenType::Slash, 18),
             test_error!(UnexpectedParenthesis, TokenType::RightParenthesis, 29),
             test_error!(
                 UnexpectedOperand,
@@ -1159,4 +1950,412 @@ mod tests {
         ];
         assert_eq!(errors_actual, errors_expected);
     }
+
+    #[test]
+    fn test_syntax_19() {
+        let code = "A[i, j] + B[k, ]";
+        let errors_actual: Vec<SyntaxError> =
+            SyntaxAnalyzer::new(&Tokenizer::process(code)).analyze();
+        let errors_expected: Vec<SyntaxError> =
+            vec![test_error!(MissingArgument, TokenType::Comma, 13)];
+        assert_eq!(errors_actual, errors_expected);
+    }
+
+    #[test]
+    fn test_syntax_20() {
+        let code = "\"unterminated";
+        let errors_actual: Vec<SyntaxError> =
+            SyntaxAnalyzer::new(&Tokenizer::process(code)).analyze();
+        let errors_expected: Vec<SyntaxError> = vec![test_error!(
+            UnmatchedQuotationMark,
+            TokenType::QuotationMark,
+            0
+        )];
+        assert_eq!(errors_actual, errors_expected);
+    }
+
+    #[test]
+    fn test_syntax_21() {
+        let code = "foo\" bar";
+        let errors_actual: Vec<SyntaxError> =
+            SyntaxAnalyzer::new(&Tokenizer::process(code)).analyze();
+        let errors_expected: Vec<SyntaxError> = vec![
+            test_error!(UnmatchedQuotationMark, TokenType::QuotationMark, 3),
+            test_error!(
+                UnexpectedOperand,
+                TokenType::Identifier,
+                5..8,
+                "bar".to_string()
+            ),
+        ];
+        assert_eq!(errors_actual, errors_expected);
+    }
+
+    #[test]
+    fn test_syntax_22() {
+        let cases = [
+            (
+                "a ++ b",
+                test_error!(ConsecutiveOperators, TokenType::Plus, 3),
+            ),
+            (
+                "a */ b",
+                test_error!(ConsecutiveOperators, TokenType::Slash, 3),
+            ),
+        ];
+        for (code, error) in cases {
+            let errors_actual: Vec<SyntaxError> =
+                SyntaxAnalyzer::new(&Tokenizer::process(code)).analyze();
+            assert_eq!(errors_actual, vec![error], "{}", code);
+        }
+    }
+
+    #[test]
+    fn test_syntax_23() {
+        let code = "a * -b";
+        let errors_actual: Vec<SyntaxError> =
+            SyntaxAnalyzer::new(&Tokenizer::process(code)).analyze();
+        assert!(errors_actual.is_empty());
+    }
+
+    #[test]
+    fn test_syntax_24() {
+        let code = "(t - 1) ? a : b ? c : d";
+        let errors_actual: Vec<SyntaxError> =
+            SyntaxAnalyzer::new(&Tokenizer::process(code)).analyze();
+        assert!(errors_actual.is_empty());
+
+        let code = "a ? : b";
+        let errors_actual: Vec<SyntaxError> =
+            SyntaxAnalyzer::new(&Tokenizer::process(code)).analyze();
+        let errors_expected: Vec<SyntaxError> =
+            vec![test_error!(ConsecutiveOperators, TokenType::Colon, 4)];
+        assert_eq!(errors_actual, errors_expected);
+
+        let errors =
+            |code: &str| SyntaxAnalyzer::new(&Tokenizer::process(code)).analyze();
+        assert!(errors("a ? b ? c : d : e").is_empty());
+        assert!(errors("f(a ? b : c, d) + A[x ? 1 : 0]").is_empty());
+        assert_eq!(
+            errors("a : b"),
+            vec![test_error!(UnmatchedColon, TokenType::Colon, 2)]
+        );
+        assert_eq!(
+            errors("a ? b"),
+            vec![test_error!(UnmatchedQuestionMark, TokenType::Question, 2)]
+        );
+        assert_eq!(
+            errors("a ? b : c : d"),
+            vec![test_error!(UnmatchedColon, TokenType::Colon, 10)]
+        );
+        // The group ends before the `:`
+        assert_eq!(
+            errors("(a ? b) : c"),
+            vec![
+                test_error!(UnmatchedQuestionMark, TokenType::Question, 3),
+                test_error!(UnmatchedColon, TokenType::Colon, 8),
+            ]
+        );
+        assert_eq!(
+            errors("f(a ? b, c : d)"),
+            vec![
+                test_error!(UnmatchedQuestionMark, TokenType::Question, 4),
+                test_error!(UnmatchedColon, TokenType::Colon, 11),
+            ]
+        );
+        assert_eq!(
+            errors("a ? b; c : d"),
+            vec![
+                test_error!(UnmatchedQuestionMark, TokenType::Question, 2),
+                test_error!(UnmatchedColon, TokenType::Colon, 9),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_syntax_25() {
+        let code = "a = b";
+        let errors_actual: Vec<SyntaxError> =
+            SyntaxAnalyzer::new(&Tokenizer::process(code)).analyze();
+        let errors_expected: Vec<SyntaxError> =
+            vec![test_error!(AssignmentNotSupported, TokenType::Equal, 2)];
+        assert_eq!(errors_actual, errors_expected);
+        assert_eq!(
+            errors_actual[0].to_string(),
+            "Assignment is not supported. Did you mean '=='?"
+        );
+
+        // Comparison is not an assignment
+        let code = "a == b";
+        let errors_actual: Vec<SyntaxError> =
+            SyntaxAnalyzer::new(&Tokenizer::process(code)).analyze();
+        assert!(errors_actual.is_empty());
+    }
+
+    #[test]
+    fn test_syntax_comparisons() {
+        let errors =
+            |code: &str| SyntaxAnalyzer::new(&Tokenizer::process(code)).analyze();
+
+        for code in ["a < b", "a <= b", "a > b", "a >= b", "a == b", "a != b"] {
+            assert!(errors(code).is_empty(), "Code: {}", code);
+        }
+        assert!(errors("a < b & c < d").is_empty());
+        assert!(errors("(a < b) < c").is_empty());
+        assert!(errors("f(a < b, c < d) ? x < y : z").is_empty());
+
+        assert_eq!(
+            errors("a < b < c"),
+            vec![test_error!(ChainedComparison, TokenType::Less, 6)]
+        );
+        assert_eq!(
+            errors("a == b + 1 >= c"),
+            vec![test_error!(ChainedComparison, TokenType::Greater, 11)]
+        );
+        assert!(errors("a < b < c").iter().all(SyntaxError::is_warning));
+        assert_eq!(
+            errors("a < < b"),
+            vec![test_error!(ConsecutiveOperators, TokenType::Less, 4)]
+        );
+        // Not glued, so these are two operators, and `==` is not a fix
+        assert_eq!(
+            errors("a < = b"),
+            vec![test_error!(ConsecutiveOperators, TokenType::Equal, 4)]
+        );
+        assert_eq!(
+            errors("= b"),
+            vec![test_error!(UnexpectedOperator, TokenType::Equal, 0)]
+        );
+    }
+
+    #[test]
+    fn test_syntax_unary_percent() {
+        let errors =
+            |code: &str| SyntaxAnalyzer::new(&Tokenizer::process(code)).analyze();
+
+        assert!(errors("a % b").is_empty());
+        assert_eq!(
+            errors("%5"),
+            vec![test_error!(UnexpectedPercent, TokenType::Percent, 0)]
+        );
+        assert_eq!(
+            errors("a * %b"),
+            vec![test_error!(UnexpectedPercent, TokenType::Percent, 4)]
+        );
+        assert_eq!(
+            errors("f(%b)"),
+            vec![test_error!(UnexpectedPercent, TokenType::Percent, 2)]
+        );
+    }
+
+    #[test]
+    fn test_syntax_empty() {
+        for code in ["", "   ", "\n"] {
+            let errors_actual: Vec<SyntaxError> =
+                SyntaxAnalyzer::new(&Tokenizer::process(code)).analyze();
+            let errors_expected: Vec<SyntaxError> =
+                vec![test_error!(EmptyExpression, TokenType::Space, 0)];
+            assert_eq!(errors_actual, errors_expected, "Code: {:?}", code);
+            assert_eq!(errors_actual[0].to_string(), "Empty expression.");
+        }
+    }
+
+    #[test]
+    fn test_syntax_mismatched_delimiters() {
+        let code = "A[i)";
+        let errors_actual: Vec<SyntaxError> =
+            SyntaxAnalyzer::new(&Tokenizer::process(code)).analyze();
+        let errors_expected: Vec<SyntaxError> = vec![test_error!(
+            MismatchedDelimiter,
+            TokenType::RightParenthesis,
+            3
+        )];
+        assert_eq!(errors_actual, errors_expected);
+
+        let code = "(a]";
+        let errors_actual: Vec<SyntaxError> =
+            SyntaxAnalyzer::new(&Tokenizer::process(code)).analyze();
+        let errors_expected: Vec<SyntaxError> =
+            vec![test_error!(MismatchedDelimiter, TokenType::RightBracket, 2)];
+        assert_eq!(errors_actual, errors_expected);
+
+        let code = "f(A[i], (B[j]))";
+        let errors_actual: Vec<SyntaxError> =
+            SyntaxAnalyzer::new(&Tokenizer::process(code)).analyze();
+        assert_eq!(errors_actual, vec![]);
+
+        // Unmatched opening parenthesis is still reported by itself
+        let code = "(A[i]";
+        let errors_actual: Vec<SyntaxError> =
+            SyntaxAnalyzer::new(&Tokenizer::process(code)).analyze();
+        let errors_expected: Vec<SyntaxError> = vec![test_error!(
+            UnmatchedParenthesis,
+            TokenType::LeftParenthesis,
+            0
+        )];
+        assert_eq!(errors_actual, errors_expected);
+    }
+
+    #[test]
+    fn test_syntax_backslash() {
+        let analyze = |code: &str| -> Vec<SyntaxError> {
+            SyntaxAnalyzer::new(&Tokenizer::process(code)).analyze()
+        };
+
+        let errors = analyze("a \\ b");
+        assert_eq!(
+            errors,
+            vec![test_error!(UnexpectedBackslash, TokenType::Backslash, 2)]
+        );
+
+        // Escapes are a part of the string literal
+        assert_eq!(analyze("\"a\\n\""), vec![]);
+        assert_eq!(analyze("f(\"C:\\\\temp\")"), vec![]);
+    }
+
+    #[test]
+    fn test_syntax_empty_arguments() {
+        let analyze = |code: &str| -> Vec<SyntaxError> {
+            SyntaxAnalyzer::new(&Tokenizer::process(code)).analyze()
+        };
+
+        assert_eq!(
+            analyze("f(a,,b)"),
+            vec![test_error!(MissingArgument, TokenType::Comma, 4)]
+        );
+        assert_eq!(
+            analyze("f(,)"),
+            vec![test_error!(UnexpectedComma, TokenType::Comma, 2)]
+        );
+        assert_eq!(
+            analyze("f(a,)"),
+            vec![test_error!(MissingArgument, TokenType::Comma, 3)]
+        );
+    }
+
+    #[test]
+    fn test_syntax_warnings() {
+        let analyze = |code: &str| -> Vec<SyntaxError> {
+            SyntaxAnalyzer::new(&Tokenizer::process(code)).analyze()
+        };
+        let code = "((a + b)) * c +";
+
+        let errors = analyze(code);
+        assert_eq!(
+            errors,
+            vec![
+                test_error!(RedundantParentheses, TokenType::LeftParenthesis, 0),
+                test_error!(UnexpectedEndOfExpression, TokenType::Plus, 14),
+            ]
+        );
+        assert!(errors[0].is_warning());
+        assert!(SyntaxError::has_errors(&errors));
+
+        let report = Reporter::default().syntax(code, false, &errors);
+        let lines: Vec<&str> = report.lines().collect();
+        assert_eq!(lines[0], "Syntax analysis: Found 1 error, 1 warning.");
+        // Errors go first
+        assert!(lines[2].starts_with("Unexpected end of expression."));
+        assert!(lines[3].starts_with("Warning: Redundant parentheses."));
+
+        // Warnings alone don't stop the compilation
+        let errors = analyze("((a)) + f((b)) + ((c) + d)");
+        assert_eq!(
+            errors,
+            vec![test_error!(
+                RedundantParentheses,
+                TokenType::LeftParenthesis,
+                0
+            )]
+        );
+        assert!(!SyntaxError::has_errors(&errors));
+        assert!(
+            Reporter::default()
+                .syntax("((a))", false, &errors)
+                .starts_with("Tokenization & syntax analysis: OK, 1 warning.\n")
+        );
+
+        // The assumed `*` is an empty range before the operand
+        let tokens = Tokenizer::insert_implicit_multiplication(Tokenizer::process("2x"));
+        let errors = SyntaxAnalyzer::new(&tokens).analyze();
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].kind, SyntaxErrorKind::ImplicitMultiplication);
+        assert_eq!(errors[0].token.position, 1..1);
+        assert!(errors[0].is_warning());
+    }
+
+    #[test]
+    fn test_syntax_max_errors_warnings() {
+        // Warnings before the errors don't take the places of the errors
+        let code = "((a)) + ((b)) + * c + * d";
+        let tokens = Tokenizer::process(code);
+        let errors = SyntaxAnalyzer::new(&tokens).with_max_errors(1).analyze();
+        let kinds: Vec<&SyntaxErrorKind> =
+            errors.iter().map(|error| &error.kind).collect();
+        assert_eq!(
+            kinds,
+            [
+                &SyntaxErrorKind::RedundantParentheses,
+                &SyntaxErrorKind::RedundantParentheses,
+                &SyntaxErrorKind::ConsecutiveOperators,
+                &SyntaxErrorKind::TooManyErrors,
+            ]
+        );
+        assert_eq!(errors[3].token.position, 22..23);
+    }
+
+    #[test]
+    fn test_syntax_error_order() {
+        let wide = SyntaxError {
+            token: Token {
+                kind: TokenType::Identifier,
+                position: 4..7,
+                value: Some("abc".to_string()),
+                location: None,
+            },
+            kind: SyntaxErrorKind::InvalidVariableName,
+            severity: Severity::Error,
+        };
+        let mut errors_actual = [
+            test_error!(UnmatchedParenthesis, TokenType::RightParenthesis, 4),
+            wide,
+            test_error!(UnexpectedParenthesis, TokenType::RightParenthesis, 4),
+            test_error!(UnexpectedOperator, TokenType::Plus, 2),
+        ];
+        errors_actual.sort();
+
+        let kinds: Vec<&SyntaxErrorKind> =
+            errors_actual.iter().map(|error| &error.kind).collect();
+        assert_eq!(
+            kinds,
+            vec![
+                &SyntaxErrorKind::UnexpectedOperator,
+                &SyntaxErrorKind::UnexpectedParenthesis,
+                &SyntaxErrorKind::UnmatchedParenthesis,
+                &SyntaxErrorKind::InvalidVariableName,
+            ]
+        );
+
+        // Same span and kind: the token decides, as in the equality
+        let first = test_error!(UnknownToken, TokenType::Unknown, 0, "#".to_string());
+        let second = test_error!(UnknownToken, TokenType::Unknown, 0, "$".to_string());
+        assert_eq!(first.cmp(&second), std::cmp::Ordering::Less);
+        let dot = test_error!(UnexpectedOperand, TokenType::Dot, 0);
+        let comma = test_error!(UnexpectedOperand, TokenType::Comma, 0);
+        assert_eq!(dot.cmp(&comma), dot.token.kind.cmp(&comma.token.kind));
+        assert_ne!(dot.cmp(&comma), std::cmp::Ordering::Equal);
+
+        // Ordering agrees with the equality: the location is skipped by both
+        let mut located =
+            test_error!(UnknownToken, TokenType::Unknown, 0, "#".to_string());
+        located.token.location = Some(Location { line: 1, column: 1 });
+        assert_eq!(located, first);
+        assert_eq!(located.cmp(&first), std::cmp::Ordering::Equal);
+        // while the severity is not
+        let mut warning =
+            test_error!(UnknownToken, TokenType::Unknown, 0, "#".to_string());
+        warning.severity = Severity::Warning;
+        assert_ne!(warning, first);
+        assert_ne!(warning.cmp(&first), std::cmp::Ordering::Equal);
+    }
 }
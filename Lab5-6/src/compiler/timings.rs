@@ -0,0 +1,102 @@
+use crate::compiler::reports::Reporter;
+use crate::utils::StringBuffer;
+use std::time::{Duration, Instant};
+
+/// Time spent in every pipeline stage, in the order of the first run.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct Timings {
+    pub stages: Vec<(&'static str, Duration)>,
+}
+
+impl Timings {
+    /// Runs the stage and adds its time to the label,
+    /// so repeated stages (e.g. computing) are summed up.
+    pub fn measure<T>(&mut self, label: &'static str, stage: impl FnOnce() -> T) -> T {
+        let start = Instant::now();
+        let result = stage();
+        let elapsed = start.elapsed();
+
+        match self.stages.iter_mut().find(|(name, _)| *name == label) {
+            Some((_, duration)) => *duration += elapsed,
+            None => self.stages.push((label, elapsed)),
+        }
+
+        result
+    }
+
+    pub fn total(&self) -> Duration {
+        self.stages.iter().map(|(_, duration)| *duration).sum()
+    }
+}
+
+impl Reporter {
+    pub fn timings(&self, timings: &Timings) -> String {
+        let mut buffer = StringBuffer::default();
+
+        let milliseconds = |duration: &Duration| duration.as_secs_f64() * 1000.0;
+
+        buffer.add_line("Stage timings:\n".to_string());
+        for (label, duration) in &timings.stages {
+            buffer.add_line(format!("{:10} {:>10.3} ms", label, milliseconds(duration)));
+        }
+        buffer.add_line(format!(
+            "\n{:10} {:>10.3} ms",
+            "Total",
+            milliseconds(&timings.total())
+        ));
+
+        buffer.get()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::compiler::context::CompilerContext;
+    use crate::config::Config;
+    use std::time::Duration;
+
+    #[test]
+    fn test_measure_pipeline() {
+        let mut context = CompilerContext::new(&Config::default());
+        context.code =
+            "a*b + a*c - (d + e + f + g)/2 + sin(x)*0 + A[i + 1]*3".to_string();
+
+        let Ok(timings) = context.measure_pipeline() else {
+            panic!("Failed to measure pipeline")
+        };
+        let labels: Vec<&str> = timings.stages.iter().map(|(label, _)| *label).collect();
+        assert_eq!(
+            labels,
+            vec![
+                "Tokenize",
+                "Syntax",
+                "Lexer",
+                "Parse",
+                "Compute",
+                "Transform",
+                "Balance",
+                "Fold",
+            ]
+        );
+        assert!(timings.total() > Duration::ZERO);
+
+        let report = context.timings_report();
+        assert!(report.starts_with("Stage timings:\n\nTokenize "));
+        assert!(report.contains("\nTotal "));
+
+        context.code = "a + (".to_string();
+        assert!(context.measure_pipeline().is_err());
+
+        // Failed stages give their reports, not the partial timings
+        context.code = "a + 1/0".to_string();
+        let Err(report) = context.measure_pipeline() else {
+            panic!("Division by zero is measured")
+        };
+        assert!(
+            report.starts_with("Computing constants of Abstract-Syntax Tree error: ")
+        );
+        context.code = "((((a))))".to_string();
+        context.max_parse_depth = 2;
+        assert_eq!(context.measure_pipeline().err(), Some(context.ast_report()));
+    }
+}
@@ -1,16 +1,38 @@
 use crate::utils::StringBuffer;
+use std::iter::Peekable;
 use std::ops::Range;
+use std::str::Chars;
 use strum_macros::Display;
 
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, Eq)]
 pub struct Token {
     pub kind: TokenType,
     pub position: Range<usize>,
     pub value: Option<String>,
+    pub location: Option<Location>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Location {
+    pub line: usize,
+    pub column: usize,
+}
+
+// Location is derived from the position, so it is not compared
+impl PartialEq for Token {
+    fn eq(&self, other: &Self) -> bool {
+        self.kind == other.kind
+            && self.position == other.position
+            && self.value == other.value
+    }
 }
 
 impl Token {
     pub fn display_position(&self) -> String {
+        if let Some(location) = &self.location {
+            return format!("[Line {}, Col {}]", location.line, location.column);
+        }
+
         if self.position.start + 1 == self.position.end {
             format!("[Position: {}]", self.position.start + 1)
         } else {
@@ -22,12 +44,18 @@ impl Token {
         }
     }
 
+    /// Source text of the token. Whitespace is kept as it is,
+    /// so the values of `Tokenizer::process_with_whitespace` make up the input.
     pub fn display_value(&self) -> String {
         let text = match self.kind {
             TokenType::Identifier | TokenType::Number => match &self.value {
                 Some(value) => value.as_str(),
                 None => "NONE",
             },
+            TokenType::StringLiteral => match &self.value {
+                Some(value) => &format!("\"{}\"", value),
+                None => "\"\"",
+            },
             TokenType::Plus => "+",
             TokenType::Minus => "-",
             TokenType::Asterisk => "*",
@@ -42,10 +70,21 @@ impl Token {
             TokenType::Pipe => "|",
             TokenType::Dot => ".",
             TokenType::Comma => ",",
+            TokenType::Question => "?",
+            TokenType::Colon => ":",
+            TokenType::Semicolon => ";",
+            TokenType::Equal => "=",
+            TokenType::Less => "<",
+            TokenType::Greater => ">",
+            TokenType::Backslash => "\\",
             TokenType::QuotationMark => "\"",
-            TokenType::Space => " ",
-            TokenType::Tab => "\\t",
-            TokenType::NewLine => "\\n",
+            // Value is the whitespace other than a plain space, e.g. `\r`
+            TokenType::Space => match &self.value {
+                Some(value) => value.as_str(),
+                None => &" ".repeat(self.position.len()),
+            },
+            TokenType::Tab => "\t",
+            TokenType::NewLine => "\n",
             TokenType::Unknown => "<UNKNOWN>",
         };
 
@@ -53,7 +92,7 @@ impl Token {
     }
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, Display)]
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Display)]
 pub enum TokenType {
     Identifier,
     Number,
@@ -76,7 +115,24 @@ pub enum TokenType {
     Dot,
     Comma,
 
+    Question,
+    Colon,
+
+    // Separates expressions: `a + b; c * d`
+    Semicolon,
+
+    // Comparisons with `=` after them (`<=`, `==`, `!=`) are two tokens
+    Equal,
+    Less,
+    Greater,
+
+    // Only valid in string escapes, e.g. a Windows path is an error
+    Backslash,
+
+    // A quote without a pair. Paired quotes make a `StringLiteral`
     QuotationMark,
+    // The whole string with quotes, the raw text between them is the value
+    StringLiteral,
 
     Space,
     Tab,
@@ -92,6 +148,7 @@ macro_rules! token {
             kind: $token_type,
             position: $position..($position + 1),
             value: None,
+            location: None,
         }
     };
     ($token_type:expr, $position:expr) => {
@@ -99,6 +156,7 @@ macro_rules! token {
             kind: $token_type,
             position: $position,
             value: None,
+            location: None,
         }
     };
     ($token_type:expr, $value:expr, $position:literal) => {
@@ -106,6 +164,7 @@ macro_rules! token {
             kind: $token_type,
             position: $position..($position + 1),
             value: Some($value),
+            location: None,
         }
     };
     ($token_type:expr, $value:expr, $position:expr) => {
@@ -113,6 +172,7 @@ macro_rules! token {
             kind: $token_type,
             position: $position,
             value: Some($value),
+            location: None,
         }
     };
 }
@@ -121,83 +181,165 @@ pub struct Tokenizer;
 
 impl Tokenizer {
     pub fn process(input: &str) -> Vec<Token> {
-        let mut tokens: Vec<Token> = Vec::new();
-        let chars: Vec<char> = input.chars().collect();
+        TokenStream::new(input).collect()
+    }
 
-        let mut in_string = false;
-        for (index, symbol) in chars.iter().enumerate() {
-            if let Some(last_token) = tokens.last()
-                && last_token.position.end > index
+    /// Spaces outside of strings are kept as `Space` tokens,
+    /// so no char of the input is lost.
+    pub fn process_with_whitespace(input: &str) -> Vec<Token> {
+        TokenStream::new(input).with_whitespace(true).collect()
+    }
+
+    /// Inserts synthetic `Asterisk` tokens where multiplication is implied:
+    /// `2x`, `2(t)` and `(a)b`. Strings are left untouched.
+    /// Synthetic tokens are empty ranges placed at the start of the next token.
+    pub fn insert_implicit_multiplication(tokens: Vec<Token>) -> Vec<Token> {
+        let mut result: Vec<Token> = Vec::with_capacity(tokens.len());
+
+        for token in tokens {
+            // `0xFF` is a literal, not `0 * xFF`
+            if let Some(previous) = result.last()
+                && Self::is_implicit_multiplication(&previous.kind, &token.kind)
+                && Self::radix_literal(previous, &token).is_none()
             {
-                continue;
+                let start = token.position.start;
+                result.push(Token {
+                    location: token.location,
+                    ..token!(TokenType::Asterisk, start..start)
+                });
             }
 
-            let token = match symbol {
-                symbol if symbol.is_alphabetic() || symbol.eq(&'_') => {
-                    let start = index;
-                    let mut end = index + 1;
+            result.push(token);
+        }
 
-                    while end < chars.len()
-                        && (chars[end].is_alphanumeric() || chars[end] == '_')
-                    {
-                        end += 1;
-                    }
+        result
+    }
 
-                    let value: String = chars[start..end].iter().collect();
-                    token!(TokenType::Identifier, value, start..end)
+    /// Merges floats without a whole or a fractional part into single `Number` tokens
+    /// with the missing part as zero: `.5` => `0.5`, `5.` => `5.0`.
+    /// Only adjacent tokens are merged, and complete floats (`1.5`) stay as they are.
+    pub fn normalize_floats(tokens: Vec<Token>) -> Vec<Token> {
+        let touches =
+            |left: &Token, right: &Token| left.position.end == right.position.start;
+        // Glued to the dot, it takes it: `1.2.3`, `a.5` or `5.x` are not normalized
+        let is_glued = |left: Option<&Token>, right: Option<&Token>| match (left, right) {
+            (Some(left), Some(right)) => {
+                touches(left, right)
+                    && [left, right].iter().all(|token| {
+                        matches!(
+                            token.kind,
+                            TokenType::Number | TokenType::Identifier | TokenType::Dot
+                        )
+                    })
+            },
+            _ => false,
+        };
+        let mut result: Vec<Token> = Vec::with_capacity(tokens.len());
+
+        let mut index = 0;
+        while let Some(token) = tokens.get(index) {
+            let previous = index.checked_sub(1).and_then(|index| tokens.get(index));
+            let next = tokens.get(index + 1);
+
+            let normalized = match (&token.kind, &token.value, next) {
+                (TokenType::Dot, _, Some(next))
+                    if next.kind == TokenType::Number
+                        && touches(token, next)
+                        && !is_glued(previous, Some(token)) =>
+                {
+                    next.value
+                        .as_ref()
+                        .map(|digits| (format!("0.{}", digits), next.position.end))
                 },
-                '0'..='9' => {
-                    let start = index;
-                    let mut end = index + 1;
-
-                    while end < chars.len() && chars[end].is_numeric() {
-                        end += 1;
-                    }
+                (TokenType::Number, Some(digits), Some(next))
+                    if next.kind == TokenType::Dot
+                        && touches(token, next)
+                        && !is_glued(previous, Some(token))
+                        && !is_glued(Some(next), tokens.get(index + 2)) =>
+                {
+                    Some((format!("{}.0", digits), next.position.end))
+                },
+                _ => None,
+            };
 
-                    let value: String = chars[start..end].iter().collect();
-                    token!(TokenType::Number, value, start..end)
+            match normalized {
+                Some((value, end)) => {
+                    result.push(Token {
+                        kind: TokenType::Number,
+                        position: token.position.start..end,
+                        value: Some(value),
+                        location: token.location,
+                    });
+                    index += 2;
                 },
-                '+' => token!(TokenType::Plus, index..index + 1),
-                '-' => token!(TokenType::Minus, index..index + 1),
-                '*' => token!(TokenType::Asterisk, index..index + 1),
-                '/' => token!(TokenType::Slash, index..index + 1),
-                '%' => token!(TokenType::Percent, index..index + 1),
-                '(' => token!(TokenType::LeftParenthesis, index..index + 1),
-                ')' => token!(TokenType::RightParenthesis, index..index + 1),
-                '[' => token!(TokenType::LeftBracket, index..index + 1),
-                ']' => token!(TokenType::RightBracket, index..index + 1),
-                '!' => token!(TokenType::ExclamationMark, index..index + 1),
-                '&' => token!(TokenType::Ampersand, index..index + 1),
-                '|' => token!(TokenType::Pipe, index..index + 1),
-                '.' => token!(TokenType::Dot, index..index + 1),
-                ',' => token!(TokenType::Comma, index..index + 1),
-                '"' => {
-                    in_string = !in_string;
-                    token!(TokenType::QuotationMark, index..index + 1)
+                None => {
+                    result.push(token.clone());
+                    index += 1;
                 },
-                '\n' => token!(TokenType::NewLine, index..index + 1),
-                c if c.eq(&'\t') => token!(TokenType::Tab, index..index + 1),
-                c if c.is_whitespace() => {
-                    let start = index;
-                    let mut end = index + 1;
+            }
+        }
 
-                    while end < chars.len() && chars[end].is_whitespace() {
-                        end += 1;
-                    }
+        result
+    }
 
-                    if !in_string {
-                        continue;
-                    }
+    /// Radix and digits of a hexadecimal (`0xFF`) or binary (`0b101`) literal,
+    /// which is tokenized as `0` with an identifier right after it.
+    /// The digits are not validated, that is done by the syntax analyzer.
+    pub fn radix_literal<'a>(number: &Token, next: &'a Token) -> Option<(u32, &'a str)> {
+        if number.kind != TokenType::Number
+            || number.value.as_deref() != Some("0")
+            || next.kind != TokenType::Identifier
+            || number.position.end != next.position.start
+        {
+            return None;
+        }
 
-                    token!(TokenType::Space, start..end)
-                },
-                c => token!(TokenType::Unknown, c.to_string(), index..index + 1),
+        let value = next.value.as_deref()?;
+        let radix = match value.chars().next()? {
+            'x' | 'X' => 16,
+            'b' | 'B' => 2,
+            _ => return None,
+        };
+        // The prefix is ASCII, one byte long
+        let digits = &value[1..];
+
+        match digits.is_empty() {
+            true => None,
+            false => Some((radix, digits)),
+        }
+    }
+
+    fn is_implicit_multiplication(previous: &TokenType, next: &TokenType) -> bool {
+        matches!(
+            (previous, next),
+            (
+                TokenType::Number,
+                TokenType::Identifier | TokenType::LeftParenthesis
+            ) | (TokenType::RightParenthesis, TokenType::Identifier)
+        )
+    }
+
+    /// Reassembles tokens into the source. Gaps between token positions are
+    /// filled with spaces, so the result re-tokenizes to the same tokens.
+    pub fn reconstruct(tokens: &[Token]) -> String {
+        let mut result = String::new();
+        let mut cursor = 0;
+
+        for token in tokens {
+            if token.position.start > cursor {
+                result.push_str(&" ".repeat(token.position.start - cursor));
+            }
+
+            let text = match token.kind {
+                TokenType::Unknown => token.value.clone().unwrap_or_default(),
+                _ => token.display_value(),
             };
+            result.push_str(&text);
 
-            tokens.push(token);
+            cursor = cursor.max(token.position.end);
         }
 
-        tokens
+        result
     }
 
     pub fn report(tokens: &[Token]) -> String {
@@ -205,11 +347,18 @@ impl Tokenizer {
 
         buffer.add_line("Tokenize: Success!\n".to_string());
         for (index, token) in tokens.iter().enumerate() {
+            // Whitespace is escaped, so every token stays on its own line
+            let value = match token.kind {
+                TokenType::Space | TokenType::Tab | TokenType::NewLine => {
+                    token.display_value().escape_debug().to_string()
+                },
+                _ => token.display_value(),
+            };
             buffer.add_line(format!(
                 "{:3}) {:20}: {:20} {}",
                 index + 1,
                 token.kind,
-                token.display_value(),
+                value,
                 token.display_position()
             ));
         }
@@ -218,9 +367,154 @@ impl Tokenizer {
     }
 }
 
+/// Lazy tokenizer: tokens are scanned one by one, without collecting the input.
+/// `Tokenizer::process` is this iterator collected into a vector.
+pub struct TokenStream<'a> {
+    chars: Peekable<Chars<'a>>,
+    // Char-based index and location of the next char
+    index: usize,
+    location: Location,
+    // Whitespace outside of strings is dropped, unless it is kept
+    is_whitespace_kept: bool,
+}
+
+impl<'a> TokenStream<'a> {
+    pub fn new(input: &'a str) -> Self {
+        Self {
+            chars: input.chars().peekable(),
+            index: 0,
+            location: Location { line: 1, column: 1 },
+            is_whitespace_kept: false,
+        }
+    }
+
+    /// Dropped by default, as the analyzer doesn't need it.
+    pub fn with_whitespace(mut self, is_kept: bool) -> Self {
+        self.is_whitespace_kept = is_kept;
+        self
+    }
+
+    fn bump(&mut self) -> Option<char> {
+        let symbol = self.chars.next()?;
+
+        self.index += 1;
+        if symbol == '\n' {
+            self.location.line += 1;
+            self.location.column = 1;
+        } else {
+            self.location.column += 1;
+        }
+
+        Some(symbol)
+    }
+
+    // Text up to the closing quote, which is consumed too. An unclosed string
+    // consumes nothing, so the rest of the input is tokenized as code
+    fn take_string(&mut self) -> Option<String> {
+        let mut lookahead = self.chars.clone();
+        let mut length = 0;
+        let mut is_escaped = false;
+        loop {
+            let symbol = lookahead.next()?;
+            match symbol {
+                '"' if !is_escaped => break,
+                '\\' if !is_escaped => is_escaped = true,
+                _ => is_escaped = false,
+            }
+            length += 1;
+        }
+
+        let value = (0..length).filter_map(|_| self.bump()).collect();
+        let _ = self.bump();
+        Some(value)
+    }
+
+    fn take_while(&mut self, first: char, predicate: impl Fn(char) -> bool) -> String {
+        let mut value = first.to_string();
+        while let Some(&symbol) = self.chars.peek()
+            && predicate(symbol)
+        {
+            value.push(symbol);
+            let _ = self.bump();
+        }
+        value
+    }
+}
+
+impl Iterator for TokenStream<'_> {
+    type Item = Token;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let start = self.index;
+            let location = self.location;
+            let symbol = self.bump()?;
+
+            let token = match symbol {
+                symbol if symbol.is_alphabetic() || symbol.eq(&'_') => {
+                    let value =
+                        self.take_while(symbol, |c| c.is_alphanumeric() || c == '_');
+                    token!(TokenType::Identifier, value, start..self.index)
+                },
+                '0'..='9' => {
+                    let value = self.take_while(symbol, |c| c.is_numeric());
+                    token!(TokenType::Number, value, start..self.index)
+                },
+                '+' => token!(TokenType::Plus, start..start + 1),
+                '-' => token!(TokenType::Minus, start..start + 1),
+                '*' => token!(TokenType::Asterisk, start..start + 1),
+                '/' => token!(TokenType::Slash, start..start + 1),
+                '%' => token!(TokenType::Percent, start..start + 1),
+                '(' => token!(TokenType::LeftParenthesis, start..start + 1),
+                ')' => token!(TokenType::RightParenthesis, start..start + 1),
+                '[' => token!(TokenType::LeftBracket, start..start + 1),
+                ']' => token!(TokenType::RightBracket, start..start + 1),
+                '!' => token!(TokenType::ExclamationMark, start..start + 1),
+                '&' => token!(TokenType::Ampersand, start..start + 1),
+                '|' => token!(TokenType::Pipe, start..start + 1),
+                '.' => token!(TokenType::Dot, start..start + 1),
+                ',' => token!(TokenType::Comma, start..start + 1),
+                '?' => token!(TokenType::Question, start..start + 1),
+                ':' => token!(TokenType::Colon, start..start + 1),
+                ';' => token!(TokenType::Semicolon, start..start + 1),
+                '=' => token!(TokenType::Equal, start..start + 1),
+                '<' => token!(TokenType::Less, start..start + 1),
+                '>' => token!(TokenType::Greater, start..start + 1),
+                '"' => match self.take_string() {
+                    Some(value) => {
+                        token!(TokenType::StringLiteral, value, start..self.index)
+                    },
+                    None => token!(TokenType::QuotationMark, start..start + 1),
+                },
+                '\\' => token!(TokenType::Backslash, start..start + 1),
+                '\n' => token!(TokenType::NewLine, start..start + 1),
+                '\t' => token!(TokenType::Tab, start..start + 1),
+                // Whitespace is skipped. Tabs and newlines after it
+                // are still tokens, so it is skipped char by char
+                c if c.is_whitespace() && !self.is_whitespace_kept => continue,
+                ' ' => token!(TokenType::Space, start..start + 1),
+                c if c.is_whitespace() => {
+                    token!(TokenType::Space, c.to_string(), start..start + 1)
+                },
+                c => token!(TokenType::Unknown, c.to_string(), start..start + 1),
+            };
+
+            return Some(Token {
+                location: Some(location),
+                ..token
+            });
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::compiler::ast::tree::{AbstractSyntaxTree, AstParser};
+    use crate::compiler::lexer::Lexer;
+    use crate::compiler::syntax::{SyntaxAnalyzer, SyntaxError};
+    use common::text::normalize_source;
+    use std::collections::HashMap;
 
     #[test]
     fn test_tokenize_01() {
@@ -369,7 +663,7 @@ mod tests {
             token!(TokenType::Unknown, '$'.to_string(), 52),
             token!(TokenType::Number, "7".to_string(), 53),
             token!(TokenType::Plus, 55),
-            token!(TokenType::Unknown, '?'.to_string(), 57),
+            token!(TokenType::Question, 57),
             token!(TokenType::Number, "8".to_string(), 58),
         ];
 
@@ -645,23 +939,18 @@ mod tests {
             token!(TokenType::Dot, 25),
             token!(TokenType::Number, "1".to_string(), 26),
             token!(TokenType::Comma, 27),
-            token!(TokenType::QuotationMark, 29),
-            token!(TokenType::Slash, 30),
-            token!(TokenType::Identifier, "dev".to_string(), 31..34),
-            token!(TokenType::Slash, 34),
-            token!(TokenType::Identifier, "null".to_string(), 35..39),
-            token!(TokenType::QuotationMark, 39),
+            token!(TokenType::StringLiteral, "/dev/null".to_string(), 29..40),
             token!(TokenType::Comma, 40),
             token!(TokenType::LeftParenthesis, 42),
             token!(TokenType::Identifier, "t".to_string(), 43),
-            token!(TokenType::Unknown, '='.to_string(), 44),
-            token!(TokenType::Unknown, '='.to_string(), 45),
+            token!(TokenType::Equal, 44),
+            token!(TokenType::Equal, 45),
             token!(TokenType::Number, "0".to_string(), 46),
             token!(TokenType::RightParenthesis, 47),
-            token!(TokenType::Unknown, '?'.to_string(), 48),
+            token!(TokenType::Question, 48),
             token!(TokenType::Number, "4".to_string(), 49),
             token!(TokenType::Identifier, "more_errors".to_string(), 50..61),
-            token!(TokenType::Unknown, ':'.to_string(), 61),
+            token!(TokenType::Colon, 61),
             token!(TokenType::Identifier, "b".to_string(), 62),
             token!(TokenType::Unknown, '^'.to_string(), 63),
             token!(TokenType::Number, "2".to_string(), 64),
@@ -1109,4 +1398,371 @@ mod tests {
 
         assert_eq!(tokens_actual, tokens_expected);
     }
+
+    #[test]
+    fn test_tokenize_18() {
+        let code = "a +\n  bc";
+
+        let tokens = Tokenizer::process(code);
+        let locations: Vec<Option<Location>> =
+            tokens.iter().map(|token| token.location).collect();
+        let expected = vec![
+            Some(Location { line: 1, column: 1 }),
+            Some(Location { line: 1, column: 3 }),
+            Some(Location { line: 1, column: 4 }),
+            Some(Location { line: 2, column: 3 }),
+        ];
+
+        assert_eq!(locations, expected);
+        assert_eq!(tokens[3].display_position(), "[Line 2, Col 3]");
+    }
+
+    #[test]
+    fn test_tokenize_19() {
+        let code = "2x+3(t)-(a)b+\"2x\"";
+
+        // Off by default: the tokens are unchanged
+        let tokens = Tokenizer::process(code);
+        assert!(!tokens.iter().any(|token| token.position.is_empty()));
+
+        let tokens_actual = Tokenizer::insert_implicit_multiplication(tokens);
+        let tokens_expected = vec![
+            token!(TokenType::Number, "2".to_string(), 0),
+            token!(TokenType::Asterisk, 1..1),
+            token!(TokenType::Identifier, "x".to_string(), 1),
+            token!(TokenType::Plus, 2),
+            token!(TokenType::Number, "3".to_string(), 3),
+            token!(TokenType::Asterisk, 4..4),
+            token!(TokenType::LeftParenthesis, 4),
+            token!(TokenType::Identifier, "t".to_string(), 5),
+            token!(TokenType::RightParenthesis, 6),
+            token!(TokenType::Minus, 7),
+            token!(TokenType::LeftParenthesis, 8),
+            token!(TokenType::Identifier, "a".to_string(), 9),
+            token!(TokenType::RightParenthesis, 10),
+            token!(TokenType::Asterisk, 11..11),
+            token!(TokenType::Identifier, "b".to_string(), 11),
+            token!(TokenType::Plus, 12),
+            token!(TokenType::StringLiteral, "2x".to_string(), 13..17),
+        ];
+
+        assert_eq!(tokens_actual, tokens_expected);
+        assert_eq!(tokens_actual[1].display_position(), "[Line 1, Col 2]");
+    }
+
+    #[test]
+    fn test_tokenize_20() {
+        let parse = |code: &str| -> AbstractSyntaxTree {
+            let tokens =
+                Tokenizer::insert_implicit_multiplication(Tokenizer::process(code));
+            // Only the warnings of the assumed multiplication
+            let errors = SyntaxAnalyzer::new(&tokens).analyze();
+            assert!(!SyntaxError::has_errors(&errors));
+            let Ok(lexemes) = Lexer::new(tokens).run() else {
+                panic!("Failed to create lexemes for: {}", code)
+            };
+            let Ok(ast) = AstParser::new(lexemes).parse() else {
+                panic!("Failed to build AST for: {}", code)
+            };
+            ast
+        };
+
+        let implicit = parse("2x+1");
+        assert_eq!(implicit, parse("2*x+1"));
+
+        let env = HashMap::from([("x".to_string(), 3.0)]);
+        assert_eq!(implicit.evaluate(&env), Ok(7.0));
+
+        // Without the mode it is still an error
+        let tokens = Tokenizer::process("2x+1");
+        assert!(!SyntaxAnalyzer::new(&tokens).analyze().is_empty());
+    }
+
+    #[test]
+    fn test_tokenize_21() {
+        let code = r#"open("/dev/null", "say \"hi\"\\") + "rest"#;
+
+        let tokens_actual = Tokenizer::process(code);
+        let tokens_expected = vec![
+            token!(TokenType::Identifier, "open".to_string(), 0..4),
+            token!(TokenType::LeftParenthesis, 4),
+            token!(TokenType::StringLiteral, "/dev/null".to_string(), 5..16),
+            token!(TokenType::Comma, 16),
+            token!(
+                TokenType::StringLiteral,
+                r#"say \"hi\"\\"#.to_string(),
+                18..32
+            ),
+            token!(TokenType::RightParenthesis, 32),
+            token!(TokenType::Plus, 34),
+            // Unclosed, the rest is tokenized as code
+            token!(TokenType::QuotationMark, 36),
+            token!(TokenType::Identifier, "rest".to_string(), 37..41),
+        ];
+
+        assert_eq!(tokens_actual, tokens_expected);
+        assert_eq!(tokens_actual[2].display_value(), "\"/dev/null\"");
+    }
+
+    #[test]
+    fn test_tokenize_22() {
+        let code = "a+b; c*d";
+
+        let tokens_actual = Tokenizer::process(code);
+        let tokens_expected = vec![
+            token!(TokenType::Identifier, "a".to_string(), 0..1),
+            token!(TokenType::Plus, 1),
+            token!(TokenType::Identifier, "b".to_string(), 2..3),
+            token!(TokenType::Semicolon, 3),
+            token!(TokenType::Identifier, "c".to_string(), 5..6),
+            token!(TokenType::Asterisk, 6),
+            token!(TokenType::Identifier, "d".to_string(), 7..8),
+        ];
+
+        assert_eq!(tokens_actual, tokens_expected);
+    }
+
+    #[test]
+    fn test_tokenize_23() {
+        let code = ".5 + 5.";
+        assert!(
+            !SyntaxAnalyzer::new(&Tokenizer::process(code))
+                .analyze()
+                .is_empty()
+        );
+
+        let tokens_actual = Tokenizer::normalize_floats(Tokenizer::process(code));
+        let tokens_expected = vec![
+            token!(TokenType::Number, "0.5".to_string(), 0..2),
+            token!(TokenType::Plus, 3),
+            token!(TokenType::Number, "5.0".to_string(), 5..7),
+        ];
+        assert_eq!(tokens_actual, tokens_expected);
+
+        assert!(SyntaxAnalyzer::new(&tokens_actual).analyze().is_empty());
+        let Ok(lexemes) = Lexer::new(tokens_actual).run() else {
+            panic!("Failed to create lexemes for: {}", code)
+        };
+        let Ok(ast) = AstParser::new(lexemes).parse() else {
+            panic!("Failed to build AST for: {}", code)
+        };
+        assert_eq!(ast.evaluate(&HashMap::new()), Ok(5.5));
+
+        // Complete floats and dots glued to other operands stay as they are
+        for code in ["1.5", "1.2.3", "a.5", "5.x", "1.5."] {
+            assert_eq!(
+                Tokenizer::normalize_floats(Tokenizer::process(code)),
+                Tokenizer::process(code)
+            );
+        }
+    }
+
+    #[test]
+    fn test_reconstruct() {
+        let inputs = [
+            "a + b * c",
+            "a+b*(c-d)/ 2.5",
+            "  func(a, \"hello   world\", B[i, j])  ",
+            "f(\"say \\\"hi\\\"\") + \"rest",
+            "x ? y : !z & (k | m) % 3",
+            "a +\n\tb # c",
+            "",
+        ];
+
+        for input in inputs {
+            let tokens = Tokenizer::process(input);
+            let reconstructed = Tokenizer::reconstruct(&tokens);
+            // Trailing spaces are the only thing that is not kept
+            assert_eq!(reconstructed, input.trim_end(), "Input: {:?}", input);
+            assert_eq!(
+                Tokenizer::process(&reconstructed),
+                tokens,
+                "Input: {:?}",
+                input
+            );
+        }
+    }
+
+    #[test]
+    fn test_keep_whitespace() {
+        let code = "  f(a,  \"x  y\") + 2.5 *b ";
+
+        let tokens = Tokenizer::process_with_whitespace(code);
+        let text: String = tokens.iter().map(Token::display_value).collect();
+        assert_eq!(text, code);
+        assert_eq!(tokens[0], token!(TokenType::Space, 0));
+
+        // Without spaces, the rest of the tokens are the same
+        let tokens: Vec<Token> = tokens
+            .into_iter()
+            .filter(|token| token.kind != TokenType::Space)
+            .collect();
+        assert_eq!(tokens, Tokenizer::process(code));
+
+        // Tabs, newlines and other whitespace are kept as well
+        for code in ["a +\t b\r\n\u{a0}c ", "   ", ""] {
+            let tokens = Tokenizer::process_with_whitespace(code);
+            let text: String = tokens.iter().map(Token::display_value).collect();
+            assert_eq!(text, code, "Input: {:?}", code);
+            assert_eq!(Tokenizer::reconstruct(&tokens), code, "Input: {:?}", code);
+        }
+
+        // The report escapes whitespace
+        let report = Tokenizer::report(&Tokenizer::process_with_whitespace("\t\r\n"));
+        assert!(report.contains(" \\t "), "{}", report);
+        assert!(report.contains(" \\r "), "{}", report);
+        assert!(report.contains(" \\n "), "{}", report);
+    }
+
+    // The previous eager implementation, kept as it was.
+    // It knows nothing of the later syntax, e.g. strings, so such inputs are not compared
+    fn process_eager(input: &str) -> Vec<Token> {
+        let mut tokens: Vec<Token> = Vec::new();
+        let chars: Vec<char> = input.chars().collect();
+        let locations = locations(&chars);
+
+        let mut in_string = false;
+        for (index, symbol) in chars.iter().enumerate() {
+            if let Some(last_token) = tokens.last()
+                && last_token.position.end > index
+            {
+                continue;
+            }
+
+            let token = match symbol {
+                symbol if symbol.is_alphabetic() || symbol.eq(&'_') => {
+                    let start = index;
+                    let mut end = index + 1;
+
+                    while end < chars.len()
+                        && (chars[end].is_alphanumeric() || chars[end] == '_')
+                    {
+                        end += 1;
+                    }
+
+                    let value: String = chars[start..end].iter().collect();
+                    token!(TokenType::Identifier, value, start..end)
+                },
+                '0'..='9' => {
+                    let start = index;
+                    let mut end = index + 1;
+
+                    while end < chars.len() && chars[end].is_numeric() {
+                        end += 1;
+                    }
+
+                    let value: String = chars[start..end].iter().collect();
+                    token!(TokenType::Number, value, start..end)
+                },
+                '+' => token!(TokenType::Plus, index..index + 1),
+                '-' => token!(TokenType::Minus, index..index + 1),
+                '*' => token!(TokenType::Asterisk, index..index + 1),
+                '/' => token!(TokenType::Slash, index..index + 1),
+                '%' => token!(TokenType::Percent, index..index + 1),
+                '(' => token!(TokenType::LeftParenthesis, index..index + 1),
+                ')' => token!(TokenType::RightParenthesis, index..index + 1),
+                '[' => token!(TokenType::LeftBracket, index..index + 1),
+                ']' => token!(TokenType::RightBracket, index..index + 1),
+                '!' => token!(TokenType::ExclamationMark, index..index + 1),
+                '&' => token!(TokenType::Ampersand, index..index + 1),
+                '|' => token!(TokenType::Pipe, index..index + 1),
+                '.' => token!(TokenType::Dot, index..index + 1),
+                ',' => token!(TokenType::Comma, index..index + 1),
+                '?' => token!(TokenType::Question, index..index + 1),
+                ':' => token!(TokenType::Colon, index..index + 1),
+                '=' => token!(TokenType::Equal, index..index + 1),
+                '"' => {
+                    in_string = !in_string;
+                    token!(TokenType::QuotationMark, index..index + 1)
+                },
+                '\n' => token!(TokenType::NewLine, index..index + 1),
+                c if c.eq(&'\t') => token!(TokenType::Tab, index..index + 1),
+                c if c.is_whitespace() => {
+                    let start = index;
+                    let mut end = index + 1;
+
+                    while end < chars.len() && chars[end].is_whitespace() {
+                        end += 1;
+                    }
+
+                    if !in_string {
+                        continue;
+                    }
+
+                    token!(TokenType::Space, start..end)
+                },
+                c => token!(TokenType::Unknown, c.to_string(), index..index + 1),
+            };
+
+            tokens.push(Token {
+                location: locations.get(token.position.start).copied(),
+                ..token
+            });
+        }
+
+        tokens
+    }
+
+    fn locations(chars: &[char]) -> Vec<Location> {
+        let mut locations = Vec::with_capacity(chars.len());
+        let (mut line, mut column) = (1, 1);
+
+        for symbol in chars {
+            locations.push(Location { line, column });
+            if *symbol == '\n' {
+                line += 1;
+                column = 1;
+            } else {
+                column += 1;
+            }
+        }
+
+        locations
+    }
+
+    #[test]
+    fn test_token_stream() {
+        let inputs = [
+            "a + b * c",
+            "func(a, B[i, j]) ? 1.5 : 0x1F",
+            "a +\n  \tb # c",
+            "швидкість * * час = 2",
+            " \t\n ",
+            "-cos(-&t))/(*(*f)(127.0.0.1, (t==0)?4more_errors:b^2) - .5",
+            "",
+        ];
+
+        for input in inputs {
+            let tokens: Vec<Token> = TokenStream::new(input).collect();
+            let tokens_eager = process_eager(input);
+            assert_eq!(tokens, tokens_eager, "Input: {:?}", input);
+
+            let locations: Vec<_> = tokens.iter().map(|token| token.location).collect();
+            let locations_eager: Vec<_> =
+                tokens_eager.iter().map(|token| token.location).collect();
+            assert_eq!(locations, locations_eager, "Input: {:?}", input);
+        }
+
+        // First error check stops early
+        let mut stream = TokenStream::new("a # b c d");
+        let unknown = stream.find(|token| token.kind == TokenType::Unknown);
+        assert_eq!(
+            unknown,
+            Some(token!(TokenType::Unknown, "#".to_string(), 2))
+        );
+        assert_eq!(
+            stream.next(),
+            Some(token!(TokenType::Identifier, "b".to_string(), 4))
+        );
+    }
+
+    #[test]
+    fn test_normalize_source() {
+        // BOM and CRLF of opened or pasted code, see `common::text::normalize_source`
+        let text = "\u{feff}a +\r\n b\r* c\r\n";
+        assert_eq!(
+            Tokenizer::process(&normalize_source(text)),
+            Tokenizer::process("a +\n b\n* c\n")
+        );
+    }
 }
@@ -1,17 +1,104 @@
+use crate::compiler::ast::tree::{DEFAULT_MAX_DEPTH, NumberFormat};
+use crate::compiler::ast::{math, validation};
+use crate::compiler::reports::{ComplexityLimits, DEFAULT_TAB_WIDTH};
+use crate::io::{self, IoError};
 use crate::logs;
+use crate::preferences::{Language, Theme};
 use log::LevelFilter;
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use thiserror::Error;
+use toml_edit::DocumentMut;
 
-const FILE_NAME: &str = "config.toml";
+pub const FILE_NAME: &str = "config.toml";
+/// Version of the config format. Files without the `version` key are version 0.
+pub const CONFIG_VERSION: u32 = 1;
+const DEFAULT_MAX_SYNTAX_ERRORS: usize = 20;
+
+// Written above the matching keys by `Config::init_default_file`
+const KEY_COMMENTS: [(&str, &str); 18] = [
+    (
+        "version",
+        "# Version of the config format, updated by the application",
+    ),
+    ("log_format", "# Format of log lines"),
+    (
+        "log_level",
+        "# One of: \"off\", \"error\", \"warn\", \"info\", \"debug\", \"trace\"",
+    ),
+    ("pretty_output", "# Colored output with highlighted errors"),
+    (
+        "number_precision",
+        "# Decimal places of numbers or \"full\"",
+    ),
+    (
+        "implicit_multiplication",
+        "# Insert '*' between a number and a name or a parenthesis: 2x => 2*x",
+    ),
+    (
+        "normalize_floats",
+        "# Read floats without a whole or a fractional part: .5 => 0.5, 5. => 5.0",
+    ),
+    (
+        "case_insensitive_names",
+        "# Match known functions and constants in any case: SIN(PI) is sin(pi)",
+    ),
+    ("stage_timings", "# Show the \"Stage Timings\" report"),
+    (
+        "max_syntax_errors",
+        "# Syntax errors shown before the rest are skipped, 0 for no limit",
+    ),
+    (
+        "max_parse_depth",
+        "# Nesting and tree height the parser accepts, at most 500",
+    ),
+    (
+        "tab_width",
+        "# Columns of a tab, to line up the highlighted errors",
+    ),
+    (
+        "max_tree_height",
+        "# Parsed tree height above which the metrics warn, 0 for no limit",
+    ),
+    (
+        "max_tree_nodes",
+        "# Parsed tree nodes above which the metrics warn, 0 for no limit",
+    ),
+    ("language", "# UI language: \"en\" or \"uk\""),
+    ("theme", "# UI theme: \"light\", \"dark\" or \"system\""),
+    (
+        "constants",
+        "# Names replaced by numbers before computing, e.g. pi = 3.141592653589793",
+    ),
+    (
+        "known_functions",
+        "# Functions with their number of arguments, checked before computing",
+    ),
+];
 
 #[derive(Debug)]
 pub struct Config {
     pub log_format: String,
     pub log_level: LevelFilter,
     pub pretty_output: bool,
+    pub number_format: NumberFormat,
+    pub implicit_multiplication: bool,
+    pub normalize_floats: bool,
+    pub case_insensitive_names: bool,
+    pub stage_timings: bool,
+    pub max_syntax_errors: usize,
+    pub max_parse_depth: usize,
+    pub tab_width: usize,
+    pub max_tree_height: usize,
+    pub max_tree_nodes: usize,
+    pub language: Language,
+    pub theme: Theme,
+    pub constants: BTreeMap<String, f64>,
+    pub known_functions: BTreeMap<String, usize>,
+    /// Where the config is loaded from and saved to.
+    pub file_path: PathBuf,
 }
 
 impl Default for Config {
@@ -20,42 +107,222 @@ impl Default for Config {
             log_format: logs::DEFAULT_SETTINGS.format.to_string(),
             log_level: logs::DEFAULT_SETTINGS.log_level,
             pretty_output: false,
+            number_format: NumberFormat::default(),
+            implicit_multiplication: false,
+            normalize_floats: false,
+            case_insensitive_names: false,
+            stage_timings: false,
+            max_syntax_errors: DEFAULT_MAX_SYNTAX_ERRORS,
+            max_parse_depth: DEFAULT_MAX_DEPTH,
+            tab_width: DEFAULT_TAB_WIDTH,
+            max_tree_height: ComplexityLimits::default().max_height,
+            max_tree_nodes: ComplexityLimits::default().max_nodes,
+            language: Language::default(),
+            theme: Theme::default(),
+            constants: math::common_constants(),
+            known_functions: validation::known_arities(),
+            file_path: PathBuf::from(FILE_NAME),
         }
     }
 }
 
 impl Config {
-    pub fn from_file() -> Result<Self, ConfigError> {
-        match fs::read_to_string(FILE_NAME) {
+    /// With `create_if_missing`, a missing file is created with the defaults,
+    /// which are used. Otherwise it is an error.
+    ///
+    /// A file of an older version is migrated: the missing keys get the defaults,
+    /// and the upgraded config is written back. Comments and unknown keys are kept.
+    pub fn from_file(path: &Path, create_if_missing: bool) -> Result<Self, ConfigError> {
+        match fs::read_to_string(path) {
             Ok(text) => {
                 let dto: ConfigDto =
                     toml::from_str(&text).map_err(ConfigError::Deserialization)?;
-                Config::try_from(dto)
-            },
-            Err(_) => {
-                let config = Self::default();
-                config.save_to_file()?;
+                let version = dto.version;
+                let config = Config {
+                    file_path: path.to_path_buf(),
+                    ..Config::try_from(dto)?
+                };
+
+                if version < CONFIG_VERSION {
+                    io::write_atomically(path, &config.migrate_toml(&text)?)?;
+                }
+
                 Ok(config)
             },
+            Err(error)
+                if error.kind() == std::io::ErrorKind::NotFound && create_if_missing =>
+            {
+                Self::init_default_file(path)?;
+                Ok(Self {
+                    file_path: path.to_path_buf(),
+                    ..Self::default()
+                })
+            },
+            Err(error) if error.kind() == std::io::ErrorKind::NotFound => {
+                Err(ConfigError::NotFound(path.to_path_buf()))
+            },
+            Err(error) => Err(ConfigError::IO(error)),
+        }
+    }
+
+    /// Writes the default config with a comment for every key.
+    /// An existing file is left untouched, in that case `false` is returned.
+    pub fn init_default_file(path: &Path) -> Result<bool, ConfigError> {
+        if path.exists() {
+            return Ok(false);
+        }
+
+        io::write_atomically(path, &Self::default().to_commented_toml()?)?;
+        Ok(true)
+    }
+
+    fn to_commented_toml(&self) -> Result<String, ConfigError> {
+        let mut data = String::from("# Delete this file to restore the defaults.\n\n");
+        for line in self.to_toml()?.lines() {
+            // Tables, e.g. `[constants]`, are written after the other keys
+            let comment = KEY_COMMENTS.iter().find(|(key, _)| {
+                line.starts_with(&format!("{} =", key)) || line == format!("[{}]", key)
+            });
+            if let Some((_, comment)) = comment {
+                data.push_str(comment);
+                data.push('\n');
+            }
+            data.push_str(line);
+            data.push('\n');
+        }
+
+        Ok(data)
+    }
+
+    // Text of the file with the missing keys and their comments added
+    fn migrate_toml(&self, text: &str) -> Result<String, ConfigError> {
+        let mut document: DocumentMut = text.parse()?;
+        let current: DocumentMut = self.to_toml()?.parse()?;
+
+        for (key, item) in current.iter() {
+            if document.contains_key(key) {
+                continue;
+            }
+            document.insert(key, item.clone());
+
+            let Some((_, comment)) = KEY_COMMENTS.iter().find(|(name, _)| *name == key)
+            else {
+                continue;
+            };
+            let prefix = format!("{}\n", comment);
+            match document.get_mut(key).and_then(|item| item.as_table_mut()) {
+                Some(table) => table.decor_mut().set_prefix(format!("\n{}", prefix)),
+                None => {
+                    if let Some(mut key) = document.key_mut(key) {
+                        key.leaf_decor_mut().set_prefix(prefix);
+                    }
+                },
+            }
         }
+        document["version"] = toml_edit::value(i64::from(CONFIG_VERSION));
+
+        Ok(document.to_string())
     }
 
     pub fn save_to_file(&self) -> Result<(), ConfigError> {
-        let dto = ConfigDto::from(self);
+        let data = self.to_toml()?;
 
-        let data = toml::to_string(&dto).map_err(ConfigError::Serialization)?;
+        Ok(io::write_atomically(&self.file_path, &data)?)
+    }
 
-        let path = PathBuf::from(FILE_NAME);
+    pub fn to_toml(&self) -> Result<String, ConfigError> {
+        let dto = ConfigDto::from(self);
 
-        fs::write(path, data).map_err(ConfigError::IO)
+        toml::to_string(&dto).map_err(ConfigError::Serialization)
     }
 }
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct ConfigDto {
+    // Missing in the files written before the versioning
+    #[serde(default)]
+    pub version: u32,
+    #[serde(default = "ConfigDto::default_log_format")]
     pub log_format: String,
+    #[serde(default = "ConfigDto::default_log_level")]
     pub log_level: String,
+    #[serde(default)]
     pub pretty_output: bool,
+    // Decimal places or "full". Missing in older config files.
+    #[serde(default = "ConfigDto::default_number_precision")]
+    pub number_precision: String,
+    #[serde(default)]
+    pub implicit_multiplication: bool,
+    #[serde(default)]
+    pub normalize_floats: bool,
+    #[serde(default)]
+    pub case_insensitive_names: bool,
+    #[serde(default)]
+    pub stage_timings: bool,
+    #[serde(default = "ConfigDto::default_max_syntax_errors")]
+    pub max_syntax_errors: usize,
+    #[serde(default = "ConfigDto::default_max_parse_depth")]
+    pub max_parse_depth: usize,
+    #[serde(default = "ConfigDto::default_tab_width")]
+    pub tab_width: usize,
+    #[serde(default = "ConfigDto::default_max_tree_height")]
+    pub max_tree_height: usize,
+    #[serde(default = "ConfigDto::default_max_tree_nodes")]
+    pub max_tree_nodes: usize,
+    // Language code: "en" or "uk"
+    #[serde(default = "ConfigDto::default_language")]
+    pub language: String,
+    // "light", "dark" or "system"
+    #[serde(default = "ConfigDto::default_theme")]
+    pub theme: String,
+    // Tables of `name = value`, the last ones to keep the other keys above them.
+    // An empty `[constants]` table turns the substitution off
+    #[serde(default = "math::common_constants")]
+    pub constants: BTreeMap<String, f64>,
+    #[serde(default = "validation::known_arities")]
+    pub known_functions: BTreeMap<String, usize>,
+}
+
+impl ConfigDto {
+    fn default_log_format() -> String {
+        logs::DEFAULT_SETTINGS.format.to_string()
+    }
+
+    fn default_log_level() -> String {
+        logs::DEFAULT_SETTINGS.log_level.to_string()
+    }
+
+    fn default_number_precision() -> String {
+        NumberFormat::default().to_string()
+    }
+
+    fn default_max_syntax_errors() -> usize {
+        DEFAULT_MAX_SYNTAX_ERRORS
+    }
+
+    fn default_max_parse_depth() -> usize {
+        DEFAULT_MAX_DEPTH
+    }
+
+    fn default_tab_width() -> usize {
+        DEFAULT_TAB_WIDTH
+    }
+
+    fn default_max_tree_height() -> usize {
+        ComplexityLimits::default().max_height
+    }
+
+    fn default_max_tree_nodes() -> usize {
+        ComplexityLimits::default().max_nodes
+    }
+
+    fn default_language() -> String {
+        Language::default().to_string()
+    }
+
+    fn default_theme() -> String {
+        Theme::default().to_string()
+    }
 }
 
 impl TryFrom<ConfigDto> for Config {
@@ -74,6 +341,27 @@ impl TryFrom<ConfigDto> for Config {
                 unknown => Err(Self::Error::UnknownLogLevel(unknown.to_string())),
             }?,
             pretty_output: value.pretty_output,
+            number_format: value
+                .number_precision
+                .parse()
+                .map_err(Self::Error::UnknownNumberPrecision)?,
+            implicit_multiplication: value.implicit_multiplication,
+            normalize_floats: value.normalize_floats,
+            case_insensitive_names: value.case_insensitive_names,
+            stage_timings: value.stage_timings,
+            max_syntax_errors: value.max_syntax_errors,
+            max_parse_depth: value.max_parse_depth,
+            tab_width: value.tab_width,
+            max_tree_height: value.max_tree_height,
+            max_tree_nodes: value.max_tree_nodes,
+            language: value
+                .language
+                .parse()
+                .map_err(Self::Error::UnknownLanguage)?,
+            theme: value.theme.parse().map_err(Self::Error::UnknownTheme)?,
+            constants: value.constants,
+            known_functions: value.known_functions,
+            file_path: PathBuf::from(FILE_NAME),
         })
     }
 }
@@ -81,9 +369,24 @@ impl TryFrom<ConfigDto> for Config {
 impl From<&Config> for ConfigDto {
     fn from(value: &Config) -> Self {
         Self {
+            version: CONFIG_VERSION,
             log_format: value.log_format.clone(),
             log_level: value.log_level.to_string(),
             pretty_output: value.pretty_output,
+            number_precision: value.number_format.to_string(),
+            implicit_multiplication: value.implicit_multiplication,
+            normalize_floats: value.normalize_floats,
+            case_insensitive_names: value.case_insensitive_names,
+            stage_timings: value.stage_timings,
+            max_syntax_errors: value.max_syntax_errors,
+            max_parse_depth: value.max_parse_depth,
+            tab_width: value.tab_width,
+            max_tree_height: value.max_tree_height,
+            max_tree_nodes: value.max_tree_nodes,
+            language: value.language.to_string(),
+            theme: value.theme.to_string(),
+            constants: value.constants.clone(),
+            known_functions: value.known_functions.clone(),
         }
     }
 }
@@ -99,6 +402,135 @@ pub enum ConfigError {
     #[error("IO: {0}")]
     IO(#[from] std::io::Error),
 
+    #[error("Migration: {0}")]
+    Migration(#[from] toml_edit::TomlError),
+
+    #[error("{0}")]
+    Write(#[from] IoError),
+
+    #[error("File \"{}\" is not found", .0.display())]
+    NotFound(PathBuf),
+
     #[error("Unknown log level: {0}")]
     UnknownLogLevel(String),
+
+    #[error("Unknown number precision: {0}. Expected 0 to 15 decimal places or \"full\"")]
+    UnknownNumberPrecision(String),
+
+    #[error("Unknown language: {0}. Expected \"en\" or \"uk\"")]
+    UnknownLanguage(String),
+
+    #[error("Unknown theme: {0}. Expected \"light\", \"dark\" or \"system\"")]
+    UnknownTheme(String),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_init_default_file() {
+        let path = std::env::temp_dir()
+            .join(format!("lab5-6-config-{}.toml", std::process::id()));
+        let _ = fs::remove_file(&path);
+
+        let strict = Config::from_file(&path, false);
+        assert!(matches!(strict, Err(ConfigError::NotFound(_))));
+
+        assert!(matches!(Config::init_default_file(&path), Ok(true)));
+        assert!(matches!(Config::init_default_file(&path), Ok(false)));
+
+        let text = fs::read_to_string(&path).unwrap_or_default();
+        let config = Config::from_file(&path, false);
+        let _ = fs::remove_file(&path);
+
+        assert!(text.contains("# UI language: \"en\" or \"uk\"\nlanguage = \"en\"\n"));
+        let Ok(config) = config else {
+            panic!("Failed to parse default config:\n{}", text)
+        };
+        assert_eq!(config.number_format, NumberFormat::Fixed(3));
+        assert_eq!(config.language, Language::English);
+        assert!(!config.pretty_output);
+        assert!(text.contains("\n# Names replaced by numbers before computing, e.g."));
+        assert_eq!(config.constants, math::common_constants());
+        assert!(text.contains("\n[known_functions]\n"));
+        assert_eq!(config.known_functions.get("log"), Some(&2));
+    }
+
+    #[test]
+    fn test_migrate_old_file() {
+        let path = std::env::temp_dir()
+            .join(format!("lab5-6-config-old-{}.toml", std::process::id()));
+        let _ = fs::write(
+            &path,
+            "# mine\npretty_output = true\nlanguage = \"uk\"\nunknown_key = 5\n",
+        );
+
+        let config = Config::from_file(&path, false);
+        let text = fs::read_to_string(&path).unwrap_or_default();
+        let migrated = Config::from_file(&path, false);
+        let _ = fs::remove_file(&path);
+
+        let Ok(config) = config else {
+            panic!("Failed to migrate old config")
+        };
+        assert!(config.pretty_output);
+        assert_eq!(config.language, Language::Ukrainian);
+        assert_eq!(config.log_format, logs::DEFAULT_SETTINGS.format);
+        assert_eq!(config.log_level, logs::DEFAULT_SETTINGS.log_level);
+        assert_eq!(config.number_format, NumberFormat::Fixed(3));
+        assert_eq!(config.max_syntax_errors, DEFAULT_MAX_SYNTAX_ERRORS);
+        assert_eq!(config.max_parse_depth, DEFAULT_MAX_DEPTH);
+        assert_eq!(config.constants, math::common_constants());
+        assert_eq!(config.known_functions, validation::known_arities());
+
+        // Written back with the current version and the kept values
+        assert!(text.contains(&format!("\nversion = {}\n", CONFIG_VERSION)));
+        assert!(text.contains("\npretty_output = true\n"));
+        assert!(text.contains("\nlanguage = \"uk\"\n"));
+        assert!(text.starts_with("# mine\n"));
+        assert!(text.contains("\nunknown_key = 5\n"));
+        assert!(text.contains("\n# Format of log lines\nlog_format = "));
+        let Ok(migrated) = migrated else {
+            panic!("Failed to parse migrated config:\n{}", text)
+        };
+        assert!(migrated.pretty_output);
+        assert_eq!(migrated.language, Language::Ukrainian);
+    }
+
+    #[test]
+    fn test_path_override() {
+        // An explicit path is loaded and saved to
+        let path = std::env::temp_dir().join(format!(
+            "lab5-6-config-override-{}.toml",
+            std::process::id()
+        ));
+        let _ = fs::write(&path, "version = 1\nlanguage = \"uk\"\n[constants]\n");
+        let config = Config::from_file(&path, false);
+        let is_saved = config
+            .as_ref()
+            .is_ok_and(|config| config.save_to_file().is_ok());
+        let text = fs::read_to_string(&path).unwrap_or_default();
+        let _ = fs::remove_file(&path);
+
+        let Ok(config) = config else {
+            panic!("Failed to load config from {}", path.display())
+        };
+        assert_eq!(config.language, Language::Ukrainian);
+        assert_eq!(config.file_path, path);
+        // Emptied by the user, and kept empty when saved
+        assert!(config.constants.is_empty());
+        assert!(is_saved);
+        assert!(text.contains("language = \"uk\"\n"));
+        assert!(text.contains("[constants]\n"));
+
+        let missing = Config::from_file(&path, false);
+        let Err(error) = missing else {
+            panic!("Missing config is loaded")
+        };
+        assert_eq!(
+            error.to_string(),
+            format!("File \"{}\" is not found", path.display())
+        );
+    }
 }
@@ -1,8 +1,10 @@
 use crate::compiler::context::CompilerContext;
 use crate::config::Config;
 use crate::errors::Error;
+use crate::io;
 use crate::ui::context::UIContext;
 use crate::ui::modals::error::ErrorModal;
+use std::path::Path;
 
 pub struct Context {
     pub compiler: CompilerContext,
@@ -23,10 +25,112 @@ impl Context {
 
     pub fn save_config(&mut self) {
         self.config.pretty_output = self.compiler.pretty_output;
+        self.config.number_format = self.compiler.number_format;
+        self.config.implicit_multiplication = self.compiler.implicit_multiplication;
+        self.config.normalize_floats = self.compiler.normalize_floats;
+        self.config.case_insensitive_names = self.compiler.case_insensitive_names;
+        self.config.stage_timings = self.compiler.stage_timings;
+        self.config.max_syntax_errors = self.compiler.max_syntax_errors;
+        self.config.max_parse_depth = self.compiler.max_parse_depth;
+        self.config.tab_width = self.compiler.tab_width;
+        self.config.max_tree_height = self.compiler.max_tree_height;
+        self.config.max_tree_nodes = self.compiler.max_tree_nodes;
+        self.config.language = self.ui.language;
+        self.config.theme = self.ui.theme;
 
         if let Err(error) = self.config.save_to_file() {
             let error: Error = error.into();
             ErrorModal::new(error).try_send_by(&self.ui.errors_tx);
         }
     }
+
+    pub fn export_session(&mut self, directory: &Path, result: &str) {
+        self.config.pretty_output = self.compiler.pretty_output;
+        self.config.number_format = self.compiler.number_format;
+        self.config.implicit_multiplication = self.compiler.implicit_multiplication;
+        self.config.normalize_floats = self.compiler.normalize_floats;
+        self.config.case_insensitive_names = self.compiler.case_insensitive_names;
+        self.config.stage_timings = self.compiler.stage_timings;
+        self.config.max_syntax_errors = self.compiler.max_syntax_errors;
+        self.config.max_parse_depth = self.compiler.max_parse_depth;
+        self.config.tab_width = self.compiler.tab_width;
+        self.config.max_tree_height = self.compiler.max_tree_height;
+        self.config.max_tree_nodes = self.compiler.max_tree_nodes;
+        self.config.language = self.ui.language;
+        self.config.theme = self.ui.theme;
+
+        let export_result = self
+            .collect_session_artifacts(result)
+            .and_then(|files| io::write_bundle(directory, &files).map_err(Error::from));
+
+        match export_result {
+            Ok(()) => log::info!("Session exported to {}", directory.display()),
+            Err(error) => ErrorModal::new(error).try_send_by(&self.ui.errors_tx),
+        }
+    }
+
+    pub fn collect_session_artifacts(
+        &self, result: &str,
+    ) -> Result<Vec<(String, String)>, Error> {
+        let files = vec![
+            ("code.xai", self.compiler.code.clone()),
+            ("result.txt", result.to_string()),
+            ("syntax.txt", self.compiler.syntax_report()),
+            ("ast.txt", self.compiler.ast_report()),
+            ("folding.txt", self.compiler.folding_report()),
+            ("pcs_simulation.txt", self.compiler.pcs_simulation_report()),
+            ("config.toml", self.config.to_toml()?),
+        ];
+
+        Ok(files
+            .into_iter()
+            .map(|(file_name, contents)| (file_name.to_string(), contents))
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_session_artifacts() {
+        let mut context = Context::new(Config::default());
+        context.compiler.code = "a + b * 0".to_string();
+
+        let Ok(files) = context.collect_session_artifacts("Result") else {
+            panic!("Failed to collect session artifacts")
+        };
+
+        let file_names: Vec<&str> = files
+            .iter()
+            .map(|(file_name, _)| file_name.as_str())
+            .collect();
+        assert_eq!(
+            file_names,
+            vec![
+                "code.xai",
+                "result.txt",
+                "syntax.txt",
+                "ast.txt",
+                "folding.txt",
+                "pcs_simulation.txt",
+                "config.toml",
+            ]
+        );
+
+        let contents = |name: &str| {
+            files
+                .iter()
+                .find(|(file_name, _)| file_name == name)
+                .map(|(_, contents)| contents.clone())
+                .unwrap_or_default()
+        };
+        assert_eq!(contents("code.xai"), "a + b * 0");
+        assert_eq!(contents("result.txt"), "Result");
+        assert_eq!(contents("syntax.txt"), context.compiler.syntax_report());
+        assert!(contents("config.toml").contains("pretty_output = false"));
+        assert!(contents("config.toml").contains("number_precision = \"3\""));
+        assert!(contents("config.toml").contains("language = \"en\""));
+    }
 }
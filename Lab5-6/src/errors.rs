@@ -1,6 +1,7 @@
 use crate::config::ConfigError;
 use crate::io::IoError;
 use crate::logs::LogError;
+use crate::session::SessionError;
 use thiserror::Error;
 
 #[derive(Debug, Error)]
@@ -13,4 +14,67 @@ pub enum Error {
 
     #[error("Logger setup. {0}")]
     Log(#[from] LogError),
+
+    #[error("Session. {0}")]
+    Session(#[from] SessionError),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::compiler::ast::tree::AstError;
+    use crate::compiler::lexer::LexerError;
+    use crate::compiler::syntax::SyntaxAnalyzer;
+    use crate::compiler::tokenizer::Tokenizer;
+
+    #[test]
+    fn test_error_sources() {
+        let tokens = Tokenizer::process("a +");
+        let Some(syntax_error) = SyntaxAnalyzer::new(&tokens).analyze().pop() else {
+            panic!("Expected a syntax error")
+        };
+        let Err(float_error) = "1.2.3".parse::<f64>() else {
+            panic!("Expected a float error")
+        };
+        let Some(token) = tokens.first() else {
+            panic!("Expected a token")
+        };
+
+        let errors: Vec<Box<dyn std::error::Error>> = vec![
+            Box::new(AstError::ExpectedColon),
+            Box::new(syntax_error),
+            Box::new(LexerError::ParseFloatError(
+                token.clone(),
+                float_error.clone(),
+            )),
+        ];
+        assert!(errors[0].source().is_none());
+        assert!(errors[1].source().is_none());
+        assert_eq!(
+            errors[2].source().map(|source| source.to_string()),
+            Some(float_error.to_string())
+        );
+
+        let missing = std::env::temp_dir().join("lab5-6-missing-file.xai");
+        let Err(io_error) = std::fs::read_to_string(&missing) else {
+            panic!("Expected an I/O error")
+        };
+        let io_message = io_error.to_string();
+
+        // Whole chain: Error -> IoError -> std::io::Error
+        let error: Box<dyn std::error::Error> =
+            Box::new(Error::from(IoError::ReadFile(io_error)));
+        let Some(source) = error.source() else {
+            panic!("Expected IoError as the source")
+        };
+        assert_eq!(
+            source.to_string(),
+            format!("Failed to read file: {}", io_message)
+        );
+        let Some(inner) = source.source() else {
+            panic!("Expected std::io::Error as the source")
+        };
+        assert!(inner.downcast_ref::<std::io::Error>().is_some());
+        assert_eq!(inner.to_string(), io_message);
+    }
 }
@@ -1,7 +1,34 @@
+use std::fs;
+use std::path::Path;
 use thiserror::Error;
 
 #[derive(Debug, Error)]
 pub enum IoError {
     #[error("Failed to read file: {0}")]
-    ReadFile(std::io::Error),
+    ReadFile(#[source] std::io::Error),
+
+    #[error("Failed to write file: {0}")]
+    WriteFile(#[source] std::io::Error),
+
+    #[error("Failed to create directory: {0}")]
+    CreateDirectory(#[source] std::io::Error),
+}
+
+/// Writes into a temporary file first, so the target is never left half-written.
+pub fn write_atomically(path: &Path, contents: &str) -> Result<(), IoError> {
+    let mut temp_path = path.as_os_str().to_owned();
+    temp_path.push(".tmp");
+
+    fs::write(&temp_path, contents).map_err(IoError::WriteFile)?;
+    fs::rename(&temp_path, path).map_err(IoError::WriteFile)
+}
+
+pub fn write_bundle(directory: &Path, files: &[(String, String)]) -> Result<(), IoError> {
+    fs::create_dir_all(directory).map_err(IoError::CreateDirectory)?;
+
+    for (file_name, contents) in files {
+        write_atomically(&directory.join(file_name), contents)?;
+    }
+
+    Ok(())
 }
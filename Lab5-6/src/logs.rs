@@ -9,11 +9,23 @@ pub const DEFAULT_SETTINGS: DefaultLoggerSettings = DefaultLoggerSettings {
     log_level: LevelFilter::Off,
 };
 
+// Longer specifiers go first, so `$MESSAGE` is not taken for `$M`
+const FORMAT_SPECIFIERS: [&str; 9] = [
+    "$MESSAGE", "$TARGET", "$LEVEL", "$Y", "$m", "$D", "$H", "$M", "$S",
+];
+
 pub struct DefaultLoggerSettings {
     pub format: &'static str,
     pub log_level: LevelFilter,
 }
 
+/// Piece of the log format, the specifiers are the ones of `FORMAT_SPECIFIERS`.
+#[derive(Debug, PartialEq)]
+enum FormatPart<'a> {
+    Text(&'a str),
+    Specifier(&'static str),
+}
+
 pub struct Logger {
     file_title: String,
     format: String,
@@ -55,10 +67,55 @@ impl Logger {
         }
     }
 
+    /// Checks the format, since an unknown specifier
+    /// would be written to every log line as is.
+    pub fn validate_format(format: &str) -> Result<(), LogError> {
+        let parts = Self::parse_format(format)?;
+
+        match parts.contains(&FormatPart::Specifier("$MESSAGE")) {
+            true => Ok(()),
+            false => Err(LogError::MissingMessage(format.to_string())),
+        }
+    }
+
+    /// Single pass, so a specifier is never found inside the value of another one.
+    fn parse_format(format: &str) -> Result<Vec<FormatPart<'_>>, LogError> {
+        let mut parts = Vec::new();
+        let mut rest = format;
+        while let Some(index) = rest.find('$') {
+            if index > 0 {
+                parts.push(FormatPart::Text(&rest[..index]));
+            }
+            rest = &rest[index..];
+
+            let Some(specifier) = FORMAT_SPECIFIERS
+                .iter()
+                .find(|specifier| rest.starts_with(*specifier))
+            else {
+                let unknown: String = rest
+                    .chars()
+                    .skip(1)
+                    .take_while(|c| c.is_ascii_alphanumeric())
+                    .collect();
+                return Err(LogError::UnknownFormatSpecifier(format!("${unknown}")));
+            };
+
+            parts.push(FormatPart::Specifier(specifier));
+            rest = &rest[specifier.len()..];
+        }
+        if !rest.is_empty() {
+            parts.push(FormatPart::Text(rest));
+        }
+
+        Ok(parts)
+    }
+
+    /// Nothing is checked when logging is off, the format is never used then.
     pub fn setup(self) -> Result<(), LogError> {
         if self.log_level == LevelFilter::Off {
             return Ok(());
         }
+        Self::validate_format(&self.format)?;
 
         let file_name = self.generate_file_name();
         let file = fern::log_file(file_name).map_err(LogError::IO)?;
@@ -76,25 +133,33 @@ impl Logger {
     }
 
     fn format_message(&self, message: &Arguments, record: &Record) -> String {
-        let log_message = self.format.clone();
+        // Validated in `setup`
+        let Ok(parts) = Self::parse_format(&self.format) else {
+            return message.to_string();
+        };
 
         // Time
         let time = Local::now();
 
-        log_message
-            // Time
-            .replacen("$Y", &format!("{:0>2}", time.year()), 1)
-            .replacen("$m", &format!("{:0>2}", time.month()), 1)
-            .replacen("$D", &format!("{:0>2}", time.day()), 1)
-            .replacen("$H", &format!("{:0>2}", time.hour()), 1)
-            .replacen("$M", &format!("{:0>2}", time.minute()), 1)
-            .replacen("$S", &format!("{:0>2}", time.second()), 1)
-            // Level
-            .replacen("$LEVEL", record.level().as_str(), 1)
-            // Target
-            .replacen("$TARGET", record.target(), 1)
-            // Message
-            .replacen("$MESSAGE", &message.to_string(), 1)
+        parts
+            .into_iter()
+            .map(|part| match part {
+                FormatPart::Text(text) => text.to_string(),
+                // Time
+                FormatPart::Specifier("$Y") => format!("{:0>2}", time.year()),
+                FormatPart::Specifier("$m") => format!("{:0>2}", time.month()),
+                FormatPart::Specifier("$D") => format!("{:0>2}", time.day()),
+                FormatPart::Specifier("$H") => format!("{:0>2}", time.hour()),
+                FormatPart::Specifier("$M") => format!("{:0>2}", time.minute()),
+                FormatPart::Specifier("$S") => format!("{:0>2}", time.second()),
+                // Level
+                FormatPart::Specifier("$LEVEL") => record.level().as_str().to_string(),
+                // Target
+                FormatPart::Specifier("$TARGET") => record.target().to_string(),
+                // Message
+                FormatPart::Specifier(_) => message.to_string(),
+            })
+            .collect()
     }
 
     fn generate_file_name(&self) -> String {
@@ -118,4 +183,66 @@ pub enum LogError {
 
     #[error("IO: {0}")]
     IO(std::io::Error),
+
+    #[error(
+        "Unknown log format specifier: {0}. Expected $Y, $m, $D, $H, $M, $S, $LEVEL, $TARGET or $MESSAGE"
+    )]
+    UnknownFormatSpecifier(String),
+
+    #[error("Log format has no $MESSAGE: {0}")]
+    MissingMessage(String),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validate_format() {
+        assert!(Logger::validate_format(DEFAULT_SETTINGS.format).is_ok());
+        assert!(Logger::validate_format("$H:$M:$S [$TARGET] $$MESSAGE").is_err());
+        assert!(Logger::validate_format("$LEVEL: $MESSAGE, cost 5$").is_err());
+
+        let Err(error) = Logger::validate_format("[$Y $LEVL] $MESSAGE") else {
+            panic!("Unknown specifier is not reported")
+        };
+        assert_eq!(
+            error.to_string(),
+            "Unknown log format specifier: $LEVL. Expected $Y, $m, $D, $H, $M, $S, $LEVEL, $TARGET or $MESSAGE"
+        );
+
+        let Err(error) = Logger::validate_format("[$H:$M] $LEVEL") else {
+            panic!("Missing message is not reported")
+        };
+        assert_eq!(
+            error.to_string(),
+            "Log format has no $MESSAGE: [$H:$M] $LEVEL"
+        );
+
+        // Invalid, but logging is off
+        let logger = Logger::default()
+            .with_format("$LEVL")
+            .with_level(LevelFilter::Off);
+        assert!(logger.setup().is_ok());
+    }
+
+    #[test]
+    fn test_parse_format() {
+        let Ok(parts) = Logger::parse_format("$H:$M $MESSAGE ($M)") else {
+            panic!("Failed to parse the format")
+        };
+        assert_eq!(
+            parts,
+            vec![
+                FormatPart::Specifier("$H"),
+                FormatPart::Text(":"),
+                FormatPart::Specifier("$M"),
+                FormatPart::Text(" "),
+                FormatPart::Specifier("$MESSAGE"),
+                FormatPart::Text(" ("),
+                FormatPart::Specifier("$M"),
+                FormatPart::Text(")"),
+            ]
+        );
+    }
 }
@@ -1,17 +1,32 @@
 // Hide console window on Windows in release mode
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")]
 
+use crate::cli::Cli;
 use crate::config::Config;
 use crate::logs::Logger;
+use clap::Parser;
+use std::path::Path;
 
 pub const PROJECT_TITLE: &str = "Lab 5-6";
 
 fn main() {
-    let config = Config::from_file().unwrap_or_else(|err| {
+    let cli = Cli::parse();
+
+    // An explicit path must exist, only the default file is created
+    let path_override = common::config::path_override(
+        cli.config.clone(),
+        std::env::var_os(common::config::PATH_VARIABLE),
+    );
+    let config = match path_override {
+        Some(path) => Config::from_file(&path, false),
+        None => Config::from_file(Path::new(config::FILE_NAME), true),
+    }
+    .unwrap_or_else(|err| {
         eprintln!("Error. {err}");
         std::process::exit(1);
     });
 
+    // Before the commands, so their runs are logged too
     Logger::default()
         .with_file_title(PROJECT_TITLE)
         .with_format(&config.log_format)
@@ -22,6 +37,18 @@ fn main() {
             std::process::exit(1);
         });
 
+    match cli.run_command(&config) {
+        Some(Ok(output)) => {
+            println!("{output}");
+            return;
+        },
+        Some(Err(report)) => {
+            eprintln!("{report}");
+            std::process::exit(1);
+        },
+        None => {},
+    }
+
     log::info!("Starting application.");
     log::info!("Config loaded: {config:#?}");
     log::info!("Logger initialized.");
@@ -32,11 +59,14 @@ fn main() {
     });
 }
 
+pub mod cli;
 pub mod compiler;
 pub mod config;
 pub mod context;
 pub mod errors;
 pub mod io;
 pub mod logs;
+pub mod preferences;
+pub mod session;
 pub mod ui;
 pub mod utils;
@@ -0,0 +1,117 @@
+use crate::io;
+use crate::io::IoError;
+use common::json::{JsonError, json_object, json_string, parse_string_object};
+use std::fs;
+use std::path::{Path, PathBuf};
+use thiserror::Error;
+
+const FILE_NAME: &str = "session.json";
+
+/// UI state, which is restored on the next start.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct Session {
+    pub code: String,
+    pub opened_file: Option<PathBuf>,
+    pub result: String,
+}
+
+impl Session {
+    /// Missing or corrupted session file is not an error, the app just starts fresh.
+    pub fn from_file() -> Self {
+        let Ok(text) = fs::read_to_string(FILE_NAME) else {
+            return Self::default();
+        };
+
+        Self::from_json(&text).unwrap_or_else(|error| {
+            log::warn!("Session file is corrupted, starting fresh. {error}");
+            Self::default()
+        })
+    }
+
+    pub fn save_to_file(&self) -> Result<(), SessionError> {
+        io::write_atomically(Path::new(FILE_NAME), &self.to_json())
+            .map_err(SessionError::IO)
+    }
+
+    /// Missing fields are left empty, unknown ones are skipped.
+    pub fn from_json(text: &str) -> Result<Self, SessionError> {
+        let mut session = Self::default();
+        for (name, value) in parse_string_object(text)? {
+            match (name.as_str(), value) {
+                ("code", Some(code)) => session.code = code,
+                ("opened_file", file) => session.opened_file = file.map(PathBuf::from),
+                ("result", Some(result)) => session.result = result,
+                ("code" | "result", None) => {
+                    return Err(SessionError::NullField(name));
+                },
+                _ => {},
+            }
+        }
+
+        Ok(session)
+    }
+
+    pub fn to_json(&self) -> String {
+        let opened_file = match &self.opened_file {
+            Some(path) => json_string(&path.to_string_lossy()),
+            None => "null".to_string(),
+        };
+
+        json_object([
+            ("code", json_string(&self.code)),
+            ("opened_file", opened_file),
+            ("result", json_string(&self.result)),
+        ])
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum SessionError {
+    #[error("Deserialization: {0}")]
+    Deserialization(#[from] JsonError),
+
+    #[error("Field \"{0}\" can not be null.")]
+    NullField(String),
+
+    #[error("{0}")]
+    IO(#[from] IoError),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_session_roundtrip() {
+        let session = Session {
+            code: "a + \"b\" * c".to_string(),
+            opened_file: Some(PathBuf::from("examples/code.xai")),
+            result: "Abstract-Syntax Tree generation success!\n\n+\n".to_string(),
+        };
+        let text = session.to_json();
+        let Ok(restored) = Session::from_json(&text) else {
+            panic!("Failed to deserialize session: {}", text)
+        };
+        assert_eq!(restored, session);
+
+        // No file opened, no result yet
+        let session = Session {
+            code: "a".to_string(),
+            ..Default::default()
+        };
+        let text = session.to_json();
+        assert_eq!(text, r#"{"code":"a","opened_file":null,"result":""}"#);
+        assert!(matches!(Session::from_json(&text), Ok(restored) if restored == session));
+    }
+
+    #[test]
+    fn test_session_corrupted() {
+        assert!(Session::from_json("{\"code\": [1, 2").is_err());
+        assert!(Session::from_json("{\"code\": 5}").is_err());
+        assert!(Session::from_json("{\"code\": null}").is_err());
+        assert!(Session::from_json("").is_err());
+        assert!(
+            matches!(Session::from_json("{}"), Ok(session) if session == Session::default())
+        );
+    }
+}
@@ -41,12 +41,16 @@ pub fn start(config: Config) -> eframe::Result {
     eframe::run_native(
         crate::PROJECT_TITLE,
         native_options,
-        Box::new(|cc| Ok(Box::new(App::new(cc, config)))),
+        Box::new(|cc| {
+            config.theme.apply(&cc.egui_ctx);
+            Ok(Box::new(App::new(cc, config)))
+        }),
     )
 }
 
 pub mod app;
 pub mod context;
+pub mod i18n;
 pub mod modals;
 pub mod styles;
 
@@ -1,5 +1,6 @@
 use crate::config::Config;
 use crate::context::Context;
+use crate::session::Session;
 use crate::ui::components::main::MainComponent;
 use crate::ui::components::side::SideComponent;
 use crate::ui::modals::Modal;
@@ -17,12 +18,14 @@ pub struct App {
 
 impl App {
     pub fn new(_: &eframe::CreationContext<'_>, config: Config) -> Self {
-        let context = Context::new(config);
+        let mut context = Context::new(config);
+        let main_component =
+            MainComponent::from_session(Session::from_file(), &mut context);
 
         Self {
             context,
 
-            main_component: Default::default(),
+            main_component,
             side_panel: Default::default(),
 
             errors: vec![],
@@ -57,6 +60,13 @@ impl eframe::App for App {
 
         ctx.request_repaint();
     }
+
+    fn on_exit(&mut self, _gl: Option<&eframe::glow::Context>) {
+        match self.main_component.session().save_to_file() {
+            Ok(()) => log::info!("Session saved."),
+            Err(error) => log::error!("Failed to save session. {error}"),
+        }
+    }
 }
 
 impl App {
@@ -1,3 +1,4 @@
+use crate::compiler::context::CompilerContext;
 use crate::context::Context;
 use egui::{DragValue, Grid};
 
@@ -14,55 +15,131 @@ impl FunctionsComponent {
 
         ui.vertical_centered_justified(|ui| {
             if ui.button("Tokenizer").clicked() {
-                context.ui.set_output(context.compiler.tokenize_report());
+                context.ui.set_output(
+                    context.compiler.logged(CompilerContext::tokenize_report),
+                );
             }
 
             if ui.button("Syntax check").clicked() {
-                context.ui.set_output(context.compiler.syntax_report());
+                let (output, errors) = context.compiler.logged(|compiler| {
+                    (compiler.syntax_report(), compiler.check_syntax())
+                });
+                context.ui.set_syntax_output(output, errors);
             }
 
             if ui.button("Create Lexemes").clicked() {
-                context.ui.set_output(context.compiler.lexer_report());
+                context
+                    .ui
+                    .set_output(context.compiler.logged(CompilerContext::lexer_report));
+            }
+
+            if ui.button("Lexeme Stream").clicked() {
+                context.ui.set_output(
+                    context
+                        .compiler
+                        .logged(CompilerContext::lexeme_stream_report),
+                );
             }
 
             if ui.button("Build AST").clicked() {
-                context.ui.set_output(context.compiler.ast_report());
+                context
+                    .ui
+                    .set_output(context.compiler.logged(CompilerContext::ast_report));
             }
 
             if ui.button("Compute AST #1").clicked() {
-                context.ui.set_output(context.compiler.compute_1_report());
+                context.ui.set_output(
+                    context.compiler.logged(CompilerContext::compute_1_report),
+                );
             }
 
             if ui.button("Transform AST").clicked() {
-                context.ui.set_output(context.compiler.transform_report());
+                context.ui.set_output(
+                    context.compiler.logged(CompilerContext::transform_report),
+                );
             }
 
             if ui.button("Compute AST #2").clicked() {
-                context.ui.set_output(context.compiler.compute_2_report());
+                context.ui.set_output(
+                    context.compiler.logged(CompilerContext::compute_2_report),
+                );
             }
 
             if ui.button("Balance AST").clicked() {
-                context.ui.set_output(context.compiler.balance_report());
+                context
+                    .ui
+                    .set_output(context.compiler.logged(CompilerContext::balance_report));
+            }
+
+            if ui.button("AST Metrics").clicked() {
+                context
+                    .ui
+                    .set_output(context.compiler.logged(CompilerContext::metrics_report));
             }
 
             if ui.button("Compute AST #3").clicked() {
-                context.ui.set_output(context.compiler.compute_3_report());
+                context.ui.set_output(
+                    context.compiler.logged(CompilerContext::compute_3_report),
+                );
             }
 
             if ui.button("Fold AST").clicked() {
-                context.ui.set_output(context.compiler.folding_report());
+                context
+                    .ui
+                    .set_output(context.compiler.logged(CompilerContext::folding_report));
             }
 
             if ui.button("Compute AST #4").clicked() {
-                context.ui.set_output(context.compiler.compute_4_report());
+                context.ui.set_output(
+                    context.compiler.logged(CompilerContext::compute_4_report),
+                );
+            }
+
+            if ui.button("Factor AST").clicked() {
+                context.ui.set_output(
+                    context.compiler.logged(CompilerContext::factoring_report),
+                );
+            }
+
+            if ui.button("Common Subexpressions").clicked() {
+                context
+                    .ui
+                    .set_output(context.compiler.logged(CompilerContext::cse_report));
             }
 
             ui.separator();
 
             if ui.button("Equivalent Forms").clicked() {
+                context.ui.set_output(
+                    context
+                        .compiler
+                        .logged(CompilerContext::equivalent_forms_report),
+                );
+            }
+
+            ui.add(
+                egui::TextEdit::singleline(&mut context.compiler.compared_code)
+                    .hint_text("Second expression"),
+            );
+            if ui.button("Is Equivalent?").clicked() {
+                context.ui.set_output(
+                    context.compiler.logged(CompilerContext::equivalence_report),
+                );
+            }
+
+            if ui.button("Copy as LaTeX").clicked() {
+                if let Ok(latex) = context.compiler.latex() {
+                    ui.ctx().copy_text(latex);
+                }
                 context
                     .ui
-                    .set_output(context.compiler.equivalent_forms_report());
+                    .set_output(context.compiler.logged(CompilerContext::latex_report));
+            }
+
+            if context.compiler.stage_timings && ui.button("Stage Timings").clicked() {
+                context
+                    .ui
+                    .set_output(context.compiler.logged(CompilerContext::timings_report));
             }
         });
 
@@ -112,9 +189,11 @@ impl FunctionsComponent {
             ui.add_space(10.0);
 
             if ui.button("Simulate PCS").clicked() {
-                context
-                    .ui
-                    .set_output(context.compiler.pcs_simulation_report());
+                context.ui.set_output(
+                    context
+                        .compiler
+                        .logged(CompilerContext::pcs_simulation_report),
+                );
             }
 
             if ui.button("PCS Config Reset").clicked() {
@@ -124,9 +203,11 @@ impl FunctionsComponent {
             ui.add_space(10.0);
 
             if ui.button("Optimization Research").clicked() {
-                context
-                    .ui
-                    .set_output(context.compiler.optimization_research_report());
+                context.ui.set_output(
+                    context
+                        .compiler
+                        .logged(CompilerContext::optimization_research_report),
+                );
             }
         });
     }
@@ -1,42 +1,155 @@
+use crate::compiler::ast::export::ExportFormat;
+use crate::compiler::ast::tree::{PrettyLine, PrettyNodeKind};
+use crate::compiler::reports::Reporter;
+use crate::compiler::source::SourceMap;
+use crate::compiler::syntax::SyntaxError;
 use crate::context::Context;
 use crate::errors::Error;
 use crate::io::IoError;
+use crate::session::Session;
+use crate::ui::i18n::Message;
 use crate::ui::modals::error::ErrorModal;
+use crate::ui::styles::Palette;
+use common::text::{needs_normalization, normalize_source};
+use egui::RichText;
+use std::collections::VecDeque;
 use std::fs;
 use std::path::PathBuf;
+use std::time::{Duration, Instant};
+
+const HISTORY_CAPACITY: usize = 50;
+// Typing faster than this is saved as a single history entry
+const HISTORY_DEBOUNCE: Duration = Duration::from_secs(1);
+// The views are rebuilt after a pause in typing this long
+const RECOMPUTE_DEBOUNCE: Duration = Duration::from_millis(200);
 
 #[derive(Debug, Default)]
 pub struct MainComponent {
     code: String,
     result: String,
+    // Errors of the syntax check result, clicking one moves the cursor to it.
+    // `None` when the result is not a syntax check
+    syntax_errors: Option<Vec<SyntaxError>>,
+    // Char offset for the code field cursor, applied on the next frame
+    pending_cursor: Option<usize>,
+
+    history: CodeHistory,
+    last_edit: Option<Instant>,
 
     opened_file: Option<PathBuf>,
+
+    // Balanced AST of the current code. `None` when it has to be rebuilt
+    ast_view: Option<Result<Vec<PrettyLine>, String>>,
+    // Pipeline stages report of the current code. `None` when it has to be rebuilt
+    stages_view: Option<String>,
+    // Time of the last typed edit which the views do not show yet
+    pending_recompute: Option<Instant>,
 }
 
 impl MainComponent {
+    pub fn from_session(session: Session, context: &mut Context) -> Self {
+        let mut component = Self {
+            result: session.result,
+            opened_file: session.opened_file,
+            ..Default::default()
+        };
+        component.set_code(session.code, context);
+
+        component
+    }
+
+    pub fn session(&self) -> Session {
+        Session {
+            code: self.code.clone(),
+            opened_file: self.opened_file.clone(),
+            result: self.result.clone(),
+        }
+    }
+
     pub fn show(&mut self, context: &mut Context, ui: &mut egui::Ui) {
         if let Some(result) = context.ui.get_output() {
             self.result = result;
+            self.syntax_errors = context.ui.take_syntax_errors();
+        }
+
+        if let Some(edited_at) = self.pending_recompute {
+            let now = Instant::now();
+            match Self::is_recompute_due(edited_at, now) {
+                true => {
+                    self.ast_view = None;
+                    self.stages_view = None;
+                    self.pending_recompute = None;
+                },
+                // No input is coming, so the frame after the pause is requested
+                false => ui.ctx().request_repaint_after(
+                    RECOMPUTE_DEBOUNCE.saturating_sub(now.duration_since(edited_at)),
+                ),
+            }
         }
 
         ui.horizontal(|ui| {
-            ui.label("Code:");
+            ui.label(context.ui.text(Message::Code));
 
-            if ui
-                .add(egui::TextEdit::singleline(&mut self.code).desired_width(500.0))
-                .changed()
-            {
+            let mut code_edit = egui::TextEdit::singleline(&mut self.code)
+                .desired_width(500.0)
+                .show(ui);
+            if let Some(offset) = self.pending_cursor.take() {
+                let cursor = egui::text::CCursor::new(offset);
+                code_edit
+                    .state
+                    .cursor
+                    .set_char_range(Some(egui::text::CCursorRange::one(cursor)));
+                code_edit.state.store(ui.ctx(), code_edit.response.id);
+                code_edit.response.request_focus();
+            }
+
+            if code_edit.response.changed() {
+                // Compiler still holds the code before this edit
+                let is_new_edit = self
+                    .last_edit
+                    .is_none_or(|last_edit| last_edit.elapsed() >= HISTORY_DEBOUNCE);
+                if is_new_edit {
+                    self.history.push(context.compiler.code.clone());
+                }
+                self.last_edit = Some(Instant::now());
+
+                // Pasted code
+                if needs_normalization(&self.code) {
+                    self.code = normalize_source(&self.code);
+                }
                 context.compiler.code = self.code.clone();
+                // The views are outdated, but rebuilt only when typing pauses
+                self.pending_recompute = Some(Instant::now());
+                // Positions of the errors are outdated
+                self.syntax_errors = None;
             };
 
+            // Undo
+            if !self.history.is_empty()
+                && ui
+                    .button("↶")
+                    .on_hover_text(context.ui.text(Message::Undo))
+                    .clicked()
+                && let Some(code) = self.history.pop()
+            {
+                self.set_code(code, context);
+            }
+
             // Clear code field
-            if ui.button("⟲").on_hover_text("Clear Code Field").clicked() {
-                self.code = String::new();
-                context.compiler.code = String::new();
+            if ui
+                .button("⟲")
+                .on_hover_text(context.ui.text(Message::ClearCodeField))
+                .clicked()
+            {
+                self.history.push(self.code.clone());
+                self.set_code(String::new(), context);
             }
 
             // Open File
-            if ui.button("📁").on_hover_text("Open File").clicked()
+            if ui
+                .button("📁")
+                .on_hover_text(context.ui.text(Message::OpenFile))
+                .clicked()
                 && let Some(path) = rfd::FileDialog::new()
                     .add_filter("text", &["txt", "xai"])
                     .pick_file()
@@ -46,40 +159,255 @@ impl MainComponent {
 
             if let Some(path) = &self.opened_file {
                 // Reload file
-                if ui.button("↺").on_hover_text("Reload File").clicked() {
+                if ui
+                    .button("↺")
+                    .on_hover_text(context.ui.text(Message::ReloadFile))
+                    .clicked()
+                {
                     self.read_file(path.clone(), context);
                 }
                 // Close file
-                if ui.button("⊗").on_hover_text("Close File").clicked() {
+                if ui
+                    .button("⊗")
+                    .on_hover_text(context.ui.text(Message::CloseFile))
+                    .clicked()
+                {
                     self.opened_file = None;
                 }
             }
 
+            if self.is_code_copyable()
+                && ui
+                    .button("🗐")
+                    .on_hover_text(context.ui.text(Message::CopyCode))
+                    .clicked()
+            {
+                ui.ctx().copy_text(self.code.clone());
+            }
+
             if !self.result.is_empty()
-                && ui.button("🗐").on_hover_text("Copy Result").clicked()
+                && ui
+                    .button("🗐")
+                    .on_hover_text(context.ui.text(Message::CopyResult))
+                    .clicked()
             {
                 ui.ctx().copy_text(self.result.trim().to_string());
             }
+
+            // Export session
+            if ui
+                .button("💾")
+                .on_hover_text(context.ui.text(Message::ExportSession))
+                .clicked()
+                && let Some(directory) = rfd::FileDialog::new().pick_folder()
+            {
+                let folder_name = chrono::Local::now()
+                    .format("session_%Y-%m-%d_%H-%M-%S")
+                    .to_string();
+                context.export_session(&directory.join(folder_name), &self.result);
+            }
         });
 
         ui.separator();
 
+        egui::CollapsingHeader::new(context.ui.text(Message::AstViewer)).show(ui, |ui| {
+            self.show_ast_viewer(context, ui);
+        });
+
+        egui::CollapsingHeader::new(context.ui.text(Message::OptimizationStages)).show(
+            ui,
+            |ui| {
+                self.show_stages(context, ui);
+            },
+        );
+
+        ui.separator();
+
+        if self.is_no_errors_badge_shown() {
+            let color = Palette::of(ui.visuals()).green;
+            ui.label(RichText::new(context.ui.text(Message::NoErrors)).color(color));
+        }
+
+        if self.is_errors_copyable() {
+            self.show_syntax_errors(context, ui);
+            return;
+        }
+
         ui.centered_and_justified(|ui| {
             egui::ScrollArea::vertical().show(ui, |ui| {
                 ui.add(
                     egui::TextEdit::multiline(&mut self.result)
                         .interactive(false)
                         .code_editor(),
-                );
+                )
+                // Read-only text edit senses hover only
+                .interact(egui::Sense::click())
+                .context_menu(|ui| Self::show_export_menu(context, ui));
             });
         });
     }
 
+    fn is_recompute_due(edited_at: Instant, now: Instant) -> bool {
+        now.saturating_duration_since(edited_at) >= RECOMPUTE_DEBOUNCE
+    }
+
+    fn is_code_copyable(&self) -> bool {
+        !self.code.is_empty()
+    }
+
+    // The list of the syntax check is shown, when it is not empty
+    fn is_errors_copyable(&self) -> bool {
+        self.syntax_errors
+            .as_ref()
+            .is_some_and(|errors| !errors.is_empty())
+    }
+
+    // Syntax check without errors, warnings are fine
+    fn is_no_errors_badge_shown(&self) -> bool {
+        self.syntax_errors
+            .as_ref()
+            .is_some_and(|errors| !SyntaxError::has_errors(errors))
+    }
+
+    fn show_syntax_errors(&mut self, context: &Context, ui: &mut egui::Ui) {
+        let errors = self.syntax_errors.as_deref().unwrap_or_default();
+
+        ui.horizontal(|ui| {
+            if let Some(summary) = self.result.lines().next() {
+                ui.label(RichText::new(summary).monospace());
+            }
+            if ui.button(context.ui.text(Message::CopyErrors)).clicked() {
+                ui.ctx()
+                    .copy_text(Reporter::default().syntax_errors_list(errors));
+            }
+        });
+
+        egui::ScrollArea::vertical()
+            .id_salt("SYNTAX_ERRORS")
+            .show(ui, |ui| {
+                let source = SourceMap::new(&self.code);
+                for error in errors {
+                    let (line, column) = source.line_col(error.token.position.start);
+                    let label =
+                        format!("Line {}, Col {}: {}", line, column, error.message());
+                    if ui
+                        .selectable_label(false, RichText::new(label).monospace())
+                        .clicked()
+                    {
+                        self.pending_cursor = Some(Self::error_offset(&source, error));
+                    }
+                }
+            });
+    }
+
+    // Token positions are char-based, as the cursor of the text edit
+    fn error_offset(source: &SourceMap, error: &SyntaxError) -> usize {
+        source.clamp(error.token.position.start)
+    }
+
+    fn show_export_menu(context: &Context, ui: &mut egui::Ui) {
+        let ast = context.compiler.parsed_ast();
+
+        for format in ExportFormat::ALL {
+            let button = ui
+                .add_enabled(
+                    ast.is_ok(),
+                    egui::Button::new(format!("Copy as {}", format.name())),
+                )
+                .on_disabled_hover_text("No valid AST for the current code.");
+
+            if button.clicked()
+                && let Ok(ast) = &ast
+            {
+                ui.ctx()
+                    .copy_text(ast.export(format, &context.compiler.number_format));
+                ui.close();
+            }
+        }
+    }
+
+    fn show_ast_viewer(&mut self, context: &Context, ui: &mut egui::Ui) {
+        if self.code.trim().is_empty() {
+            ui.label(context.ui.text(Message::CodeFieldIsEmpty));
+            return;
+        }
+
+        let ast_view = self.ast_view.get_or_insert_with(|| {
+            context
+                .compiler
+                .balanced_ast()
+                .map(|ast| ast.pretty_print_lines())
+        });
+
+        let palette = Palette::of(ui.visuals());
+        match ast_view {
+            Ok(lines) => {
+                egui::ScrollArea::vertical()
+                    .id_salt("AST_VIEWER")
+                    .max_height(250.0)
+                    .show(ui, |ui| {
+                        for line in lines.iter() {
+                            ui.horizontal(|ui| {
+                                ui.spacing_mut().item_spacing.x = 0.0;
+                                ui.label(RichText::new(&line.prefix).monospace());
+                                ui.label(
+                                    RichText::new(&line.text)
+                                        .monospace()
+                                        .color(Self::node_color(palette, line.kind)),
+                                );
+                            });
+                        }
+                    });
+            },
+            Err(error) => {
+                let color = Palette::of(ui.visuals()).red;
+                ui.label(RichText::new(error.trim()).monospace().color(color));
+            },
+        }
+    }
+
+    fn show_stages(&mut self, context: &Context, ui: &mut egui::Ui) {
+        if self.code.trim().is_empty() {
+            ui.label(context.ui.text(Message::CodeFieldIsEmpty));
+            return;
+        }
+
+        let stages_view = self
+            .stages_view
+            .get_or_insert_with(|| context.compiler.pipeline_diff_report());
+
+        egui::ScrollArea::vertical()
+            .id_salt("STAGES_VIEWER")
+            .max_height(250.0)
+            .show(ui, |ui| {
+                ui.label(RichText::new(stages_view.trim()).monospace());
+            });
+    }
+
+    fn node_color(palette: &Palette, kind: PrettyNodeKind) -> egui::Color32 {
+        match kind {
+            PrettyNodeKind::Operator => palette.blue,
+            PrettyNodeKind::Number | PrettyNodeKind::StringLiteral => palette.green,
+            PrettyNodeKind::Identifier => palette.yellow,
+            PrettyNodeKind::FunctionCall | PrettyNodeKind::ArrayAccess => palette.orange,
+        }
+    }
+
+    fn set_code(&mut self, code: String, context: &mut Context) {
+        self.code = code;
+        context.compiler.code = self.code.clone();
+        self.ast_view = None;
+        self.stages_view = None;
+        self.syntax_errors = None;
+        self.last_edit = None;
+        self.pending_recompute = None;
+    }
+
     fn read_file(&mut self, path: PathBuf, context: &mut Context) {
         match fs::read_to_string(&path) {
             Ok(text) => {
-                self.code = text;
-                context.compiler.code = self.code.clone();
+                self.history.push(self.code.clone());
+                self.set_code(normalize_source(&text), context);
                 self.opened_file = Some(path.clone());
             },
             Err(error) => {
@@ -89,3 +417,160 @@ impl MainComponent {
         }
     }
 }
+
+#[derive(Debug)]
+pub struct CodeHistory {
+    states: VecDeque<String>,
+    capacity: usize,
+}
+
+impl Default for CodeHistory {
+    fn default() -> Self {
+        Self::new(HISTORY_CAPACITY)
+    }
+}
+
+impl CodeHistory {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            states: VecDeque::with_capacity(capacity),
+            capacity,
+        }
+    }
+
+    pub fn push(&mut self, state: String) {
+        if self.states.back() == Some(&state) {
+            return;
+        }
+        if self.states.len() == self.capacity {
+            self.states.pop_front();
+        }
+        self.states.push_back(state);
+    }
+
+    pub fn pop(&mut self) -> Option<String> {
+        self.states.pop_back()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.states.is_empty()
+    }
+
+    pub fn len(&self) -> usize {
+        self.states.len()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::compiler::syntax::SyntaxAnalyzer;
+    use crate::compiler::tokenizer::{TokenType, Tokenizer};
+    use crate::config::Config;
+
+    #[test]
+    fn test_session() {
+        let mut context = Context::new(Config::default());
+        let session = Session {
+            code: "a + b".to_string(),
+            opened_file: Some(PathBuf::from("code.xai")),
+            result: "Result".to_string(),
+        };
+
+        let component = MainComponent::from_session(session.clone(), &mut context);
+        assert_eq!(context.compiler.code, "a + b");
+        assert!(component.history.is_empty());
+        assert_eq!(component.session(), session);
+    }
+
+    #[test]
+    fn test_copy_code_visibility() {
+        let mut context = Context::new(Config::default());
+        let mut component = MainComponent::from_session(Session::default(), &mut context);
+        assert!(!component.is_code_copyable());
+
+        component.set_code("a + b".to_string(), &mut context);
+        assert!(component.is_code_copyable());
+    }
+
+    #[test]
+    fn test_recompute_debounce() {
+        let edited_at = Instant::now();
+        let after = |milliseconds| edited_at + Duration::from_millis(milliseconds);
+
+        assert!(!MainComponent::is_recompute_due(edited_at, edited_at));
+        assert!(!MainComponent::is_recompute_due(edited_at, after(199)));
+        assert!(MainComponent::is_recompute_due(edited_at, after(200)));
+        assert!(MainComponent::is_recompute_due(edited_at, after(1000)));
+
+        // Frame time before the edit is never due
+        assert!(!MainComponent::is_recompute_due(after(50), edited_at));
+    }
+
+    #[test]
+    fn test_error_list_state() {
+        let analyze =
+            |code: &str| SyntaxAnalyzer::new(&Tokenizer::process(code)).analyze();
+
+        // Not a syntax check result
+        let mut component = MainComponent::default();
+        assert!(!component.is_errors_copyable());
+        assert!(!component.is_no_errors_badge_shown());
+
+        component.syntax_errors = Some(analyze("a + b"));
+        assert!(!component.is_errors_copyable());
+        assert!(component.is_no_errors_badge_shown());
+
+        component.syntax_errors = Some(analyze("a + * b"));
+        assert!(component.is_errors_copyable());
+        assert!(!component.is_no_errors_badge_shown());
+
+        // Warnings are listed, but they are not errors
+        component.syntax_errors = Some(analyze("((a))"));
+        assert!(component.is_errors_copyable());
+        assert!(component.is_no_errors_badge_shown());
+
+        let errors = analyze("a + * b");
+        assert_eq!(
+            Reporter::default().syntax_errors_list(&errors),
+            "Two operators in a row. [Line 1, Col 5]\n"
+        );
+    }
+
+    #[test]
+    fn test_error_offset() {
+        let code = "ä + * b";
+        let errors = SyntaxAnalyzer::new(&Tokenizer::process(code)).analyze();
+        let Some(error) = errors.first() else {
+            panic!("No syntax errors for: {}", code)
+        };
+        assert_eq!(error.token.kind, TokenType::Asterisk);
+        assert_eq!(MainComponent::error_offset(&SourceMap::new(code), error), 4);
+
+        // Error at the end of the code which was shortened since
+        assert_eq!(MainComponent::error_offset(&SourceMap::new("ä"), error), 1);
+    }
+
+    #[test]
+    fn test_history() {
+        let mut history = CodeHistory::new(3);
+        assert!(history.is_empty());
+
+        history.push("a".to_string());
+        history.push("a + b".to_string());
+        // Same state twice in a row is stored once
+        history.push("a + b".to_string());
+        assert_eq!(history.len(), 2);
+
+        history.push("a + b * c".to_string());
+        history.push("".to_string());
+        // Oldest state is dropped when the capacity is reached
+        assert_eq!(history.len(), 3);
+
+        assert_eq!(history.pop(), Some("".to_string()));
+        assert_eq!(history.pop(), Some("a + b * c".to_string()));
+        assert_eq!(history.pop(), Some("a + b".to_string()));
+        assert_eq!(history.pop(), None);
+        assert!(history.is_empty());
+    }
+}
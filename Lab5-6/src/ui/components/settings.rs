@@ -1,4 +1,8 @@
+use crate::compiler::ast::tree::{MAX_PARSE_DEPTH, NumberFormat};
 use crate::context::Context;
+use crate::preferences::{Language, Theme};
+use crate::ui::i18n::Message;
+use egui::{ComboBox, DragValue};
 
 #[derive(Default)]
 pub struct SettingsComponent;
@@ -6,17 +10,120 @@ pub struct SettingsComponent;
 impl SettingsComponent {
     pub fn show(&self, context: &mut Context, ui: &mut egui::Ui) {
         ui.vertical_centered(|ui| {
-            ui.heading("Settings");
+            ui.heading(context.ui.text(Message::Settings));
         });
 
         ui.add_space(10.0);
 
-        ui.checkbox(&mut context.compiler.pretty_output, "Pretty Output");
+        ui.checkbox(
+            &mut context.compiler.pretty_output,
+            context.ui.text(Message::PrettyOutput),
+        );
+        ui.checkbox(
+            &mut context.compiler.implicit_multiplication,
+            context.ui.text(Message::ImplicitMultiplication),
+        );
+        ui.checkbox(
+            &mut context.compiler.normalize_floats,
+            context.ui.text(Message::NormalizeFloats),
+        );
+        ui.checkbox(
+            &mut context.compiler.case_insensitive_names,
+            context.ui.text(Message::CaseInsensitiveNames),
+        );
+        ui.checkbox(
+            &mut context.compiler.stage_timings,
+            context.ui.text(Message::StageTimings),
+        );
+
+        ui.horizontal(|ui| {
+            ui.label(context.ui.text(Message::MaxSyntaxErrors));
+            ui.add(DragValue::new(&mut context.compiler.max_syntax_errors).speed(1));
+        });
+
+        ui.horizontal(|ui| {
+            ui.label(context.ui.text(Message::MaxParseDepth));
+            ui.add(
+                DragValue::new(&mut context.compiler.max_parse_depth)
+                    .speed(1)
+                    .range(1..=MAX_PARSE_DEPTH),
+            );
+        });
+
+        ui.horizontal(|ui| {
+            ui.label(context.ui.text(Message::MaxTreeHeight));
+            ui.add(DragValue::new(&mut context.compiler.max_tree_height).speed(1));
+        });
+
+        ui.horizontal(|ui| {
+            ui.label(context.ui.text(Message::MaxTreeNodes));
+            ui.add(DragValue::new(&mut context.compiler.max_tree_nodes).speed(1));
+        });
+
+        ui.horizontal(|ui| {
+            ui.label(context.ui.text(Message::TabWidth));
+            ui.add(
+                DragValue::new(&mut context.compiler.tab_width)
+                    .speed(1)
+                    .range(1..=16),
+            );
+        });
+
+        let (mut precision, mut is_full) = match context.compiler.number_format {
+            NumberFormat::Fixed(precision) => (precision, false),
+            NumberFormat::Full => (NumberFormat::DEFAULT_PRECISION, true),
+        };
+        ui.horizontal(|ui| {
+            ui.label(context.ui.text(Message::NumberPrecision));
+            ui.add_enabled(
+                !is_full,
+                DragValue::new(&mut precision)
+                    .speed(1)
+                    .range(0..=NumberFormat::MAX_PRECISION),
+            );
+            ui.checkbox(&mut is_full, context.ui.text(Message::FullPrecision));
+        });
+        context.compiler.number_format = match is_full {
+            true => NumberFormat::Full,
+            false => NumberFormat::Fixed(precision),
+        };
+
+        ui.horizontal(|ui| {
+            ui.label(context.ui.text(Message::Language));
+            ComboBox::from_id_salt("LANGUAGE_COMBO")
+                .selected_text(context.ui.language.name())
+                .show_ui(ui, |ui| {
+                    for language in Language::ALL {
+                        ui.selectable_value(
+                            &mut context.ui.language,
+                            language,
+                            language.name(),
+                        );
+                    }
+                });
+        });
+
+        ui.horizontal(|ui| {
+            ui.label(context.ui.text(Message::Theme));
+            ComboBox::from_id_salt("THEME_COMBO")
+                .selected_text(context.ui.text(Message::theme(context.ui.theme)))
+                .show_ui(ui, |ui| {
+                    for theme in Theme::ALL {
+                        let name = context.ui.text(Message::theme(theme));
+                        if ui
+                            .selectable_value(&mut context.ui.theme, theme, name)
+                            .changed()
+                        {
+                            theme.apply(ui.ctx());
+                        }
+                    }
+                });
+        });
 
         ui.add_space(10.0);
 
         ui.vertical_centered_justified(|ui| {
-            if ui.button("Save Config").clicked() {
+            if ui.button(context.ui.text(Message::SaveConfig)).clicked() {
                 context.save_config();
             }
         });
@@ -1,20 +1,31 @@
+use crate::compiler::syntax::SyntaxError;
 use crate::config::Config;
+use crate::preferences::{Language, Theme};
+use crate::ui::i18n::Message;
 use crate::ui::modals::error::ErrorModal;
 use crossbeam::channel::{Receiver, Sender, unbounded};
 
 pub struct UIContext {
     pub output: Option<String>,
+    // Errors of the syntax check output, shown as a clickable list.
+    // `None` when the output is not a syntax check
+    pub syntax_errors: Option<Vec<SyntaxError>>,
+    pub language: Language,
+    pub theme: Theme,
 
     pub errors_tx: Sender<ErrorModal>,
     pub errors_rx: Receiver<ErrorModal>,
 }
 
 impl UIContext {
-    pub fn new(_: &Config) -> Self {
+    pub fn new(config: &Config) -> Self {
         let (errors_tx, errors_rx) = unbounded::<ErrorModal>();
 
         Self {
             output: None,
+            syntax_errors: None,
+            language: config.language,
+            theme: config.theme,
             errors_tx,
             errors_rx,
         }
@@ -22,9 +33,23 @@ impl UIContext {
 
     pub fn set_output(&mut self, output: String) {
         self.output = Some(output);
+        self.syntax_errors = None;
+    }
+
+    pub fn set_syntax_output(&mut self, output: String, errors: Vec<SyntaxError>) {
+        self.output = Some(output);
+        self.syntax_errors = Some(errors);
     }
 
     pub fn get_output(&mut self) -> Option<String> {
         self.output.take()
     }
+
+    pub fn take_syntax_errors(&mut self) -> Option<Vec<SyntaxError>> {
+        self.syntax_errors.take()
+    }
+
+    pub fn text(&self, message: Message) -> &'static str {
+        crate::ui::i18n::translate(self.language, message)
+    }
 }
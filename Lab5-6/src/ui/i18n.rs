@@ -0,0 +1,234 @@
+use crate::preferences::{Language, Theme};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Message {
+    Code,
+    Undo,
+    ClearCodeField,
+    OpenFile,
+    ReloadFile,
+    CloseFile,
+    CopyCode,
+    CopyResult,
+    CopyErrors,
+    NoErrors,
+    ExportSession,
+    AstViewer,
+    OptimizationStages,
+    CodeFieldIsEmpty,
+    Settings,
+    PrettyOutput,
+    ImplicitMultiplication,
+    NormalizeFloats,
+    CaseInsensitiveNames,
+    StageTimings,
+    MaxSyntaxErrors,
+    MaxParseDepth,
+    MaxTreeHeight,
+    MaxTreeNodes,
+    TabWidth,
+    NumberPrecision,
+    FullPrecision,
+    Language,
+    Theme,
+    LightTheme,
+    DarkTheme,
+    SystemTheme,
+    SaveConfig,
+}
+
+impl Message {
+    pub const ALL: [Message; 33] = [
+        Message::Code,
+        Message::Undo,
+        Message::ClearCodeField,
+        Message::OpenFile,
+        Message::ReloadFile,
+        Message::CloseFile,
+        Message::CopyCode,
+        Message::CopyResult,
+        Message::CopyErrors,
+        Message::NoErrors,
+        Message::ExportSession,
+        Message::AstViewer,
+        Message::OptimizationStages,
+        Message::CodeFieldIsEmpty,
+        Message::Settings,
+        Message::PrettyOutput,
+        Message::ImplicitMultiplication,
+        Message::NormalizeFloats,
+        Message::CaseInsensitiveNames,
+        Message::StageTimings,
+        Message::MaxSyntaxErrors,
+        Message::MaxParseDepth,
+        Message::MaxTreeHeight,
+        Message::MaxTreeNodes,
+        Message::TabWidth,
+        Message::NumberPrecision,
+        Message::FullPrecision,
+        Message::Language,
+        Message::Theme,
+        Message::LightTheme,
+        Message::DarkTheme,
+        Message::SystemTheme,
+        Message::SaveConfig,
+    ];
+
+    pub fn theme(theme: Theme) -> Message {
+        match theme {
+            Theme::Light => Message::LightTheme,
+            Theme::Dark => Message::DarkTheme,
+            Theme::System => Message::SystemTheme,
+        }
+    }
+}
+
+const ENGLISH: &[(Message, &str)] = &[
+    (Message::Code, "Code:"),
+    (Message::Undo, "Undo"),
+    (Message::ClearCodeField, "Clear Code Field"),
+    (Message::OpenFile, "Open File"),
+    (Message::ReloadFile, "Reload File"),
+    (Message::CloseFile, "Close File"),
+    (Message::CopyCode, "Copy Code"),
+    (Message::CopyResult, "Copy Result"),
+    (Message::CopyErrors, "Copy Errors"),
+    (Message::NoErrors, "No errors ✓"),
+    (Message::ExportSession, "Export Session"),
+    (Message::AstViewer, "AST Viewer"),
+    (Message::OptimizationStages, "Optimization Stages"),
+    (Message::CodeFieldIsEmpty, "Code field is empty."),
+    (Message::Settings, "Settings"),
+    (Message::PrettyOutput, "Pretty Output"),
+    (
+        Message::ImplicitMultiplication,
+        "Implicit Multiplication (2x = 2*x)",
+    ),
+    (Message::NormalizeFloats, "Normalize Floats (.5 = 0.5)"),
+    (
+        Message::CaseInsensitiveNames,
+        "Case-Insensitive Names (SIN = sin)",
+    ),
+    (Message::StageTimings, "Stage Timings"),
+    (
+        Message::MaxSyntaxErrors,
+        "Max Syntax Errors (0 = no limit):",
+    ),
+    (Message::MaxParseDepth, "Max Parse Depth:"),
+    (Message::MaxTreeHeight, "Max Tree Height (0 = no limit):"),
+    (Message::MaxTreeNodes, "Max Tree Nodes (0 = no limit):"),
+    (Message::TabWidth, "Tab Width:"),
+    (Message::NumberPrecision, "Number Precision:"),
+    (Message::FullPrecision, "Full"),
+    (Message::Language, "Language:"),
+    (Message::Theme, "Theme:"),
+    (Message::LightTheme, "Light"),
+    (Message::DarkTheme, "Dark"),
+    (Message::SystemTheme, "System"),
+    (Message::SaveConfig, "Save Config"),
+];
+
+const UKRAINIAN: &[(Message, &str)] = &[
+    (Message::Code, "Код:"),
+    (Message::Undo, "Скасувати"),
+    (Message::ClearCodeField, "Очистити поле коду"),
+    (Message::OpenFile, "Відкрити файл"),
+    (Message::ReloadFile, "Перезавантажити файл"),
+    (Message::CloseFile, "Закрити файл"),
+    (Message::CopyCode, "Копіювати код"),
+    (Message::CopyResult, "Копіювати результат"),
+    (Message::CopyErrors, "Копіювати помилки"),
+    (Message::NoErrors, "Помилок немає ✓"),
+    (Message::ExportSession, "Експортувати сесію"),
+    (Message::AstViewer, "Перегляд AST"),
+    (Message::OptimizationStages, "Етапи оптимізації"),
+    (Message::CodeFieldIsEmpty, "Поле коду порожнє."),
+    (Message::Settings, "Налаштування"),
+    (Message::PrettyOutput, "Гарний вивід"),
+    (
+        Message::ImplicitMultiplication,
+        "Неявне множення (2x = 2*x)",
+    ),
+    (Message::NormalizeFloats, "Нормалізувати дробові (.5 = 0.5)"),
+    (
+        Message::CaseInsensitiveNames,
+        "Імена без урахування регістру (SIN = sin)",
+    ),
+    (Message::StageTimings, "Час етапів"),
+    (
+        Message::MaxSyntaxErrors,
+        "Макс. синтаксичних помилок (0 = без обмежень):",
+    ),
+    (Message::MaxParseDepth, "Макс. глибина розбору:"),
+    (
+        Message::MaxTreeHeight,
+        "Макс. висота дерева (0 = без обмежень):",
+    ),
+    (
+        Message::MaxTreeNodes,
+        "Макс. вузлів дерева (0 = без обмежень):",
+    ),
+    (Message::TabWidth, "Ширина табуляції:"),
+    (Message::NumberPrecision, "Точність чисел:"),
+    (Message::FullPrecision, "Повна"),
+    (Message::Language, "Мова:"),
+    (Message::Theme, "Тема:"),
+    (Message::LightTheme, "Світла"),
+    (Message::DarkTheme, "Темна"),
+    (Message::SystemTheme, "Системна"),
+    (Message::SaveConfig, "Зберегти налаштування"),
+];
+
+pub fn translate(language: Language, message: Message) -> &'static str {
+    lookup(table(language), message)
+}
+
+fn table(language: Language) -> &'static [(Message, &'static str)] {
+    match language {
+        Language::English => ENGLISH,
+        Language::Ukrainian => UKRAINIAN,
+    }
+}
+
+// Missing keys fall back to English
+fn lookup(table: &[(Message, &'static str)], message: Message) -> &'static str {
+    [table, ENGLISH]
+        .iter()
+        .find_map(|table| {
+            table
+                .iter()
+                .find(|(key, _)| *key == message)
+                .map(|(_, text)| *text)
+        })
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_translate() {
+        assert_eq!(translate(Language::English, Message::Code), "Code:");
+        assert_eq!(translate(Language::Ukrainian, Message::Code), "Код:");
+        assert_eq!(
+            translate(Language::Ukrainian, Message::OpenFile),
+            "Відкрити файл"
+        );
+
+        // Every message has an English text
+        for message in Message::ALL {
+            assert!(!translate(Language::English, message).is_empty());
+        }
+        // And a Ukrainian one, not the fallback
+        assert_eq!(UKRAINIAN.len(), Message::ALL.len());
+    }
+
+    #[test]
+    fn test_translate_fallback() {
+        let partial = &[(Message::Code, "Код:")];
+        assert_eq!(lookup(partial, Message::Code), "Код:");
+        assert_eq!(lookup(partial, Message::OpenFile), "Open File");
+        assert_eq!(lookup(&[], Message::Undo), "Undo");
+    }
+}
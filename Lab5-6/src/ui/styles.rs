@@ -1,5 +1,101 @@
+use crate::preferences::Theme;
+
+impl Theme {
+    /// `system_theme` is used by `System`. When it is unknown, the theme is dark,
+    /// as the default one of egui.
+    pub fn visuals(&self, system_theme: Option<egui::Theme>) -> egui::Visuals {
+        let is_dark = match self {
+            Theme::Light => false,
+            Theme::Dark => true,
+            Theme::System => system_theme != Some(egui::Theme::Light),
+        };
+
+        match is_dark {
+            true => egui::Visuals::dark(),
+            false => egui::Visuals::light(),
+        }
+    }
+
+    pub fn apply(&self, ctx: &egui::Context) {
+        let visuals = self.visuals(ctx.system_theme());
+
+        // Otherwise egui switches the visuals along with the system theme
+        ctx.set_theme(match visuals.dark_mode {
+            true => egui::Theme::Dark,
+            false => egui::Theme::Light,
+        });
+        ctx.set_visuals(visuals);
+    }
+}
+
+/// Text colors, readable on the background of the active theme.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Palette {
+    pub green: egui::Color32,
+    pub red: egui::Color32,
+    pub blue: egui::Color32,
+    pub yellow: egui::Color32,
+    pub orange: egui::Color32,
+}
+
+impl Palette {
+    pub const DARK: Palette = Palette {
+        green: colors::GREEN,
+        red: colors::RED,
+        blue: colors::BLUE,
+        yellow: colors::YELLOW,
+        orange: colors::ORANGE,
+    };
+
+    pub const LIGHT: Palette = Palette {
+        green: colors::DARK_GREEN,
+        red: colors::DARK_RED,
+        blue: colors::BLUE,
+        yellow: colors::DARK_YELLOW,
+        orange: colors::DARK_ORANGE,
+    };
+
+    pub fn of(visuals: &egui::Visuals) -> &'static Palette {
+        match visuals.dark_mode {
+            true => &Palette::DARK,
+            false => &Palette::LIGHT,
+        }
+    }
+}
+
 pub mod colors {
     pub const GREEN: egui::Color32 = egui::Color32::from_rgb(0, 255, 0);
     pub const RED: egui::Color32 = egui::Color32::from_rgb(255, 0, 0);
     pub const BLUE: egui::Color32 = egui::Color32::from_rgb(0, 0, 255);
+    pub const YELLOW: egui::Color32 = egui::Color32::from_rgb(255, 255, 0);
+    pub const ORANGE: egui::Color32 = egui::Color32::from_rgb(255, 165, 0);
+
+    // For the light background
+    pub const DARK_GREEN: egui::Color32 = egui::Color32::from_rgb(0, 128, 0);
+    pub const DARK_RED: egui::Color32 = egui::Color32::from_rgb(192, 0, 0);
+    pub const DARK_YELLOW: egui::Color32 = egui::Color32::from_rgb(150, 120, 0);
+    pub const DARK_ORANGE: egui::Color32 = egui::Color32::from_rgb(200, 100, 0);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_theme_visuals() {
+        let dark = Theme::Dark.visuals(Some(egui::Theme::Light));
+        assert!(dark.dark_mode);
+        assert_eq!(dark.panel_fill, egui::Visuals::dark().panel_fill);
+
+        let light = Theme::Light.visuals(Some(egui::Theme::Dark));
+        assert!(!light.dark_mode);
+        assert_eq!(light.panel_fill, egui::Visuals::light().panel_fill);
+
+        // System follows the system theme, dark if unknown
+        assert!(!Theme::System.visuals(Some(egui::Theme::Light)).dark_mode);
+        assert!(Theme::System.visuals(None).dark_mode);
+
+        assert_eq!(Palette::of(&dark), &Palette::DARK);
+        assert_eq!(Palette::of(&light), &Palette::LIGHT);
+    }
 }
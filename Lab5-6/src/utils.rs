@@ -4,25 +4,20 @@ pub trait StringExtension {
 
 impl StringExtension for String {
     fn replace_char(&mut self, index: usize, ch: char) {
-        if index < self.len() {
-            let start = self
-                .char_indices()
-                .nth(index)
-                .map(|(i, _)| i)
-                .unwrap_or_else(|| panic!("Index ({}) out of bounds.", index));
-            let end = self
-                .char_indices()
-                .nth(index + 1)
-                .map(|(i, _)| i)
-                .unwrap_or_else(|| panic!("Index ({}) out of bounds.", index + 1));
+        // Index is counted in chars, not bytes
+        if let Some((start, old)) = self.char_indices().nth(index) {
+            let end = start + old.len_utf8();
             self.replace_range(start..end, &ch.to_string());
         }
     }
 }
 
+const INDENT: &str = "    ";
+
 #[derive(Default)]
 pub struct StringBuffer {
     buffer: String,
+    indent_level: usize,
 }
 
 impl StringBuffer {
@@ -35,7 +30,73 @@ impl StringBuffer {
         self.buffer.push('\n');
     }
 
+    pub fn add_indented(&mut self, line: String) {
+        self.buffer.push_str(&INDENT.repeat(self.indent_level));
+        self.add_line(line);
+    }
+
+    pub fn push_indent(&mut self) {
+        self.indent_level += 1;
+    }
+
+    pub fn pop_indent(&mut self) {
+        self.indent_level = self.indent_level.saturating_sub(1);
+    }
+
     pub fn get(self) -> String {
         self.buffer
     }
 }
+
+/// Quoted string with the escapes of the source, the reverse of `Lexer::unescape`:
+/// the text `a"b` is printed as `"a\"b"`.
+pub fn escape_string(text: &str) -> String {
+    let mut result = String::with_capacity(text.len() + 2);
+    result.push('"');
+    for ch in text.chars() {
+        match ch {
+            '"' => result.push_str("\\\""),
+            '\\' => result.push_str("\\\\"),
+            '\n' => result.push_str("\\n"),
+            '\t' => result.push_str("\\t"),
+            ch => result.push(ch),
+        }
+    }
+    result.push('"');
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_indentation() {
+        let mut buffer = StringBuffer::default();
+
+        buffer.add_indented("root".to_string());
+        buffer.push_indent();
+        buffer.add_indented("child".to_string());
+        buffer.push_indent();
+        buffer.add_indented("grandchild".to_string());
+        buffer.pop_indent();
+        buffer.add_indented("second child".to_string());
+        buffer.pop_indent();
+        // Popping below zero keeps the level at zero
+        buffer.pop_indent();
+        buffer.add_line("plain".to_string());
+        buffer.add_indented("root again".to_string());
+
+        assert_eq!(
+            buffer.get(),
+            "root\n    child\n        grandchild\n    second child\nplain\nroot again\n"
+        );
+    }
+
+    #[test]
+    fn test_escape_string() {
+        assert_eq!(escape_string("text"), "\"text\"");
+        assert_eq!(escape_string("a\", \"b"), "\"a\\\", \\\"b\"");
+        assert_eq!(escape_string("\\n\n\t"), "\"\\\\n\\n\\t\"");
+    }
+}